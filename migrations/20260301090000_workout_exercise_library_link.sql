@@ -0,0 +1,12 @@
+-- Link workout exercises back to the exercise library (nullable, free-text name is kept)
+ALTER TABLE workout_exercises
+    ADD COLUMN exercise_library_id INTEGER REFERENCES exercise_library(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_workout_exercises_exercise_library_id
+    ON workout_exercises (exercise_library_id);
+
+-- One-off data fixes that run in the app, recorded so they run once per database
+CREATE TABLE IF NOT EXISTS data_backfills (
+    name TEXT PRIMARY KEY NOT NULL,
+    ran_at TEXT NOT NULL
+);
@@ -0,0 +1,9 @@
+-- In-progress workout sessions, saved as they are edited so they survive crashes
+CREATE TABLE IF NOT EXISTS workout_session_drafts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    workout_id INTEGER NOT NULL,
+    session TEXT NOT NULL,            -- Serialized session state (JSON)
+    FOREIGN KEY (workout_id) REFERENCES workouts(id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+-- Start the rest timer when a set is checked off during a session
+ALTER TABLE workouts ADD COLUMN auto_start_rest_timer BOOLEAN NOT NULL DEFAULT 1;
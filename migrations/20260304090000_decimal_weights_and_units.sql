@@ -0,0 +1,20 @@
+-- Weights become decimals (1.25 kg / 2.5 lb plates) stored with the unit they were entered in
+
+ALTER TABLE workout_exercises ADD COLUMN working_weight_decimal REAL NOT NULL DEFAULT 0;
+UPDATE workout_exercises SET working_weight_decimal = CAST(working_weight AS REAL);
+ALTER TABLE workout_exercises DROP COLUMN working_weight;
+ALTER TABLE workout_exercises RENAME COLUMN working_weight_decimal TO working_weight;
+ALTER TABLE workout_exercises ADD COLUMN weight_unit TEXT NOT NULL DEFAULT 'Kg'; -- Enum: Kg, Lb
+
+ALTER TABLE workout_logs ADD COLUMN weight_decimal REAL NOT NULL DEFAULT 0;
+UPDATE workout_logs SET weight_decimal = CAST(weight AS REAL);
+ALTER TABLE workout_logs DROP COLUMN weight;
+ALTER TABLE workout_logs RENAME COLUMN weight_decimal TO weight;
+ALTER TABLE workout_logs ADD COLUMN weight_unit TEXT NOT NULL DEFAULT 'Kg'; -- Enum: Kg, Lb
+
+-- User level settings, a single row
+CREATE TABLE IF NOT EXISTS app_settings (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    weight_unit TEXT NOT NULL DEFAULT 'Kg' -- Display unit, Enum: Kg, Lb
+);
+INSERT OR IGNORE INTO app_settings (id) VALUES (1);
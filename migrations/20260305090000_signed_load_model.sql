@@ -0,0 +1,10 @@
+-- Calisthenics loads: weight is signed (negative = assistance), an assistance band per set,
+-- and the fraction of bodyweight moved (e.g. 1.0 for a pull-up, 0.0 for a barbell lift)
+
+ALTER TABLE workout_exercises ADD COLUMN assistance_band TEXT; -- Enum: Band (Nullable)
+ALTER TABLE workout_exercises ADD COLUMN bodyweight_fraction REAL NOT NULL DEFAULT 0;
+
+ALTER TABLE workout_logs ADD COLUMN assistance_band TEXT; -- Enum: Band (Nullable)
+ALTER TABLE workout_logs ADD COLUMN bodyweight_fraction REAL NOT NULL DEFAULT 0;
+
+ALTER TABLE app_settings ADD COLUMN bodyweight_kg REAL; -- Nullable until the user sets it
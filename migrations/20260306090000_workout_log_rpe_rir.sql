@@ -0,0 +1,3 @@
+-- Structured effort per set, 1-10 in half steps (NULL when not recorded)
+ALTER TABLE workout_logs ADD COLUMN rpe REAL;
+ALTER TABLE workout_logs ADD COLUMN rir REAL;
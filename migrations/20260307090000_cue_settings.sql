@@ -0,0 +1,3 @@
+-- sound per timer cue, stored as a JSON map of overrides; cues not in the map use their default tone
+ALTER TABLE app_settings ADD COLUMN cue_sounds TEXT NOT NULL DEFAULT '{}';
+ALTER TABLE app_settings ADD COLUMN countdown_beeps BOOLEAN NOT NULL DEFAULT 1;
@@ -0,0 +1,6 @@
+-- preferences that used to be hard-coded in the client
+ALTER TABLE app_settings ADD COLUMN volume REAL NOT NULL DEFAULT 50; -- percent, 0 to 100
+ALTER TABLE app_settings ADD COLUMN theme TEXT NOT NULL DEFAULT 'Dark'; -- Enum: Dark, Light
+ALTER TABLE app_settings ADD COLUMN text_scale REAL NOT NULL DEFAULT 1.0;
+ALTER TABLE app_settings ADD COLUMN default_rest_seconds INTEGER NOT NULL DEFAULT 60; -- rest for new workout exercises
+ALTER TABLE app_settings ADD COLUMN pagination_limit INTEGER NOT NULL DEFAULT 50;
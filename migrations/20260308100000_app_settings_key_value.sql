@@ -0,0 +1,29 @@
+-- settings become key/value pairs so a new setting doesn't need its own column.
+-- values are JSON, settings without a row use their default
+CREATE TABLE IF NOT EXISTS app_settings_kv (
+    key TEXT PRIMARY KEY NOT NULL,
+    value TEXT NOT NULL
+);
+
+INSERT INTO app_settings_kv (key, value)
+SELECT 'weight_unit', json_quote(weight_unit) FROM app_settings WHERE id = 1
+UNION ALL
+SELECT 'bodyweight_kg', json_quote(bodyweight_kg) FROM app_settings WHERE id = 1
+UNION ALL
+SELECT 'cue_sounds', cue_sounds FROM app_settings WHERE id = 1
+UNION ALL
+SELECT 'countdown_beeps', CASE WHEN countdown_beeps THEN 'true' ELSE 'false' END
+FROM app_settings WHERE id = 1
+UNION ALL
+SELECT 'volume', json_quote(volume) FROM app_settings WHERE id = 1
+UNION ALL
+SELECT 'theme', json_quote(theme) FROM app_settings WHERE id = 1
+UNION ALL
+SELECT 'text_scale', json_quote(text_scale) FROM app_settings WHERE id = 1
+UNION ALL
+SELECT 'default_rest_seconds', json_quote(default_rest_seconds) FROM app_settings WHERE id = 1
+UNION ALL
+SELECT 'pagination_limit', json_quote(pagination_limit) FROM app_settings WHERE id = 1;
+
+DROP TABLE app_settings;
+ALTER TABLE app_settings_kv RENAME TO app_settings;
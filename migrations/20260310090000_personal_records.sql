@@ -0,0 +1,17 @@
+-- records set by a logged set, checked against that exercise's earlier sessions when a session is saved
+CREATE TABLE IF NOT EXISTS personal_records (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_at TEXT NOT NULL,
+    workout_log_id INTEGER NOT NULL,
+    exercise_library_id INTEGER,      -- copied from the workout exercise when it was linked
+    exercise_name TEXT NOT NULL,
+    record_type TEXT NOT NULL,        -- Enum: HeaviestWeight, MostReps, LongestHold, Estimated1rm
+    value REAL NOT NULL,              -- kg, reps or seconds depending on the record type
+    load_kg REAL,                     -- the load the reps were done at, MostReps only
+    previous_value REAL NOT NULL,     -- the best before this record
+    FOREIGN KEY (workout_log_id) REFERENCES workout_logs(id) ON DELETE CASCADE,
+    FOREIGN KEY (exercise_library_id) REFERENCES exercise_library(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_personal_records_workout_log_id
+    ON personal_records (workout_log_id);
@@ -0,0 +1,4 @@
+-- when a session ran and when each set was checked off, NULL for sessions logged before this
+ALTER TABLE workout_log_groups ADD COLUMN started_at TEXT;
+ALTER TABLE workout_log_groups ADD COLUMN finished_at TEXT;
+ALTER TABLE workout_logs ADD COLUMN completed_at TEXT;
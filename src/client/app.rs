@@ -1,15 +1,31 @@
+use crate::client::home_page::HomePage;
+use crate::db::backup::BackupService;
+use crate::enums::Theme;
 use crate::exercise::exercises_page::ExercisesPage;
+use crate::personal_record::personal_records_page::PersonalRecordsPage;
+use crate::settings::settings_dto::{SettingsRes, VOLUME_RANGE, settings_to_req};
+use crate::settings::settings_page::{SettingsChange, SettingsPage};
+use crate::settings::settings_service::SettingsService;
+use crate::stats::stats_page::StatsPage;
+use crate::tempo::Tempo;
 use crate::timer::Timer;
+use crate::timer::audio_engine::{AudioEngine, AudioStatus};
 use crate::timer::countdown_timer::CountDownTimer;
+use crate::timer::cue::Cue;
 use crate::timer::emom_timer::EMOMTimer;
 use crate::timer::metronome::Metronome;
 use crate::workout::start_workout_page::StartWorkoutPage;
 use crate::workout::workouts_page::WorkoutsPage;
+use crate::workout_log::exercise_history_page::ExerciseHistoryPage;
+use crate::workout_log::workout_log_dto::ExerciseHistoryTarget;
 use crate::workout_log::workout_logs_page::WorkoutLogsPage;
 use eframe::egui;
 use egui::FontId;
 use egui::TextStyle;
 use sqlx::{Pool, Sqlite};
+use std::time::{Duration, Instant};
+
+const CUE_FLASH: Duration = Duration::from_millis(200);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MainPageState {
@@ -18,37 +34,131 @@ pub enum MainPageState {
     Workouts,
     StartWorkout,
     WorkoutLogs,
+    ExerciseHistory, // opened from other pages, not in the header
+    Stats,
+    PersonalRecords,
+    Settings,
 }
 
 pub enum PageAction {
     None,
     GoToStartWorkout(u32),
     GoToWorkoutDetails(u32),
+    GoToWorkoutLog(u32),
+    GoToActiveWorkout,
+    GoToExerciseHistory(ExerciseHistoryTarget),
+    CloseExerciseHistory,
+    StartRestTimer(u16),
+    StartEmomTimer(EmomSetup),
+    StartTempoMetronome(Tempo),
+}
+
+// an EMOM run configured from a workout exercise
+pub struct EmomSetup {
+    pub workout_exercise_id: u32,
+    pub rounds: u32,
+    pub work_seconds: u32,
+    pub rest_seconds: u32,
 }
 
 pub struct WorkoutUtil {
     current_page: MainPageState,
+    home_page: HomePage,
     exercises_page: ExercisesPage,
     workouts_page: WorkoutsPage,
     start_workout_page: StartWorkoutPage,
     workout_logs_page: WorkoutLogsPage,
+    exercise_history_page: ExerciseHistoryPage,
+    // page the exercise history was opened from
+    history_return_page: MainPageState,
+    stats_page: StatsPage,
+    personal_records_page: PersonalRecordsPage,
+    settings_page: SettingsPage,
     metronome: Metronome,
     rest_timer: CountDownTimer,
     emom_timer: EMOMTimer,
+    audio: AudioEngine,
+    // workout exercise whose sets are completed by the EMOM rounds
+    emom_workout_exercise_id: Option<u32>,
+    settings: SettingsRes,
+    settings_service: SettingsService,
 }
 
 impl WorkoutUtil {
-    pub fn new(_cc: &eframe::CreationContext<'_>, pool: Pool<Sqlite>) -> Self {
-        Self {
+    pub fn new(
+        cc: &eframe::CreationContext<'_>,
+        pool: Pool<Sqlite>,
+        settings: SettingsRes,
+        backup_service: BackupService,
+    ) -> Self {
+        let mut start_workout_page = StartWorkoutPage::default(pool.clone());
+        start_workout_page.check_for_drafts(&cc.egui_ctx);
+
+        let audio = AudioEngine::new();
+        let mut app = Self {
             current_page: MainPageState::Home,
+            home_page: HomePage::default(pool.clone()),
             exercises_page: ExercisesPage::default(pool.clone()),
             workouts_page: WorkoutsPage::default(pool.clone()),
-            start_workout_page: StartWorkoutPage::default(pool.clone()),
+            start_workout_page,
             workout_logs_page: WorkoutLogsPage::default(pool.clone()),
-            metronome: Metronome::new(),
-            rest_timer: CountDownTimer::new(),
-            emom_timer: EMOMTimer::new(),
+            exercise_history_page: ExerciseHistoryPage::default(pool.clone()),
+            history_return_page: MainPageState::Home,
+            stats_page: StatsPage::default(pool.clone()),
+            personal_records_page: PersonalRecordsPage::default(pool.clone()),
+            settings_page: SettingsPage::new(backup_service),
+            metronome: Metronome::new(&audio),
+            rest_timer: CountDownTimer::new(&audio, Cue::RestFinished),
+            emom_timer: EMOMTimer::new(&audio),
+            audio,
+            emom_workout_exercise_id: None,
+            settings,
+            settings_service: SettingsService::new(pool.clone()),
+        };
+        app.apply_settings(&cc.egui_ctx);
+        app
+    }
+
+    fn apply_settings(&mut self, ctx: &egui::Context) {
+        apply_style(ctx, &self.settings);
+        self.exercises_page.apply_settings(&self.settings);
+        self.workouts_page.apply_settings(&self.settings);
+        self.start_workout_page.apply_settings(&self.settings);
+        self.workout_logs_page.apply_settings(&self.settings);
+        self.exercise_history_page.apply_settings(&self.settings);
+        self.stats_page.apply_settings(&self.settings);
+        self.personal_records_page.apply_settings(&self.settings);
+        self.audio.set_sounds(self.settings.cue_sounds.clone());
+        for timer in [
+            &mut self.rest_timer,
+            &mut self.emom_timer.work_timer,
+            &mut self.emom_timer.rest_timer,
+        ] {
+            timer.countdown_beeps = self.settings.countdown_beeps;
+            timer.volume = self.settings.volume;
         }
+        self.metronome.set_volume(self.settings.volume);
+    }
+
+    fn handle_settings_change(&mut self, ctx: &egui::Context, change: SettingsChange) {
+        match change {
+            SettingsChange::None => {}
+            SettingsChange::Preview => self.apply_settings(ctx),
+            SettingsChange::Save => {
+                self.apply_settings(ctx);
+                self.save_settings();
+            }
+        }
+    }
+
+    fn save_settings(&self) {
+        let service = self.settings_service.clone();
+        let req = settings_to_req(&self.settings);
+        tokio::spawn(async move {
+            if let Err(error) = service.update_settings(req).await {
+                println!("Failed to save settings: {}", error);
+            }
+        });
     }
 
     fn header(&mut self, ui: &mut egui::Ui) {
@@ -63,6 +173,9 @@ impl WorkoutUtil {
                 (MainPageState::Workouts, "Workouts"),
                 (MainPageState::StartWorkout, "Start Workout"),
                 (MainPageState::WorkoutLogs, "Workout Logs"),
+                (MainPageState::Stats, "Stats"),
+                (MainPageState::PersonalRecords, "PRs"),
+                (MainPageState::Settings, "Settings"),
             ] {
                 let is_active = self.current_page == page;
 
@@ -79,6 +192,11 @@ impl WorkoutUtil {
                     });
 
                 if ui.add(button).clicked() {
+                    match page {
+                        MainPageState::Home => self.home_page.refresh(),
+                        MainPageState::PersonalRecords => self.personal_records_page.refresh(),
+                        _ => {}
+                    }
                     self.current_page = page;
                 }
             }
@@ -87,22 +205,27 @@ impl WorkoutUtil {
 
     fn render_page(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
         let action = match self.current_page {
-            MainPageState::Home => {
-                self.render_home(ui);
-                PageAction::None
-            }
-            MainPageState::Exercises => {
-                self.exercises_page.render_page(ctx, ui);
-                PageAction::None
-            }
+            MainPageState::Home => self.home_page.render_page(ctx, ui),
+            MainPageState::Exercises => self.exercises_page.render_page(ctx, ui),
             MainPageState::Workouts => self.workouts_page.render_page(ctx, ui),
             MainPageState::StartWorkout => self.start_workout_page.render_page(ctx, ui),
-            MainPageState::WorkoutLogs => {
-                self.workout_logs_page.render_page(ctx, ui);
+            MainPageState::WorkoutLogs => self.workout_logs_page.render_page(ctx, ui),
+            MainPageState::ExerciseHistory => self.exercise_history_page.render_page(ctx, ui),
+            MainPageState::Stats => self.stats_page.render_page(ctx, ui),
+            MainPageState::PersonalRecords => self.personal_records_page.render_page(ctx, ui),
+            MainPageState::Settings => {
+                let change =
+                    self.settings_page
+                        .render_page(ctx, ui, &mut self.settings, &self.audio);
+                self.handle_settings_change(ctx, change);
                 PageAction::None
             }
         };
 
+        self.handle_page_action(ctx, action);
+    }
+
+    fn handle_page_action(&mut self, ctx: &egui::Context, action: PageAction) {
         match action {
             PageAction::GoToStartWorkout(workout_id) => {
                 self.start_workout_page.load_workout(ctx, workout_id);
@@ -112,27 +235,53 @@ impl WorkoutUtil {
                 self.workouts_page.open_details_view(workout_id);
                 self.current_page = MainPageState::Workouts;
             }
+            PageAction::GoToWorkoutLog(workout_log_group_id) => {
+                self.workout_logs_page
+                    .open_details_view(ctx, workout_log_group_id);
+                self.current_page = MainPageState::WorkoutLogs;
+            }
+            PageAction::GoToActiveWorkout => {
+                self.current_page = MainPageState::StartWorkout;
+            }
+            PageAction::GoToExerciseHistory(target) => {
+                self.exercise_history_page.open(target);
+                self.history_return_page = self.current_page;
+                self.current_page = MainPageState::ExerciseHistory;
+            }
+            PageAction::CloseExerciseHistory => {
+                self.current_page = self.history_return_page;
+            }
+            PageAction::StartRestTimer(seconds) => {
+                self.rest_timer.start_with_seconds(seconds as u32);
+            }
+            PageAction::StartEmomTimer(setup) => {
+                self.emom_timer
+                    .configure(setup.rounds, setup.work_seconds, setup.rest_seconds);
+                self.emom_timer.toggle();
+                self.emom_workout_exercise_id = Some(setup.workout_exercise_id);
+            }
+            PageAction::StartTempoMetronome(tempo) => {
+                self.metronome.set_tempo(Some(tempo));
+                self.metronome.toggle();
+            }
             PageAction::None => {}
         }
     }
 
-    fn render_home(&mut self, ui: &mut egui::Ui) {
-        ui.heading("Home");
-        ui.label("Welcome to Workout Util!");
-        ui.label("Select an option from the menu above.");
-    }
-
     fn footer(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
         self.metronome.tick();
         self.rest_timer.tick();
         self.emom_timer.tick();
+        self.sync_emom_rounds();
 
         if self.metronome.is_running || self.rest_timer.is_running || self.emom_timer.is_running {
             ctx.request_repaint();
         }
 
         ui.horizontal(|ui| {
-            self.render_volume_slider(ui);
+            self.render_audio_status(ui);
+            ui.separator();
+            self.render_volume_slider(ctx, ui);
             ui.separator();
             self.render_rest_timer(ui);
             ui.separator();
@@ -142,43 +291,88 @@ impl WorkoutUtil {
         });
     }
 
-    fn render_volume_slider(&mut self, ui: &mut egui::Ui) {
-        let mut volume: f32 = 10.0;
-        ui.add(egui::Slider::new(&mut volume, 1.0..=50.0).text("Volume"));
-        self.metronome.volume = volume;
-        self.rest_timer.volume = volume;
-        self.emom_timer.work_timer.volume = volume;
-        self.emom_timer.rest_timer.volume = volume;
+    fn sync_emom_rounds(&mut self) {
+        let finished_rounds = self.emom_timer.take_finished_rounds();
+        if let Some(workout_exercise_id) = self.emom_workout_exercise_id {
+            for round in finished_rounds {
+                self.start_workout_page
+                    .complete_set(workout_exercise_id, round as u8);
+            }
+        }
+        if !self.emom_timer.is_running && self.emom_timer.current_round == 0 {
+            self.emom_workout_exercise_id = None;
+        }
+    }
+
+    // lights up on every cue, which is the only signal when there is no audio device
+    fn render_audio_status(&mut self, ui: &mut egui::Ui) {
+        let status = self.audio.status();
+        let flashing = self
+            .audio
+            .last_cue_at()
+            .and_then(|at| Instant::now().checked_duration_since(at))
+            .is_some_and(|since| since < CUE_FLASH);
+        let color = match (&status, flashing) {
+            (_, true) => egui::Color32::YELLOW,
+            (AudioStatus::Silent(_), false) => egui::Color32::DARK_RED,
+            (_, false) => egui::Color32::DARK_GREEN,
+        };
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::hover());
+        ui.painter().circle_filled(rect.center(), 5.0, color);
+        let label = ui.label(status.to_string());
+        if let AudioStatus::Silent(reason) = &status {
+            label.on_hover_text(format!("No audio output device: {}", reason));
+        }
+    }
+
+    fn render_volume_slider(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        let response = ui.add(
+            egui::Slider::new(&mut self.settings.volume, VOLUME_RANGE)
+                .text("Volume")
+                .suffix("%")
+                .integer(),
+        );
+        self.handle_settings_change(ctx, SettingsChange::from_response(&response));
     }
 
     fn render_emom_timer(&mut self, ui: &mut egui::Ui) {
         ui.label("EMOM Timer");
         ui.label("Rounds:");
-        ui.add(egui::DragValue::new(&mut self.emom_timer.rounds).range(1..=100));
+        let mut reconfigured = ui
+            .add(egui::DragValue::new(&mut self.emom_timer.rounds).range(1..=100))
+            .changed();
 
         ui.label("Work:");
-        ui.add(
-            egui::DragValue::new(&mut self.emom_timer.work_timer.input_minutes)
-                .suffix("m")
-                .range(0..=60),
-        );
-        ui.add(
-            egui::DragValue::new(&mut self.emom_timer.work_timer.input_seconds)
-                .suffix("s")
-                .range(0..=59),
-        );
+        reconfigured |= ui
+            .add(
+                egui::DragValue::new(&mut self.emom_timer.work_timer.input_minutes)
+                    .suffix("m")
+                    .range(0..=60),
+            )
+            .changed();
+        reconfigured |= ui
+            .add(
+                egui::DragValue::new(&mut self.emom_timer.work_timer.input_seconds)
+                    .suffix("s")
+                    .range(0..=59),
+            )
+            .changed();
 
         ui.label("Rest:");
-        ui.add(
-            egui::DragValue::new(&mut self.emom_timer.rest_timer.input_minutes)
-                .suffix("m")
-                .range(0..=60),
-        );
-        ui.add(
-            egui::DragValue::new(&mut self.emom_timer.rest_timer.input_seconds)
-                .suffix("s")
-                .range(0..=59),
-        );
+        reconfigured |= ui
+            .add(
+                egui::DragValue::new(&mut self.emom_timer.rest_timer.input_minutes)
+                    .suffix("m")
+                    .range(0..=60),
+            )
+            .changed();
+        reconfigured |= ui
+            .add(
+                egui::DragValue::new(&mut self.emom_timer.rest_timer.input_seconds)
+                    .suffix("s")
+                    .range(0..=59),
+            )
+            .changed();
 
         ui.separator();
 
@@ -209,12 +403,33 @@ impl WorkoutUtil {
             })
             .clicked()
         {
+            // a manual stop ends the link to the workout exercise
+            if self.emom_timer.is_running {
+                self.emom_workout_exercise_id = None;
+            }
             self.emom_timer.toggle();
         }
+        // so does changing the setup by hand
+        if reconfigured {
+            self.emom_workout_exercise_id = None;
+        }
     }
 
     fn render_metronome(&mut self, ui: &mut egui::Ui) {
         ui.label("Metronome");
+        if let Some(tempo) = self.metronome.tempo {
+            let phase = match self.metronome.current_phase() {
+                Some(phase) if self.metronome.is_running => format!(" - {}", phase),
+                _ => String::new(),
+            };
+            ui.label(format!(
+                "Tempo {}{} (Rep {})",
+                tempo, phase, self.metronome.reps
+            ));
+            if ui.button("Clear Tempo").clicked() {
+                self.metronome.set_tempo(None);
+            }
+        }
         if ui
             .button(if self.metronome.is_running {
                 "Stop"
@@ -258,23 +473,6 @@ impl WorkoutUtil {
 
 impl eframe::App for WorkoutUtil {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        ctx.set_visuals(egui::Visuals::dark());
-
-        let mut style = (*ctx.style()).clone();
-
-        style.text_styles = [
-            (TextStyle::Heading, FontId::proportional(28.0)),
-            (TextStyle::Body, FontId::proportional(18.0)),
-            (TextStyle::Monospace, FontId::monospace(16.0)),
-            (TextStyle::Button, FontId::proportional(18.0)),
-            (TextStyle::Small, FontId::proportional(14.0)),
-        ]
-        .into();
-        style.spacing.item_spacing = egui::vec2(10.0, 8.0);
-        style.spacing.button_padding = egui::vec2(8.0, 4.0);
-
-        ctx.set_style(style);
-
         egui::TopBottomPanel::top("top_panel")
             .default_height(60.0)
             .show(ctx, |ui| {
@@ -290,5 +488,35 @@ impl eframe::App for WorkoutUtil {
         egui::CentralPanel::default().show(ctx, |ui| {
             self.render_page(ctx, ui);
         });
+
+        let action = self.start_workout_page.render_draft_prompt(ctx);
+        self.handle_page_action(ctx, action);
     }
+
+    // autosave waits between writes, the last changes would be lost without this
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.start_workout_page.save_draft_on_exit();
+    }
+}
+
+// only called when the settings change, egui keeps the style between frames
+fn apply_style(ctx: &egui::Context, settings: &SettingsRes) {
+    ctx.set_theme(match settings.theme {
+        Theme::Dark => egui::Theme::Dark,
+        Theme::Light => egui::Theme::Light,
+    });
+
+    let scale = settings.text_scale;
+    ctx.all_styles_mut(|style| {
+        style.text_styles = [
+            (TextStyle::Heading, FontId::proportional(28.0 * scale)),
+            (TextStyle::Body, FontId::proportional(18.0 * scale)),
+            (TextStyle::Monospace, FontId::monospace(16.0 * scale)),
+            (TextStyle::Button, FontId::proportional(18.0 * scale)),
+            (TextStyle::Small, FontId::proportional(14.0 * scale)),
+        ]
+        .into();
+        style.spacing.item_spacing = egui::vec2(10.0, 8.0);
+        style.spacing.button_padding = egui::vec2(8.0, 4.0);
+    });
 }
@@ -1,7 +1,9 @@
 use crate::db::pagination_support::PaginationState;
+use crate::error::AppError;
 use eframe::egui;
 use std::time::{Duration, Instant};
 
+#[derive(Default)]
 pub struct CommonUiState {
     pub error_message: Option<(String, Instant)>,
     pub success_message: Option<(String, Instant)>,
@@ -9,17 +11,6 @@ pub struct CommonUiState {
     pub initialized: bool,
 }
 
-impl Default for CommonUiState {
-    fn default() -> Self {
-        Self {
-            error_message: None,
-            success_message: None,
-            loading: false,
-            initialized: false,
-        }
-    }
-}
-
 impl CommonUiState {
     pub fn set_as_loading(&mut self) {
         self.loading = true;
@@ -49,6 +40,10 @@ impl CommonUiState {
         self.error_message = Some((msg.to_string(), Instant::now()));
     }
 
+    pub fn show_app_error(&mut self, error: &AppError) {
+        self.error_message = Some((error.to_string(), Instant::now()));
+    }
+
     pub fn show_success(&mut self, msg: &str) {
         self.success_message = Some((msg.to_string(), Instant::now()));
     }
@@ -91,6 +86,22 @@ pub fn combo_opt<T: std::fmt::Debug + PartialEq + Copy>(
         });
 }
 
+// rpe/rir picker, only offers valid values (1-10 in half steps), returns true when changed
+pub fn effort_combo(ui: &mut egui::Ui, salt: &str, current: &mut Option<f64>) -> bool {
+    let original = *current;
+    egui::ComboBox::from_id_salt(salt)
+        .width(60.0)
+        .selected_text(current.map(|v| v.to_string()).unwrap_or("-".to_string()))
+        .show_ui(ui, |ui| {
+            ui.selectable_value(current, None, "-");
+            for half_steps in 2..=20 {
+                let value = half_steps as f64 / 2.0;
+                ui.selectable_value(current, Some(value), value.to_string());
+            }
+        });
+    *current != original
+}
+
 pub fn filter_combo<T: Copy + PartialEq + std::fmt::Debug + 'static>(
     ui: &mut egui::Ui,
     label: &str,
@@ -137,18 +148,14 @@ pub fn render_pagination(ui: &mut egui::Ui, pagination_state: &mut PaginationSta
             refresh_needed = true;
         }
 
-        if pagination_state.has_previous() {
-            if ui.button("Previous").clicked() {
-                pagination_state.go_backwards();
-                refresh_needed = true;
-            }
+        if pagination_state.has_previous() && ui.button("Previous").clicked() {
+            pagination_state.go_backwards();
+            refresh_needed = true;
         }
 
-        if pagination_state.has_next() {
-            if ui.button("Next").clicked() {
-                pagination_state.go_forwards();
-                refresh_needed = true;
-            }
+        if pagination_state.has_next() && ui.button("Next").clicked() {
+            pagination_state.go_forwards();
+            refresh_needed = true;
         }
     });
 
@@ -0,0 +1,184 @@
+use crate::client::app::PageAction;
+use crate::client::app_utils::CommonUiState;
+use crate::error::{AppError, AppResult};
+use crate::workout::workout_dto::WorkoutRes;
+use crate::workout::workout_service::WorkoutService;
+use crate::workout_log::workout_log_dto::RecentSessionRes;
+use crate::workout_log::workout_log_service::WorkoutLogService;
+use eframe::egui;
+use sqlx::{Pool, Sqlite};
+use std::sync::mpsc::{Receiver, Sender, channel};
+
+const RECENT_SESSIONS: u32 = 3;
+
+pub struct HomeDashboard {
+    pub next_workout: Option<WorkoutRes>,
+    pub recent_sessions: Vec<RecentSessionRes>,
+    pub sessions_this_week: u32,
+}
+
+pub struct HomePage {
+    workout_service: WorkoutService,
+    workout_log_service: WorkoutLogService,
+    dashboard: Option<HomeDashboard>,
+    // Async Communication
+    receiver: Receiver<HomePageMsg>,
+    sender: Sender<HomePageMsg>,
+    // UI Status
+    common_ui_state: CommonUiState,
+}
+
+pub enum HomePageMsg {
+    Loaded(HomeDashboard),
+    Error(AppError),
+}
+
+impl HomePage {
+    pub fn default(pool: Pool<Sqlite>) -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            workout_service: WorkoutService::new(pool.clone()),
+            workout_log_service: WorkoutLogService::new(pool),
+            dashboard: None,
+            receiver,
+            sender,
+            common_ui_state: CommonUiState::default(),
+        }
+    }
+}
+
+impl HomePage {
+    // sessions may have been logged since the dashboard was loaded
+    pub fn refresh(&mut self) {
+        self.common_ui_state.not_initialized();
+    }
+
+    fn handle_async_messages(&mut self) {
+        while let Ok(msg) = self.receiver.try_recv() {
+            self.common_ui_state.set_as_not_loading();
+            match msg {
+                HomePageMsg::Loaded(dashboard) => {
+                    self.dashboard = Some(dashboard);
+                }
+                HomePageMsg::Error(err) => {
+                    self.common_ui_state.show_app_error(&err);
+                }
+            }
+        }
+    }
+
+    fn fetch_dashboard(&mut self, ctx: &egui::Context) {
+        if self.common_ui_state.initialized || self.common_ui_state.loading {
+            return;
+        }
+        self.common_ui_state.set_as_loading();
+        self.common_ui_state.initialize();
+
+        let workout_service = self.workout_service.clone();
+        let workout_log_service = self.workout_log_service.clone();
+        let sender = self.sender.clone();
+        let ctx = ctx.clone();
+
+        tokio::spawn(async move {
+            let load = async {
+                AppResult::Ok(HomeDashboard {
+                    next_workout: workout_service.get_next_workout().await?,
+                    recent_sessions: workout_log_service
+                        .get_recent_sessions(RECENT_SESSIONS)
+                        .await?,
+                    sessions_this_week: workout_log_service
+                        .count_sessions_in_week(chrono::Local::now().date_naive())
+                        .await?,
+                })
+            };
+            match load.await {
+                Ok(dashboard) => {
+                    let _ = sender.send(HomePageMsg::Loaded(dashboard));
+                }
+                Err(e) => {
+                    let _ = sender.send(HomePageMsg::Error(e));
+                }
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    fn render_start_button(ui: &mut egui::Ui, dashboard: &HomeDashboard) -> PageAction {
+        let Some(workout) = &dashboard.next_workout else {
+            ui.label("No active workouts yet, create one on the Workouts page.");
+            return PageAction::None;
+        };
+
+        let button = egui::Button::new(
+            egui::RichText::new(format!("Start Next Workout: {}", workout.name))
+                .size(24.0)
+                .color(egui::Color32::WHITE),
+        )
+        .fill(egui::Color32::from_rgb(40, 140, 60))
+        .min_size(egui::vec2(320.0, 60.0));
+
+        if ui.add(button).clicked() {
+            return PageAction::GoToStartWorkout(workout.id);
+        }
+        PageAction::None
+    }
+
+    fn render_recent_sessions(ui: &mut egui::Ui, dashboard: &HomeDashboard) -> PageAction {
+        let mut page_action = PageAction::None;
+
+        ui.heading("Recent Activity");
+        if dashboard.recent_sessions.is_empty() {
+            ui.label("No workouts logged yet.");
+            return page_action;
+        }
+
+        egui::Grid::new("home_recent_sessions_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                for session in &dashboard.recent_sessions {
+                    ui.label(session.date.to_string());
+                    ui.label(session.workout_names.as_deref().unwrap_or("-"));
+                    ui.label(format!("{} sets", session.set_count));
+                    ui.label(session.notes.as_deref().unwrap_or(""));
+                    if ui.button("View").clicked() {
+                        page_action = PageAction::GoToWorkoutLog(session.id);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        page_action
+    }
+
+    pub fn render_page(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) -> PageAction {
+        self.handle_async_messages();
+        self.fetch_dashboard(ctx);
+
+        ui.heading("Home");
+        ui.separator();
+        self.common_ui_state.show_toasts(ui);
+
+        let Some(dashboard) = &self.dashboard else {
+            ui.spinner();
+            return PageAction::None;
+        };
+
+        ui.add_space(10.0);
+        let start_action = Self::render_start_button(ui, dashboard);
+
+        ui.add_space(20.0);
+        ui.label(
+            egui::RichText::new(format!(
+                "Workouts this week: {}",
+                dashboard.sessions_this_week
+            ))
+            .strong(),
+        );
+
+        ui.add_space(20.0);
+        match Self::render_recent_sessions(ui, dashboard) {
+            PageAction::None => start_action,
+            recent_action => recent_action,
+        }
+    }
+}
@@ -1,13 +1,62 @@
 use crate::client::app::WorkoutUtil;
-use crate::db::{DEFAULT_DB_URL, init_db};
+use crate::db::backup::BackupService;
+use crate::db::db_location::{DB_PATH_FLAG, resolve_db_location};
+use crate::db::init_db_file;
+use crate::settings::settings_service::SettingsService;
+use crate::workout::workout_service::WorkoutService;
 use eframe::egui;
 use std::error::Error;
 
 pub mod app;
 pub mod app_utils;
+pub mod home_page;
 
 pub async fn run_app() -> Result<(), Box<dyn Error>> {
-    let pool = init_db(DEFAULT_DB_URL).await;
+    let location = resolve_db_location()?;
+    println!(
+        "Using database {} ({})",
+        location.path.display(),
+        location.source
+    );
+    // older builds kept the database in the working directory
+    let legacy = std::path::Path::new("data.db");
+    if !location.path.exists() && legacy.exists() {
+        println!(
+            "Found an existing data.db in the working directory, pass {} data.db to keep using it",
+            DB_PATH_FLAG
+        );
+    }
+    let pool = init_db_file(&location.path).await?;
+
+    match WorkoutService::new(pool.clone())
+        .backfill_exercise_library_ids()
+        .await
+    {
+        Ok(Some(linked)) => println!(
+            "Linked {} workout exercises to the exercise library",
+            linked
+        ),
+        Ok(None) => {}
+        Err(error) => println!("Failed to link workout exercises: {}", error),
+    }
+
+    let settings = match SettingsService::new(pool.clone()).get_settings().await {
+        Ok(settings) => settings,
+        Err(error) => {
+            println!("Failed to load settings, using defaults: {}", error);
+            Default::default()
+        }
+    };
+
+    let backup_service = BackupService::new(pool.clone(), location.path.clone());
+    match backup_service
+        .run_daily_backup(settings.daily_backups)
+        .await
+    {
+        Ok(Some(path)) => println!("Backed up the database to {}", path.display()),
+        Ok(None) => {}
+        Err(error) => println!("Failed to back up the database: {}", error),
+    }
 
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_inner_size([1800.0, 1200.0]),
@@ -17,7 +66,14 @@ pub async fn run_app() -> Result<(), Box<dyn Error>> {
     eframe::run_native(
         "Workout Util",
         native_options,
-        Box::new(|cc| Ok(Box::new(WorkoutUtil::new(cc, pool)))),
+        Box::new(|cc| {
+            Ok(Box::new(WorkoutUtil::new(
+                cc,
+                pool,
+                settings,
+                backup_service,
+            )))
+        }),
     )?;
     Ok(())
 }
@@ -0,0 +1,483 @@
+use crate::db::{MIGRATOR, SqliteExecutor};
+use crate::error::{AppError, AppResult};
+use chrono::{DateTime, Local, NaiveDate};
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Connection, Pool, Sqlite, SqliteConnection};
+use std::path::{Path, PathBuf};
+
+const BACKUP_DIR: &str = "backups";
+const DAILY_PREFIX: &str = "daily-";
+const PRE_MIGRATION_PREFIX: &str = "pre-migration-";
+const PRE_RESTORE_PREFIX: &str = "pre-restore-";
+const MANUAL_PREFIX: &str = "manual-";
+// pre-migration and pre-restore snapshots only pile up across upgrades, keep a few
+const KEEP_SAFETY_BACKUPS: usize = 5;
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackupInfo {
+    pub path: PathBuf,
+    pub name: String,
+    pub size_bytes: u64,
+    pub modified: Option<DateTime<Local>>,
+}
+
+// backups live next to the database, so --db keeps them together
+pub fn backup_dir(db_path: &Path) -> PathBuf {
+    db_path.parent().unwrap_or(Path::new(".")).join(BACKUP_DIR)
+}
+
+// a validated backup waiting to replace the database on the next start
+pub fn staged_restore_path(db_path: &Path) -> PathBuf {
+    let mut path = db_path.as_os_str().to_owned();
+    path.push(".restore");
+    PathBuf::from(path)
+}
+
+fn timestamp() -> String {
+    Local::now().format(TIMESTAMP_FORMAT).to_string()
+}
+
+// a consistent copy even while the app has the database open
+async fn snapshot<'e>(executor: impl SqliteExecutor<'e>, dest: &Path) -> AppResult<()> {
+    if let Some(dir) = dest.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(AppError::io(format!("Failed to create {}", dir.display())))?;
+    }
+    sqlx::query("VACUUM INTO ?")
+        .bind(dest.to_string_lossy().to_string())
+        .execute(executor)
+        .await
+        .map_err(AppError::db(&format!(
+            "Failed to back up to {}",
+            dest.display()
+        )))?;
+    Ok(())
+}
+
+async fn applied_migrations<'e>(executor: impl SqliteExecutor<'e>) -> AppResult<Vec<i64>> {
+    sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success = 1")
+        .fetch_all(executor)
+        .await
+        .or_else(|e| match e {
+            // a brand new database has no migrations table yet
+            sqlx::Error::Database(ref db) if db.message().contains("no such table") => Ok(vec![]),
+            e => Err(AppError::db("Failed to read migrations")(e)),
+        })
+}
+
+// snapshots an existing database before migrations it hasn't seen yet are applied
+pub async fn backup_before_migrations(
+    db: &Pool<Sqlite>,
+    db_path: &Path,
+) -> AppResult<Option<PathBuf>> {
+    let applied = applied_migrations(db).await?;
+    let Some(current) = applied.iter().max() else {
+        return Ok(None); // nothing to lose yet
+    };
+    if MIGRATOR.iter().all(|m| applied.contains(&m.version)) {
+        return Ok(None);
+    }
+    let dest = backup_dir(db_path).join(format!(
+        "{}{}-{}.db",
+        PRE_MIGRATION_PREFIX,
+        current,
+        timestamp()
+    ));
+    snapshot(db, &dest).await?;
+    prune_backups(
+        &backup_dir(db_path),
+        PRE_MIGRATION_PREFIX,
+        KEEP_SAFETY_BACKUPS,
+    )?;
+    Ok(Some(dest))
+}
+
+// at most one per day, keeping the newest `keep`; 0 turns daily backups off
+pub async fn run_daily_backup(
+    db: &Pool<Sqlite>,
+    db_path: &Path,
+    keep: u32,
+    today: NaiveDate,
+) -> AppResult<Option<PathBuf>> {
+    if keep == 0 {
+        return Ok(None);
+    }
+    let dir = backup_dir(db_path);
+    let dest = dir.join(format!("{}{}.db", DAILY_PREFIX, today.format("%Y-%m-%d")));
+    let created = if dest.exists() {
+        None
+    } else {
+        snapshot(db, &dest).await?;
+        Some(dest)
+    };
+    prune_backups(&dir, DAILY_PREFIX, keep as usize)?;
+    Ok(created)
+}
+
+pub async fn backup_now(db: &Pool<Sqlite>, db_path: &Path) -> AppResult<PathBuf> {
+    let dest = backup_dir(db_path).join(format!("{}{}.db", MANUAL_PREFIX, timestamp()));
+    snapshot(db, &dest).await?;
+    Ok(dest)
+}
+
+// names embed a sortable date, so the newest sort last
+fn prune_backups(dir: &Path, prefix: &str, keep: usize) -> AppResult<()> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .map_err(AppError::io(format!("Failed to read {}", dir.display())))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name.starts_with(prefix) && name.ends_with(".db"))
+        .collect();
+    names.sort();
+    let excess = names.len().saturating_sub(keep);
+    for name in &names[..excess] {
+        std::fs::remove_file(dir.join(name)).map_err(AppError::io(format!(
+            "Failed to remove old backup {}",
+            name
+        )))?;
+    }
+    Ok(())
+}
+
+// newest first
+pub fn list_backups(db_path: &Path) -> AppResult<Vec<BackupInfo>> {
+    let dir = backup_dir(db_path);
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut backups: Vec<BackupInfo> = std::fs::read_dir(&dir)
+        .map_err(AppError::io(format!("Failed to read {}", dir.display())))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "db"))
+        .map(|entry| {
+            let metadata = entry.metadata().ok();
+            BackupInfo {
+                path: entry.path(),
+                name: entry.file_name().to_string_lossy().to_string(),
+                size_bytes: metadata.as_ref().map(|m| m.len()).unwrap_or(0),
+                modified: metadata
+                    .and_then(|m| m.modified().ok())
+                    .map(DateTime::<Local>::from),
+            }
+        })
+        .collect();
+    backups.sort_by(|a, b| b.modified.cmp(&a.modified).then(b.name.cmp(&a.name)));
+    Ok(backups)
+}
+
+// opens the file read-only and checks it is an intact workout-util database this build can run
+pub async fn validate_backup(path: &Path) -> AppResult<()> {
+    let options = SqliteConnectOptions::new().filename(path).read_only(true);
+    let mut conn = SqliteConnection::connect_with(&options)
+        .await
+        .map_err(|e| {
+            AppError::InvalidBackup(format!(
+                "{} is not a readable database: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+    let integrity: String = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_one(&mut conn)
+        .await
+        .map_err(|e| {
+            AppError::InvalidBackup(format!("{} is not a valid database: {}", path.display(), e))
+        })?;
+    if integrity != "ok" {
+        return Err(AppError::InvalidBackup(format!(
+            "{} is damaged: {}",
+            path.display(),
+            integrity
+        )));
+    }
+
+    let applied = applied_migrations(&mut conn).await?;
+    if applied.is_empty() {
+        return Err(AppError::InvalidBackup(format!(
+            "{} is not a workout-util database",
+            path.display()
+        )));
+    }
+    if let Some(version) = applied
+        .iter()
+        .find(|version| MIGRATOR.iter().all(|m| m.version != **version))
+    {
+        return Err(AppError::InvalidBackup(format!(
+            "{} was made by a newer build of workout-util (migration {})",
+            path.display(),
+            version
+        )));
+    }
+    let _ = conn.close().await;
+    Ok(())
+}
+
+// the open pool can't have its file swapped, so the restore finishes on the next start
+pub async fn stage_restore(db_path: &Path, backup: &Path) -> AppResult<()> {
+    validate_backup(backup).await?;
+    std::fs::copy(backup, staged_restore_path(db_path))
+        .map_err(AppError::io(format!("Failed to copy {}", backup.display())))?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StagedRestore {
+    Nothing,
+    Restored,
+    Rejected(String), // why, and where the staged file went; the current database is kept
+}
+
+// runs before the pool is opened; the current database is kept as a pre-restore backup
+pub async fn apply_staged_restore(db_path: &Path) -> AppResult<StagedRestore> {
+    let staged = staged_restore_path(db_path);
+    if !staged.exists() {
+        return Ok(StagedRestore::Nothing);
+    }
+    // a bad staged file must not keep the app from starting, move it out of the way
+    if let Err(reason) = validate_backup(&staged).await {
+        let mut rejected = staged.as_os_str().to_owned();
+        rejected.push(format!("-rejected-{}", timestamp()));
+        let rejected = PathBuf::from(rejected);
+        let moved = match std::fs::rename(&staged, &rejected) {
+            Ok(()) => format!("moved to {}", rejected.display()),
+            Err(_) => match std::fs::remove_file(&staged) {
+                Ok(()) => "deleted".to_string(),
+                Err(e) => format!("could not be removed: {}", e),
+            },
+        };
+        return Ok(StagedRestore::Rejected(format!(
+            "{}, the staged file was {}",
+            reason, moved
+        )));
+    }
+
+    if db_path.exists() {
+        let mut conn =
+            SqliteConnection::connect_with(&SqliteConnectOptions::new().filename(db_path))
+                .await
+                .map_err(AppError::db(&format!(
+                    "Failed to open {}",
+                    db_path.display()
+                )))?;
+        let dest = backup_dir(db_path).join(format!("{}{}.db", PRE_RESTORE_PREFIX, timestamp()));
+        snapshot(&mut conn, &dest).await?;
+        let _ = conn.close().await;
+        prune_backups(
+            &backup_dir(db_path),
+            PRE_RESTORE_PREFIX,
+            KEEP_SAFETY_BACKUPS,
+        )?;
+    }
+
+    // a leftover journal from the old file would be replayed into the restored one
+    for suffix in ["-wal", "-shm", "-journal"] {
+        let mut path = db_path.as_os_str().to_owned();
+        path.push(suffix);
+        let _ = std::fs::remove_file(PathBuf::from(path));
+    }
+    std::fs::rename(&staged, db_path).map_err(AppError::io(format!(
+        "Failed to restore {}",
+        db_path.display()
+    )))?;
+    Ok(StagedRestore::Restored)
+}
+
+#[derive(Clone)]
+pub struct BackupService {
+    pool: Pool<Sqlite>,
+    db_path: PathBuf,
+}
+
+impl BackupService {
+    pub fn new(pool: Pool<Sqlite>, db_path: PathBuf) -> Self {
+        Self { pool, db_path }
+    }
+
+    pub async fn run_daily_backup(&self, keep: u32) -> AppResult<Option<PathBuf>> {
+        run_daily_backup(&self.pool, &self.db_path, keep, Local::now().date_naive()).await
+    }
+
+    pub async fn backup_now(&self) -> AppResult<PathBuf> {
+        backup_now(&self.pool, &self.db_path).await
+    }
+
+    pub async fn list_backups(&self) -> AppResult<Vec<BackupInfo>> {
+        list_backups(&self.db_path)
+    }
+
+    pub async fn stage_restore(&self, backup: PathBuf) -> AppResult<()> {
+        stage_restore(&self.db_path, &backup).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::init_db_file;
+
+    fn temp_db_path(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "workout_util_backup_{}_{}",
+            test,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("data.db")
+    }
+
+    fn names(db_path: &Path) -> Vec<String> {
+        let mut names: Vec<String> = list_backups(db_path)
+            .unwrap()
+            .into_iter()
+            .map(|b| b.name)
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn test_daily_backups_rotate() {
+        let db_path = temp_db_path("daily");
+        let pool = init_db_file(&db_path).await.unwrap();
+
+        for day in 1..=4 {
+            let date = NaiveDate::from_ymd_opt(2026, 3, day).unwrap();
+            assert!(
+                run_daily_backup(&pool, &db_path, 2, date)
+                    .await
+                    .unwrap()
+                    .is_some()
+            );
+        }
+        // a second run on the same day keeps the first snapshot
+        let date = NaiveDate::from_ymd_opt(2026, 3, 4).unwrap();
+        assert!(
+            run_daily_backup(&pool, &db_path, 2, date)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        assert_eq!(
+            names(&db_path),
+            vec!["daily-2026-03-03.db", "daily-2026-03-04.db"]
+        );
+
+        pool.close().await;
+        std::fs::remove_dir_all(db_path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_no_migration_backup_when_up_to_date() {
+        let db_path = temp_db_path("up_to_date");
+        let pool = init_db_file(&db_path).await.unwrap();
+
+        assert_eq!(backup_before_migrations(&pool, &db_path).await, Ok(None));
+
+        // pretend the newest migration hasn't run yet
+        let latest = MIGRATOR.iter().map(|m| m.version).max().unwrap();
+        sqlx::query("DELETE FROM _sqlx_migrations WHERE version = ?")
+            .bind(latest)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let backup = backup_before_migrations(&pool, &db_path)
+            .await
+            .unwrap()
+            .expect("pending migration should trigger a backup");
+        assert!(validate_backup(&backup).await.is_ok());
+
+        pool.close().await;
+        std::fs::remove_dir_all(db_path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restore_swaps_in_backup_on_next_start() {
+        let db_path = temp_db_path("restore");
+        let pool = init_db_file(&db_path).await.unwrap();
+        sqlx::query(r#"UPDATE app_settings SET value = '"Lb"' WHERE key = 'weight_unit'"#)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let backup = backup_now(&pool, &db_path).await.unwrap();
+        sqlx::query(r#"UPDATE app_settings SET value = '"Kg"' WHERE key = 'weight_unit'"#)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        stage_restore(&db_path, &backup).await.unwrap();
+        pool.close().await;
+
+        let pool = init_db_file(&db_path).await.unwrap();
+        let unit: String =
+            sqlx::query_scalar("SELECT value FROM app_settings WHERE key = 'weight_unit'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(unit, "\"Lb\"");
+        assert!(!staged_restore_path(&db_path).exists());
+        assert!(
+            names(&db_path)
+                .iter()
+                .any(|n| n.starts_with(PRE_RESTORE_PREFIX))
+        );
+
+        pool.close().await;
+        std::fs::remove_dir_all(db_path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restore_rejects_invalid_files() {
+        let db_path = temp_db_path("invalid");
+        std::fs::create_dir_all(db_path.parent().unwrap()).unwrap();
+        let garbage = db_path.with_file_name("garbage.db");
+        std::fs::write(&garbage, b"definitely not sqlite").unwrap();
+
+        assert!(matches!(
+            stage_restore(&db_path, &garbage).await,
+            Err(AppError::InvalidBackup(_))
+        ));
+        assert!(!staged_restore_path(&db_path).exists());
+
+        std::fs::remove_dir_all(db_path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bad_staged_restore_keeps_current_database() {
+        let db_path = temp_db_path("bad_staged");
+        let pool = init_db_file(&db_path).await.unwrap();
+        sqlx::query(r#"UPDATE app_settings SET value = '"Lb"' WHERE key = 'weight_unit'"#)
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+
+        // staged by hand or damaged after it was validated
+        std::fs::write(staged_restore_path(&db_path), b"definitely not sqlite").unwrap();
+        assert!(matches!(
+            apply_staged_restore(&db_path).await,
+            Ok(StagedRestore::Rejected(_))
+        ));
+        assert!(!staged_restore_path(&db_path).exists());
+        let dir = std::fs::read_dir(db_path.parent().unwrap()).unwrap();
+        assert!(dir.flatten().any(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with("data.db.restore-rejected-")
+        }));
+
+        let pool = init_db_file(&db_path).await.unwrap();
+        let unit: String =
+            sqlx::query_scalar("SELECT value FROM app_settings WHERE key = 'weight_unit'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(unit, "\"Lb\"");
+
+        pool.close().await;
+        std::fs::remove_dir_all(db_path.parent().unwrap()).unwrap();
+    }
+}
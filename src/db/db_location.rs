@@ -0,0 +1,165 @@
+use crate::error::{AppError, AppResult};
+use std::ffi::OsString;
+use std::fmt;
+use std::path::PathBuf;
+
+pub const DB_PATH_FLAG: &str = "--db";
+pub const DB_PATH_ENV: &str = "WORKOUT_UTIL_DB";
+const APP_DIR: &str = "workout-util";
+const DB_FILE: &str = "data.db";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DbSource {
+    Flag,
+    Env,
+    DataDir,
+}
+
+impl fmt::Display for DbSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DbSource::Flag => write!(f, "from {}", DB_PATH_FLAG),
+            DbSource::Env => write!(f, "from {}", DB_PATH_ENV),
+            DbSource::DataDir => write!(f, "default data directory"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DbLocation {
+    pub path: PathBuf,
+    pub source: DbSource,
+}
+
+// --db flag, then WORKOUT_UTIL_DB, then the user's data directory
+pub fn resolve_db_location() -> AppResult<DbLocation> {
+    resolve_from(std::env::args_os().skip(1), |key| std::env::var_os(key))
+}
+
+fn resolve_from(
+    args: impl IntoIterator<Item = OsString>,
+    env: impl Fn(&str) -> Option<OsString>,
+) -> AppResult<DbLocation> {
+    if let Some(path) = db_path_flag(args)? {
+        return Ok(DbLocation {
+            path,
+            source: DbSource::Flag,
+        });
+    }
+    if let Some(path) = env(DB_PATH_ENV).filter(|path| !path.is_empty()) {
+        return Ok(DbLocation {
+            path: path.into(),
+            source: DbSource::Env,
+        });
+    }
+    let data_dir = data_dir(&env).ok_or_else(|| {
+        AppError::Validation(format!(
+            "Could not find a data directory, pass {} <path> or set {}",
+            DB_PATH_FLAG, DB_PATH_ENV
+        ))
+    })?;
+    Ok(DbLocation {
+        path: data_dir.join(APP_DIR).join(DB_FILE),
+        source: DbSource::DataDir,
+    })
+}
+
+// accepts "--db path" and "--db=path", other arguments are ignored
+fn db_path_flag(args: impl IntoIterator<Item = OsString>) -> AppResult<Option<PathBuf>> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let arg = arg.to_string_lossy().to_string();
+        if arg == DB_PATH_FLAG {
+            return match args.next() {
+                Some(path) if !path.is_empty() => Ok(Some(path.into())),
+                _ => Err(AppError::Validation(format!(
+                    "{} needs a path",
+                    DB_PATH_FLAG
+                ))),
+            };
+        }
+        if let Some(path) = arg.strip_prefix(&format!("{}=", DB_PATH_FLAG)) {
+            if path.is_empty() {
+                return Err(AppError::Validation(format!(
+                    "{} needs a path",
+                    DB_PATH_FLAG
+                )));
+            }
+            return Ok(Some(path.into()));
+        }
+    }
+    Ok(None)
+}
+
+// relative values are ignored, as the XDG spec asks
+fn data_dir(env: &impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    let absolute = |key: &str| env(key).map(PathBuf::from).filter(|p| p.is_absolute());
+    if cfg!(windows) {
+        return absolute("APPDATA");
+    }
+    absolute("XDG_DATA_HOME").or_else(|| absolute("HOME").map(|home| home.join(".local/share")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<OsString> {
+        values.iter().map(OsString::from).collect()
+    }
+
+    fn env_of(vars: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<OsString> {
+        |key| {
+            vars.iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| OsString::from(value))
+        }
+    }
+
+    #[test]
+    fn test_flag_wins_over_env_and_data_dir() {
+        let env = env_of(&[(DB_PATH_ENV, "/env/workouts.db"), ("XDG_DATA_HOME", "/xdg")]);
+
+        let location = resolve_from(args(&["--db", "/flag/workouts.db"]), &env).unwrap();
+        assert_eq!(location.path, PathBuf::from("/flag/workouts.db"));
+        assert_eq!(location.source, DbSource::Flag);
+
+        let location = resolve_from(args(&["--db=/flag/other.db"]), &env).unwrap();
+        assert_eq!(location.path, PathBuf::from("/flag/other.db"));
+
+        let location = resolve_from(args(&[]), &env).unwrap();
+        assert_eq!(location.path, PathBuf::from("/env/workouts.db"));
+        assert_eq!(location.source, DbSource::Env);
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_data_dir_follows_xdg() {
+        let location = resolve_from(
+            args(&[]),
+            env_of(&[("XDG_DATA_HOME", "/xdg"), ("HOME", "/home/me")]),
+        )
+        .unwrap();
+        assert_eq!(location.path, PathBuf::from("/xdg/workout-util/data.db"));
+        assert_eq!(location.source, DbSource::DataDir);
+
+        // a relative XDG_DATA_HOME is invalid, fall back to ~/.local/share
+        let location = resolve_from(
+            args(&[]),
+            env_of(&[("XDG_DATA_HOME", "relative"), ("HOME", "/home/me")]),
+        )
+        .unwrap();
+        assert_eq!(
+            location.path,
+            PathBuf::from("/home/me/.local/share/workout-util/data.db")
+        );
+
+        assert!(resolve_from(args(&[]), env_of(&[])).is_err());
+    }
+
+    #[test]
+    fn test_flag_without_path_is_an_error() {
+        assert!(resolve_from(args(&["--db"]), env_of(&[])).is_err());
+        assert!(resolve_from(args(&["--db="]), env_of(&[])).is_err());
+    }
+}
@@ -1,9 +1,13 @@
+pub mod backup;
+pub mod db_location;
 pub mod pagination_support;
 
-use sqlx::migrate::MigrateDatabase;
+use crate::error::{AppError, AppResult};
+use backup::StagedRestore;
+use sqlx::migrate::{MigrateError, Migrator};
+use sqlx::sqlite::SqliteConnectOptions;
 use sqlx::{Pool, Sqlite, SqlitePool};
-
-pub const DEFAULT_DB_URL: &str = "sqlite://data.db";
+use std::path::Path;
 
 pub type SqliteTx<'a> = sqlx::Transaction<'a, Sqlite>;
 
@@ -13,43 +17,114 @@ impl<'e, T: sqlx::Executor<'e, Database = Sqlite>> SqliteExecutor<'e> for T {}
 #[cfg(test)]
 pub const IN_MEMORY_DB_URL: &str = "sqlite::memory:";
 
-async fn init_db_file(url: &str) {
-    if !Sqlite::database_exists(url).await.unwrap_or(false) {
-        println!("Creating database {}", url);
-        match Sqlite::create_database(url).await {
-            Ok(_) => println!("Create db success"),
-            Err(error) => panic!("error: {}", error),
+// compiled into the binary so it runs outside the source tree
+pub(crate) static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+async fn connect_db(options: SqliteConnectOptions) -> AppResult<Pool<Sqlite>> {
+    SqlitePool::connect_with(options.create_if_missing(true))
+        .await
+        .map_err(AppError::db("Failed to open database"))
+}
+
+fn latest_known_migration() -> i64 {
+    MIGRATOR.iter().map(|m| m.version).max().unwrap_or(0)
+}
+
+async fn migrate_db(db: &Pool<Sqlite>) -> AppResult<()> {
+    match MIGRATOR.run(db).await {
+        Ok(_) => {
+            println!("Database schema at version {}", latest_known_migration());
+            Ok(())
         }
-    } else {
-        println!("Database already exists");
+        // a migration applied by a newer build that this binary doesn't know about
+        Err(MigrateError::VersionMissing(version)) => Err(AppError::SchemaTooNew {
+            version,
+            latest: latest_known_migration(),
+        }),
+        Err(error @ MigrateError::VersionMismatch(version)) => Err(AppError::Database {
+            context: format!(
+                "Migration {} was changed after it was applied to this database",
+                version
+            ),
+            message: error.to_string(),
+        }),
+        Err(error) => Err(AppError::Database {
+            context: "Migration failed".to_string(),
+            message: error.to_string(),
+        }),
     }
 }
 
-async fn connect_db(url: &str) -> Pool<Sqlite> {
-    SqlitePool::connect(url).await.unwrap()
+#[cfg(test)]
+pub async fn init_db(url: &str) -> AppResult<Pool<Sqlite>> {
+    use std::str::FromStr;
+
+    let options = SqliteConnectOptions::from_str(url)
+        .map_err(AppError::db(&format!("Invalid database url {}", url)))?;
+    let db = connect_db(options).await?;
+    migrate_db(&db).await?;
+    Ok(db)
 }
-// should I use the file system or not? hmmm db tables are not likely to change or be added a lot
-// there is an argument for keeping them as consts in the code so the binary doesn't need to load the file
-async fn migrate_db(db: &Pool<Sqlite>) {
-    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
-    let migrations = std::path::Path::new(&crate_dir).join("./migrations");
-    let migration_results = sqlx::migrate::Migrator::new(migrations)
-        .await
-        .unwrap()
-        .run(db)
-        .await;
-    match migration_results {
-        Ok(_) => println!("Migration success"),
-        Err(error) => {
-            panic!("error: {}", error);
+
+pub async fn init_db_file(path: &Path) -> AppResult<Pool<Sqlite>> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)
+            .map_err(AppError::io(format!("Failed to create {}", dir.display())))?;
+    }
+    match backup::apply_staged_restore(path).await? {
+        StagedRestore::Restored => println!("Restored the database from the staged backup"),
+        StagedRestore::Rejected(reason) => {
+            println!("Skipped restoring the staged backup: {}", reason)
         }
+        StagedRestore::Nothing => {}
+    }
+    let db = connect_db(SqliteConnectOptions::new().filename(path)).await?;
+    if let Some(backup) = backup::backup_before_migrations(&db, path).await? {
+        println!(
+            "Backed up the database to {} before migrating",
+            backup.display()
+        );
     }
-    println!("migration: {:?}", migration_results);
+    migrate_db(&db).await?;
+    Ok(db)
 }
 
-pub async fn init_db(url: &str) -> Pool<Sqlite> {
-    init_db_file(url).await;
-    let db = connect_db(url).await;
-    migrate_db(&db).await;
-    db
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_newer_schema_is_reported() {
+        let pool = init_db(IN_MEMORY_DB_URL).await.unwrap();
+
+        // pretend a newer build added a migration
+        sqlx::query(
+            "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+             VALUES (99990101000000, 'from the future', 1, x'00', 0)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let error = migrate_db(&pool).await.unwrap_err();
+        assert_eq!(
+            error,
+            AppError::SchemaTooNew {
+                version: 99990101000000,
+                latest: latest_known_migration(),
+            }
+        );
+        assert!(error.to_string().contains("newer than this build"));
+    }
+
+    #[tokio::test]
+    async fn test_init_db_file_creates_missing_directories() {
+        let dir = std::env::temp_dir().join(format!("workout_util_db_test_{}", std::process::id()));
+        let path = dir.join("nested").join("data.db");
+
+        let pool = init_db_file(&path).await.unwrap();
+        assert!(path.exists());
+        drop(pool);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -40,6 +40,7 @@ pub struct PaginationParams {
 
 pub struct PaginationState {
     pub limit: u32,
+    default_limit: u32, // from the settings, the limit field can be changed per page
     pub next_cursor: Option<u32>,
     pub prev_cursor: Option<u32>,
     pub current_cursor: Option<u32>, // Cursor used for current page
@@ -50,6 +51,7 @@ impl Default for PaginationState {
     fn default() -> Self {
         Self {
             limit: 50,
+            default_limit: 50,
             next_cursor: None,
             prev_cursor: None,
             current_cursor: None,
@@ -59,6 +61,17 @@ impl Default for PaginationState {
 }
 
 impl PaginationState {
+    // returns true when the default changed and the list should be reloaded
+    pub fn apply_default_limit(&mut self, limit: u32) -> bool {
+        if limit == self.default_limit {
+            return false;
+        }
+        self.default_limit = limit;
+        self.limit = limit;
+        self.reset_pagination();
+        true
+    }
+
     pub fn reset_pagination(&mut self) {
         self.current_cursor = None;
         self.next_cursor = None;
@@ -87,6 +87,97 @@ pub enum Band {
     Green,
 }
 
+impl fmt::Display for Band {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Band::Yellow => write!(f, "Yellow"),
+            Band::Red => write!(f, "Red"),
+            Band::Black => write!(f, "Black"),
+            Band::Purple => write!(f, "Purple"),
+            Band::Green => write!(f, "Green"),
+        }
+    }
+}
+
+const KG_PER_LB: f64 = 0.45359237;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Type, Serialize, Deserialize, Default)]
+pub enum WeightUnit {
+    #[default]
+    Kg,
+    Lb,
+}
+
+impl WeightUnit {
+    pub fn convert(self, value: f64, to: WeightUnit) -> f64 {
+        match (self, to) {
+            (WeightUnit::Kg, WeightUnit::Lb) => value / KG_PER_LB,
+            (WeightUnit::Lb, WeightUnit::Kg) => value * KG_PER_LB,
+            _ => value,
+        }
+    }
+
+    // at most 2 decimals, trailing zeros dropped: "20 kg", "21.25 kg", "44.09 lb"
+    pub fn format(self, value: f64) -> String {
+        let rounded = format!("{:.2}", value);
+        let trimmed = rounded.trim_end_matches('0').trim_end_matches('.');
+        format!("{} {}", trimmed, self)
+    }
+}
+
+impl fmt::Display for WeightUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WeightUnit::Kg => write!(f, "kg"),
+            WeightUnit::Lb => write!(f, "lb"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Type, Serialize, Deserialize, Default)]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Theme::Dark => write!(f, "Dark"),
+            Theme::Light => write!(f, "Light"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Type)]
+pub enum RecordType {
+    HeaviestWeight,
+    MostReps, // at a given load
+    LongestHold,
+    Estimated1rm,
+}
+
+impl RecordType {
+    pub const ALL: [RecordType; 4] = [
+        RecordType::HeaviestWeight,
+        RecordType::MostReps,
+        RecordType::LongestHold,
+        RecordType::Estimated1rm,
+    ];
+}
+
+impl fmt::Display for RecordType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordType::HeaviestWeight => write!(f, "Heaviest Weight"),
+            RecordType::MostReps => write!(f, "Most Reps"),
+            RecordType::LongestHold => write!(f, "Longest Hold"),
+            RecordType::Estimated1rm => write!(f, "Estimated 1RM"),
+        }
+    }
+}
+
 // data models
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Type)]
 pub enum PushOrPull {
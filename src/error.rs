@@ -0,0 +1,162 @@
+use sqlx::error::ErrorKind;
+use std::fmt;
+
+pub type AppResult<T> = Result<T, AppError>;
+
+const MAX_LISTED_DEPENDENTS: usize = 5;
+
+// returned by every repo and service, pages show it with to_string()
+#[derive(Debug, Clone, PartialEq)]
+pub enum AppError {
+    NotFound(&'static str), // what was looked up, e.g. "Workout"
+    Validation(String),
+    // a delete blocked by ON DELETE RESTRICT, with the logged sessions that still point at the row
+    ForeignKeyRestricted {
+        what: &'static str,
+        dependents: Vec<String>,
+    },
+    Conflict(String), // unique constraint
+    Database {
+        context: String,
+        message: String,
+    },
+    // reading or writing files outside the database, e.g. backups
+    Io {
+        context: String,
+        message: String,
+    },
+    InvalidBackup(String), // a file that can't be restored, and why
+    // the database was migrated by a newer build than this one
+    SchemaTooNew {
+        version: i64,
+        latest: i64,
+    },
+}
+
+impl AppError {
+    // keeps what was being done; constraint violations get their own variant
+    pub fn db(context: &str) -> impl FnOnce(sqlx::Error) -> AppError + '_ {
+        move |error| AppError::from_sqlx(context, error)
+    }
+
+    // db() for deletes, naming the row when ON DELETE RESTRICT blocks it
+    pub fn db_delete(what: &'static str) -> impl FnOnce(sqlx::Error) -> AppError {
+        move |error| {
+            let context = format!("Failed to delete {}", what.to_lowercase());
+            match AppError::from_sqlx(&context, error) {
+                AppError::ForeignKeyRestricted { dependents, .. } => {
+                    AppError::ForeignKeyRestricted { what, dependents }
+                }
+                other => other,
+            }
+        }
+    }
+
+    pub fn io(context: String) -> impl FnOnce(std::io::Error) -> AppError {
+        move |error| AppError::Io {
+            context,
+            message: error.to_string(),
+        }
+    }
+
+    fn from_sqlx(context: &str, error: sqlx::Error) -> AppError {
+        match &error {
+            sqlx::Error::RowNotFound => return AppError::NotFound("Record"),
+            // sqlite reports ON DELETE RESTRICT as a trigger constraint, not a foreign key one
+            sqlx::Error::Database(db)
+                if db.kind() == ErrorKind::ForeignKeyViolation
+                    || db.message().contains("FOREIGN KEY constraint failed") =>
+            {
+                return AppError::ForeignKeyRestricted {
+                    what: "Record",
+                    dependents: vec![],
+                };
+            }
+            sqlx::Error::Database(db) => match db.kind() {
+                ErrorKind::UniqueViolation => {
+                    return AppError::Conflict(db.message().to_string());
+                }
+                ErrorKind::NotNullViolation | ErrorKind::CheckViolation => {
+                    return AppError::Validation(db.message().to_string());
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+        AppError::Database {
+            context: context.to_string(),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(error: sqlx::Error) -> Self {
+        AppError::from_sqlx("Database error", error)
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AppError::NotFound(what) => write!(f, "{} not found, it may have been deleted", what),
+            AppError::Validation(message) => write!(f, "{}", message),
+            AppError::ForeignKeyRestricted { what, dependents } if dependents.is_empty() => {
+                write!(
+                    f,
+                    "{} can't be deleted while logged sets still refer to it",
+                    what
+                )
+            }
+            AppError::ForeignKeyRestricted { what, dependents } => {
+                write!(
+                    f,
+                    "{} can't be deleted, it is logged in {} session{}: {}",
+                    what,
+                    dependents.len(),
+                    if dependents.len() == 1 { "" } else { "s" },
+                    dependents[..dependents.len().min(MAX_LISTED_DEPENDENTS)].join(", ")
+                )?;
+                if dependents.len() > MAX_LISTED_DEPENDENTS {
+                    write!(f, " and {} more", dependents.len() - MAX_LISTED_DEPENDENTS)?;
+                }
+                Ok(())
+            }
+            AppError::Conflict(message) => write!(f, "Already exists: {}", message),
+            AppError::Database { context, message } => write!(f, "{}: {}", context, message),
+            AppError::Io { context, message } => write!(f, "{}: {}", context, message),
+            AppError::InvalidBackup(reason) => write!(f, "Can't restore this backup: {}", reason),
+            AppError::SchemaTooNew { version, latest } => write!(
+                f,
+                "The database schema (migration {}) is newer than this build of workout-util \
+                 (latest migration {}). Update the app, or open a different database with --db.",
+                version, latest
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restricted_delete_lists_sessions() {
+        let error = AppError::ForeignKeyRestricted {
+            what: "Workout",
+            dependents: vec!["2026-03-01".to_string(), "2026-03-04".to_string()],
+        };
+        assert_eq!(
+            error.to_string(),
+            "Workout can't be deleted, it is logged in 2 sessions: 2026-03-01, 2026-03-04"
+        );
+
+        let error = AppError::ForeignKeyRestricted {
+            what: "Workout exercise",
+            dependents: (1..=7).map(|day| format!("2026-03-0{}", day)).collect(),
+        };
+        assert!(error.to_string().ends_with("2026-03-05 and 2 more"));
+    }
+}
@@ -72,7 +72,7 @@ impl HasId for ExerciseLibraryRes {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct ExerciseLibraryFilterReq {
     pub name: Option<String>,
     pub push_or_pull: Option<Vec<PushOrPull>>,
@@ -86,23 +86,6 @@ pub struct ExerciseLibraryFilterReq {
     pub grip_width: Option<Vec<GripWidth>>,
 }
 
-impl Default for ExerciseLibraryFilterReq {
-    fn default() -> Self {
-        Self {
-            name: None,
-            push_or_pull: None,
-            dynamic_or_static: None,
-            straight_or_bent: None,
-            squat_or_hinge: None,
-            upper_or_lower: None,
-            compound_or_isolation: None,
-            lever_variation: None,
-            grip: None,
-            grip_width: None,
-        }
-    }
-}
-
 // row is mapped to these valid structs
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct UpperBodyCompoundExercise {
@@ -209,6 +192,18 @@ pub enum ValidExercise {
     LowerBodyIsolation(LowerBodyIsolationExercise),
 }
 
+impl ExerciseName for ValidExercise {
+    fn full_name(&self) -> String {
+        match self {
+            ValidExercise::StraightArmCompound(x) => x.full_name(),
+            ValidExercise::BentArmCompound(x) => x.full_name(),
+            ValidExercise::UpperBodyIsolation(x) => x.full_name(),
+            ValidExercise::LowerBodyCompound(x) => x.full_name(),
+            ValidExercise::LowerBodyIsolation(x) => x.full_name(),
+        }
+    }
+}
+
 pub fn get_exercise_id(v: &ValidExercise) -> u32 {
     match v {
         ValidExercise::StraightArmCompound(x) => x.id,
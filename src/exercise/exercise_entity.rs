@@ -2,6 +2,7 @@ use crate::enums::{
     CompoundOrIsolation, DynamicOrStatic, Grip, GripWidth, LeverVariation, PushOrPull,
     SquatOrHinge, StraightOrBentArm, UpperOrLower,
 };
+use crate::error::{AppError, AppResult};
 use crate::exercise::exercise_dto::{
     BentArmCompoundExercise, ExerciseLibraryReq, LowerBodyCompoundExercise,
     LowerBodyIsolationExercise, StraightArmCompoundExercise, UpperBodyIsolationExercise,
@@ -27,7 +28,7 @@ pub struct ExerciseLibraryEntity {
 }
 
 impl ExerciseLibraryEntity {
-    pub fn from_req(req: ExerciseLibraryReq) -> Result<ExerciseLibraryEntity, String> {
+    pub fn from_req(req: ExerciseLibraryReq) -> AppResult<ExerciseLibraryEntity> {
         let entity = ExerciseLibraryEntity {
             id: 0, // let db generate id
             name: req.name,
@@ -46,56 +47,62 @@ impl ExerciseLibraryEntity {
         Ok(entity)
     }
 
-    fn validate_invariants(&self) -> Result<(), String> {
+    fn validate_invariants(&self) -> AppResult<()> {
         // Reject contradictory fields
         if self.name.is_empty() {
-            return Err("Exercise name cannot be empty".into());
+            return Err(AppError::Validation("Exercise name cannot be empty".into()));
         }
         if self.upper_or_lower == UpperOrLower::Lower && self.push_or_pull.is_some() {
-            return Err("Lower body exercises cannot have push/pull designation".into());
+            return Err(AppError::Validation(
+                "Lower body exercises cannot have push/pull designation".into(),
+            ));
         }
         if self.upper_or_lower == UpperOrLower::Upper && self.squat_or_hinge.is_some() {
-            return Err("Upper body exercises cannot have squat/hinge designation".into());
+            return Err(AppError::Validation(
+                "Upper body exercises cannot have squat/hinge designation".into(),
+            ));
         }
-        if self.compound_or_isolation == CompoundOrIsolation::Isolation {
-            if self.push_or_pull.is_some()
+        if self.compound_or_isolation == CompoundOrIsolation::Isolation
+            && (self.push_or_pull.is_some()
                 || self.lever_variation.is_some()
                 || self.grip.is_some()
-                || self.grip_width.is_some()
-            {
-                return Err("Isolation exercises cannot have compound-specific attributes".into());
-            }
+                || self.grip_width.is_some())
+        {
+            return Err(AppError::Validation(
+                "Isolation exercises cannot have compound-specific attributes".into(),
+            ));
         }
         Ok(())
     }
 
-    pub fn to_valid_struct(&self) -> Result<ValidExercise, String> {
+    pub fn to_valid_struct(&self) -> AppResult<ValidExercise> {
         self.validate_invariants()?;
 
         match self.upper_or_lower {
             UpperOrLower::Upper => match self.compound_or_isolation {
                 CompoundOrIsolation::Compound => {
-                    let push_or_pull = self.push_or_pull.ok_or(
+                    let push_or_pull = self.push_or_pull.ok_or(AppError::Validation(
                         "Upper body compound exercises require a push/pull designation".to_string(),
-                    )?;
-                    let straight_or_bent = self.straight_or_bent.ok_or(
+                    ))?;
+                    let straight_or_bent = self.straight_or_bent.ok_or(AppError::Validation(
                         "Upper body compound exercises require a straight/bent arm designation"
                             .to_string(),
-                    )?;
-                    let grip = self.grip.ok_or(
+                    ))?;
+                    let grip = self.grip.ok_or(AppError::Validation(
                         "Upper body compound exercises require a grip designation".to_string(),
-                    )?;
-                    let grip_width = self.grip_width.ok_or(
+                    ))?;
+                    let grip_width = self.grip_width.ok_or(AppError::Validation(
                         "Upper body compound exercises require a grip width designation"
                             .to_string(),
-                    )?;
+                    ))?;
 
                     match straight_or_bent {
                         StraightOrBentArm::Straight => {
-                            let lever_variation = self.lever_variation.ok_or(
-                                "Straight-arm compound exercises require a lever variation"
-                                    .to_string(),
-                            )?;
+                            let lever_variation =
+                                self.lever_variation.ok_or(AppError::Validation(
+                                    "Straight-arm compound exercises require a lever variation"
+                                        .to_string(),
+                                ))?;
 
                             Ok(ValidExercise::StraightArmCompound(
                                 StraightArmCompoundExercise {
@@ -123,10 +130,10 @@ impl ExerciseLibraryEntity {
                     }
                 }
                 CompoundOrIsolation::Isolation => {
-                    let straight_or_bent = self.straight_or_bent.ok_or(
+                    let straight_or_bent = self.straight_or_bent.ok_or(AppError::Validation(
                         "Upper body isolation exercises require a straight/bent arm designation"
                             .to_string(),
-                    )?;
+                    ))?;
 
                     Ok(ValidExercise::UpperBodyIsolation(
                         UpperBodyIsolationExercise {
@@ -140,10 +147,10 @@ impl ExerciseLibraryEntity {
             },
             UpperOrLower::Lower => match self.compound_or_isolation {
                 CompoundOrIsolation::Compound => {
-                    let squat_or_hinge = self.squat_or_hinge.ok_or(
+                    let squat_or_hinge = self.squat_or_hinge.ok_or(AppError::Validation(
                         "Lower body compound exercises require a squat/hinge designation"
                             .to_string(),
-                    )?;
+                    ))?;
 
                     Ok(ValidExercise::LowerBodyCompound(
                         LowerBodyCompoundExercise {
@@ -172,6 +179,7 @@ mod test {
         CompoundOrIsolation, DynamicOrStatic, Grip, GripWidth, LeverVariation, PushOrPull,
         SquatOrHinge, StraightOrBentArm, UpperOrLower,
     };
+    use crate::error::AppError;
     use crate::exercise::exercise_entity::ExerciseLibraryEntity;
 
     #[test]
@@ -199,7 +207,9 @@ mod test {
         invalid.push_or_pull = None;
         assert_eq!(
             invalid.to_valid_struct().err().unwrap(),
-            "Upper body compound exercises require a push/pull designation"
+            AppError::Validation(
+                "Upper body compound exercises require a push/pull designation".to_string()
+            )
         );
 
         // 3. Missing Straight/Bent
@@ -207,7 +217,9 @@ mod test {
         invalid.straight_or_bent = None;
         assert_eq!(
             invalid.to_valid_struct().err().unwrap(),
-            "Upper body compound exercises require a straight/bent arm designation"
+            AppError::Validation(
+                "Upper body compound exercises require a straight/bent arm designation".to_string()
+            )
         );
 
         // 4. Missing Grip
@@ -215,7 +227,9 @@ mod test {
         invalid.grip = None;
         assert_eq!(
             invalid.to_valid_struct().err().unwrap(),
-            "Upper body compound exercises require a grip designation"
+            AppError::Validation(
+                "Upper body compound exercises require a grip designation".to_string()
+            )
         );
 
         // 5. Missing Grip Width
@@ -223,7 +237,9 @@ mod test {
         invalid.grip_width = None;
         assert_eq!(
             invalid.to_valid_struct().err().unwrap(),
-            "Upper body compound exercises require a grip width designation"
+            AppError::Validation(
+                "Upper body compound exercises require a grip width designation".to_string()
+            )
         );
     }
 
@@ -252,7 +268,9 @@ mod test {
         invalid.lever_variation = None;
         assert_eq!(
             invalid.to_valid_struct().err().unwrap(),
-            "Straight-arm compound exercises require a lever variation"
+            AppError::Validation(
+                "Straight-arm compound exercises require a lever variation".to_string()
+            )
         );
     }
 
@@ -281,7 +299,10 @@ mod test {
         invalid.straight_or_bent = None;
         assert_eq!(
             invalid.to_valid_struct().err().unwrap(),
-            "Upper body isolation exercises require a straight/bent arm designation"
+            AppError::Validation(
+                "Upper body isolation exercises require a straight/bent arm designation"
+                    .to_string()
+            )
         );
     }
 
@@ -310,7 +331,9 @@ mod test {
         invalid.squat_or_hinge = None;
         assert_eq!(
             invalid.to_valid_struct().err().unwrap(),
-            "Lower body compound exercises require a squat/hinge designation"
+            AppError::Validation(
+                "Lower body compound exercises require a squat/hinge designation".to_string()
+            )
         );
     }
 
@@ -6,6 +6,7 @@ use crate::enums::{
     CompoundOrIsolation, Grip, GripWidth, LeverVariation, PushOrPull, SquatOrHinge,
     StraightOrBentArm, UpperOrLower,
 };
+use crate::error::{AppError, AppResult};
 use crate::exercise::exercise_dto::{
     ExerciseLibraryFilterReq, ExerciseLibraryReq, ExerciseLibraryRes, ValidExercise,
 };
@@ -15,6 +16,12 @@ use sqlx::{QueryBuilder, Sqlite};
 #[derive(Clone, Copy)]
 pub struct ExerciseRepo {}
 
+impl Default for ExerciseRepo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ExerciseRepo {
     pub fn new() -> Self {
         Self {}
@@ -24,7 +31,7 @@ impl ExerciseRepo {
         &self,
         tx: &mut SqliteTx<'_>,
         req: ExerciseLibraryReq,
-    ) -> Result<u32, String> {
+    ) -> AppResult<u32> {
         let entity = ExerciseLibraryEntity::from_req(req)?;
 
         let result = sqlx::query(
@@ -48,7 +55,7 @@ impl ExerciseRepo {
         .bind(entity.description)
         .execute(&mut **tx)
         .await
-        .map_err(|e| format!("Failed to create exercise: {}", e))?;
+        .map_err(AppError::db("Failed to create exercise"))?;
 
         let id = result.last_insert_rowid() as u32;
 
@@ -59,7 +66,7 @@ impl ExerciseRepo {
         &self,
         tx: &mut SqliteTx<'_>,
         valid_exercise: ValidExercise,
-    ) -> Result<(), String> {
+    ) -> AppResult<()> {
         let (
             id,
             name,
@@ -162,28 +169,24 @@ impl ExerciseRepo {
         .bind(id)
         .execute(&mut **tx)
         .await
-        .map_err(|e| format!("Failed to update exercise: {}", e))?;
+        .map_err(AppError::db("Failed to update exercise"))?;
 
         if result.rows_affected() == 0 {
-            return Err("Exercise not found".to_string());
+            return Err(AppError::NotFound("Exercise"));
         }
 
         Ok(())
     }
 
-    pub async fn delete_exercise(
-        &self,
-        tx: &mut SqliteTx<'_>,
-        exercise_id: u32,
-    ) -> Result<(), String> {
+    pub async fn delete_exercise(&self, tx: &mut SqliteTx<'_>, exercise_id: u32) -> AppResult<()> {
         let result = sqlx::query("DELETE FROM exercise_library WHERE id = ?")
             .bind(exercise_id)
             .execute(&mut **tx)
             .await
-            .map_err(|e| format!("Failed to delete exercise (might be in use): {}", e))?;
+            .map_err(AppError::db_delete("Exercise"))?;
 
         if result.rows_affected() == 0 {
-            return Err("Exercise not found".to_string());
+            return Err(AppError::NotFound("Exercise"));
         }
 
         Ok(())
@@ -193,24 +196,34 @@ impl ExerciseRepo {
         &self,
         executor: impl SqliteExecutor<'e>,
         exercise_id: u32,
-    ) -> Result<ValidExercise, String> {
+    ) -> AppResult<ValidExercise> {
         let row: ExerciseLibraryEntity =
             sqlx::query_as("SELECT * FROM exercise_library WHERE id = ?")
                 .bind(exercise_id)
                 .fetch_optional(executor)
                 .await
-                .map_err(|e| format!("Database error: {}", e))?
-                .ok_or_else(|| "Exercise not found".to_string())?;
+                .map_err(AppError::db("Database error"))?
+                .ok_or(AppError::NotFound("Exercise"))?;
 
         row.to_valid_struct()
     }
 
+    pub async fn get_all_exercises<'e>(
+        &self,
+        executor: impl SqliteExecutor<'e>,
+    ) -> AppResult<Vec<ExerciseLibraryRes>> {
+        sqlx::query_as("SELECT * FROM exercise_library ORDER BY id")
+            .fetch_all(executor)
+            .await
+            .map_err(AppError::db("Database error"))
+    }
+
     pub async fn paginate_exercises<'e>(
         &self,
         executor: impl SqliteExecutor<'e>,
         filter_req: Option<ExerciseLibraryFilterReq>,
         pagination_params: PaginationParams,
-    ) -> Result<PaginationRes<ExerciseLibraryRes>, String> {
+    ) -> AppResult<PaginationRes<ExerciseLibraryRes>> {
         let mut qb = QueryBuilder::new("SELECT * FROM exercise_library WHERE 1=1");
         self.pagination_filters(filter_req, &mut qb);
         keyset_paginate(&pagination_params, None, &mut qb);
@@ -219,7 +232,7 @@ impl ExerciseRepo {
             .build_query_as()
             .fetch_all(executor)
             .await
-            .map_err(|e| format!("Failed to paginate exercises: {}", e))?;
+            .map_err(AppError::db("Failed to paginate exercises"))?;
 
         let cursors = get_cursors(&pagination_params, &mut rows);
         Ok(PaginationRes::new(rows, cursors))
@@ -236,103 +249,103 @@ impl ExerciseRepo {
                 qb.push_bind(format!("%{}%", name));
             }
 
-            if let Some(vals) = req.push_or_pull {
-                if !vals.is_empty() {
-                    qb.push(" AND push_or_pull IN (");
-                    let mut sep = qb.separated(", ");
-                    for v in vals {
-                        sep.push_bind(v);
-                    }
-                    sep.push_unseparated(")");
+            if let Some(vals) = req.push_or_pull
+                && !vals.is_empty()
+            {
+                qb.push(" AND push_or_pull IN (");
+                let mut sep = qb.separated(", ");
+                for v in vals {
+                    sep.push_bind(v);
                 }
+                sep.push_unseparated(")");
             }
 
-            if let Some(vals) = req.dynamic_or_static {
-                if !vals.is_empty() {
-                    qb.push(" AND dynamic_or_static IN (");
-                    let mut sep = qb.separated(", ");
-                    for v in vals {
-                        sep.push_bind(v);
-                    }
-                    sep.push_unseparated(")");
+            if let Some(vals) = req.dynamic_or_static
+                && !vals.is_empty()
+            {
+                qb.push(" AND dynamic_or_static IN (");
+                let mut sep = qb.separated(", ");
+                for v in vals {
+                    sep.push_bind(v);
                 }
+                sep.push_unseparated(")");
             }
 
-            if let Some(vals) = req.straight_or_bent {
-                if !vals.is_empty() {
-                    qb.push(" AND straight_or_bent IN (");
-                    let mut sep = qb.separated(", ");
-                    for v in vals {
-                        sep.push_bind(v);
-                    }
-                    sep.push_unseparated(")");
+            if let Some(vals) = req.straight_or_bent
+                && !vals.is_empty()
+            {
+                qb.push(" AND straight_or_bent IN (");
+                let mut sep = qb.separated(", ");
+                for v in vals {
+                    sep.push_bind(v);
                 }
+                sep.push_unseparated(")");
             }
 
-            if let Some(vals) = req.squat_or_hinge {
-                if !vals.is_empty() {
-                    qb.push(" AND squat_or_hinge IN (");
-                    let mut sep = qb.separated(", ");
-                    for v in vals {
-                        sep.push_bind(v);
-                    }
-                    sep.push_unseparated(")");
+            if let Some(vals) = req.squat_or_hinge
+                && !vals.is_empty()
+            {
+                qb.push(" AND squat_or_hinge IN (");
+                let mut sep = qb.separated(", ");
+                for v in vals {
+                    sep.push_bind(v);
                 }
+                sep.push_unseparated(")");
             }
 
-            if let Some(vals) = req.upper_or_lower {
-                if !vals.is_empty() {
-                    qb.push(" AND upper_or_lower IN (");
-                    let mut sep = qb.separated(", ");
-                    for v in vals {
-                        sep.push_bind(v);
-                    }
-                    sep.push_unseparated(")");
+            if let Some(vals) = req.upper_or_lower
+                && !vals.is_empty()
+            {
+                qb.push(" AND upper_or_lower IN (");
+                let mut sep = qb.separated(", ");
+                for v in vals {
+                    sep.push_bind(v);
                 }
+                sep.push_unseparated(")");
             }
 
-            if let Some(vals) = req.compound_or_isolation {
-                if !vals.is_empty() {
-                    qb.push(" AND compound_or_isolation IN (");
-                    let mut sep = qb.separated(", ");
-                    for v in vals {
-                        sep.push_bind(v);
-                    }
-                    sep.push_unseparated(")");
+            if let Some(vals) = req.compound_or_isolation
+                && !vals.is_empty()
+            {
+                qb.push(" AND compound_or_isolation IN (");
+                let mut sep = qb.separated(", ");
+                for v in vals {
+                    sep.push_bind(v);
                 }
+                sep.push_unseparated(")");
             }
 
-            if let Some(vals) = req.lever_variation {
-                if !vals.is_empty() {
-                    qb.push(" AND lever_variation IN (");
-                    let mut sep = qb.separated(", ");
-                    for v in vals {
-                        sep.push_bind(v);
-                    }
-                    sep.push_unseparated(")");
+            if let Some(vals) = req.lever_variation
+                && !vals.is_empty()
+            {
+                qb.push(" AND lever_variation IN (");
+                let mut sep = qb.separated(", ");
+                for v in vals {
+                    sep.push_bind(v);
                 }
+                sep.push_unseparated(")");
             }
 
-            if let Some(vals) = req.grip {
-                if !vals.is_empty() {
-                    qb.push(" AND grip IN (");
-                    let mut sep = qb.separated(", ");
-                    for v in vals {
-                        sep.push_bind(v);
-                    }
-                    sep.push_unseparated(")");
+            if let Some(vals) = req.grip
+                && !vals.is_empty()
+            {
+                qb.push(" AND grip IN (");
+                let mut sep = qb.separated(", ");
+                for v in vals {
+                    sep.push_bind(v);
                 }
+                sep.push_unseparated(")");
             }
 
-            if let Some(vals) = req.grip_width {
-                if !vals.is_empty() {
-                    qb.push(" AND grip_width IN (");
-                    let mut sep = qb.separated(", ");
-                    for v in vals {
-                        sep.push_bind(v);
-                    }
-                    sep.push_unseparated(")");
+            if let Some(vals) = req.grip_width
+                && !vals.is_empty()
+            {
+                qb.push(" AND grip_width IN (");
+                let mut sep = qb.separated(", ");
+                for v in vals {
+                    sep.push_bind(v);
                 }
+                sep.push_unseparated(")");
             }
         }
     }
@@ -350,7 +363,7 @@ mod tests {
     use sqlx::SqlitePool;
 
     async fn setup_db() -> SqlitePool {
-        init_db(IN_MEMORY_DB_URL).await
+        init_db(IN_MEMORY_DB_URL).await.unwrap()
     }
 
     // Helper to create a valid request for an Upper Body Compound exercise
@@ -1,4 +1,5 @@
 use crate::db::pagination_support::{PaginationParams, PaginationRes};
+use crate::error::AppResult;
 use crate::exercise::exercise_dto::{
     ExerciseLibraryFilterReq, ExerciseLibraryReq, ExerciseLibraryRes, ValidExercise,
 };
@@ -19,40 +20,31 @@ impl ExerciseService {
         }
     }
 
-    pub async fn create(&self, req: ExerciseLibraryReq) -> Result<u32, String> {
-        let mut conn = match self.pool.begin().await {
-            Ok(conn) => conn,
-            Err(e) => return Err(e.to_string()),
-        };
+    pub async fn create(&self, req: ExerciseLibraryReq) -> AppResult<u32> {
+        let mut conn = self.pool.begin().await?;
 
         let res = self.repo.create_exercise(&mut conn, req).await;
-        conn.commit().await.unwrap();
+        conn.commit().await?;
         res
     }
 
-    pub async fn update(&self, valid_exercise: ValidExercise) -> Result<(), String> {
-        let mut conn = match self.pool.begin().await {
-            Ok(conn) => conn,
-            Err(e) => return Err(e.to_string()),
-        };
+    pub async fn update(&self, valid_exercise: ValidExercise) -> AppResult<()> {
+        let mut conn = self.pool.begin().await?;
 
         self.repo.update_exercise(&mut conn, valid_exercise).await?;
-        conn.commit().await.unwrap();
+        conn.commit().await?;
         Ok(())
     }
 
-    pub async fn delete(&self, exercise_id: u32) -> Result<(), String> {
-        let mut conn = match self.pool.begin().await {
-            Ok(conn) => conn,
-            Err(e) => return Err(e.to_string()),
-        };
+    pub async fn delete(&self, exercise_id: u32) -> AppResult<()> {
+        let mut conn = self.pool.begin().await?;
 
         self.repo.delete_exercise(&mut conn, exercise_id).await?;
-        conn.commit().await.unwrap();
+        conn.commit().await?;
         Ok(())
     }
 
-    pub async fn get_one(&self, exercise_id: u32) -> Result<ValidExercise, String> {
+    pub async fn get_one(&self, exercise_id: u32) -> AppResult<ValidExercise> {
         self.repo.get_one_exercise(&self.pool, exercise_id).await
     }
 
@@ -60,7 +52,7 @@ impl ExerciseService {
         &self,
         filter_req: Option<ExerciseLibraryFilterReq>,
         pagination_params: PaginationParams,
-    ) -> Result<PaginationRes<ExerciseLibraryRes>, String> {
+    ) -> AppResult<PaginationRes<ExerciseLibraryRes>> {
         self.repo
             .paginate_exercises(&self.pool, filter_req, pagination_params)
             .await
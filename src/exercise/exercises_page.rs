@@ -1,3 +1,4 @@
+use crate::client::app::PageAction;
 use crate::client::app_utils;
 use crate::client::app_utils::{CommonUiState, filter_combo, render_pagination};
 use crate::db::pagination_support::{PaginationRes, PaginationState};
@@ -5,12 +6,15 @@ use crate::enums::{
     CompoundOrIsolation, DynamicOrStatic, Grip, GripWidth, LeverVariation, PushOrPull,
     SquatOrHinge, StraightOrBentArm, UpperOrLower,
 };
+use crate::error::AppError;
 use crate::exercise::exercise_dto::{
     ExerciseLibraryFilterReq, ExerciseLibraryReq, ExerciseLibraryRes, ExerciseName, ValidExercise,
     exercise_library_default_req, exercise_to_req, get_exercise_id, get_exercise_name,
 };
 use crate::exercise::exercise_entity::ExerciseLibraryEntity;
 use crate::exercise::exercise_service::ExerciseService;
+use crate::settings::settings_dto::SettingsRes;
+use crate::workout_log::workout_log_dto::ExerciseHistoryTarget;
 use eframe::egui;
 use sqlx::{Pool, Sqlite};
 use std::sync::mpsc::{Receiver, Sender, channel};
@@ -39,7 +43,7 @@ pub enum ExercisesPageMsg {
     DetailLoaded(ValidExercise),
     Saved,
     Deleted,
-    Error(String),
+    Error(AppError),
 }
 
 impl ExercisesPage {
@@ -60,6 +64,17 @@ impl ExercisesPage {
     }
 }
 
+impl ExercisesPage {
+    pub fn apply_settings(&mut self, settings: &SettingsRes) {
+        if self
+            .pagination_state
+            .apply_default_limit(settings.pagination_limit)
+        {
+            self.trigger_list_refresh();
+        }
+    }
+}
+
 pub enum ExercisesPageState {
     DetailsClosed,
     DetailsOpenView,
@@ -84,13 +99,8 @@ impl ExercisesPage {
                 ExercisesPageMsg::Saved => {
                     self.common_ui_state
                         .show_success("Exercise saved successfully");
-                    if matches!(self.state, ExercisesPageState::CreateNew) {
-                        self.state = ExercisesPageState::DetailsClosed;
-                        self.trigger_list_refresh();
-                    } else {
-                        self.state = ExercisesPageState::DetailsClosed;
-                        self.trigger_list_refresh();
-                    }
+                    self.state = ExercisesPageState::DetailsClosed;
+                    self.trigger_list_refresh();
                 }
                 ExercisesPageMsg::Deleted => {
                     self.common_ui_state.show_success("Exercise deleted");
@@ -98,7 +108,7 @@ impl ExercisesPage {
                     self.trigger_list_refresh();
                 }
                 ExercisesPageMsg::Error(e) => {
-                    self.common_ui_state.show_error(&e);
+                    self.common_ui_state.show_app_error(&e);
                 }
             }
         }
@@ -157,7 +167,7 @@ impl ExercisesPage {
         let is_edit = matches!(self.state, ExercisesPageState::DetailsEditView);
         let ctx = ctx.clone();
         let service = self.service.clone();
-        let id_opt = self.current_detail.as_ref().map(|d| get_exercise_id(d));
+        let id_opt = self.current_detail.as_ref().map(get_exercise_id);
         tokio::spawn(async move {
             let res = if is_edit {
                 if let Some(id) = id_opt {
@@ -175,7 +185,7 @@ impl ExercisesPage {
                         Err(e) => Err(e),
                     }
                 } else {
-                    Err("No ID found for edit".to_string())
+                    Err(AppError::NotFound("Exercise"))
                 }
             } else {
                 service.create(req).await.map(|_| ())
@@ -216,7 +226,8 @@ impl ExercisesPage {
         });
     }
 
-    fn render_details_open_view(&mut self, ui: &mut egui::Ui) {
+    fn render_details_open_view(&mut self, ui: &mut egui::Ui) -> PageAction {
+        let mut page_action = PageAction::None;
         ui.heading("Exercise Details");
         ui.separator();
         if let Some(detail) = &self.current_detail {
@@ -313,13 +324,23 @@ impl ExercisesPage {
             if ui.button("Close").clicked() {
                 self.state = ExercisesPageState::DetailsClosed;
             }
-            if ui.button("Edit").clicked() {
-                if let Some(d) = &self.current_detail {
-                    self.form_data = exercise_to_req(d);
-                    self.state = ExercisesPageState::DetailsEditView;
-                }
+            if ui.button("Edit").clicked()
+                && let Some(d) = &self.current_detail
+            {
+                self.form_data = exercise_to_req(d);
+                self.state = ExercisesPageState::DetailsEditView;
+            }
+            if ui.button("History").clicked()
+                && let Some(d) = &self.current_detail
+            {
+                page_action = PageAction::GoToExerciseHistory(ExerciseHistoryTarget {
+                    exercise_library_id: Some(get_exercise_id(d)),
+                    exercise_name: d.full_name(),
+                });
             }
         });
+
+        page_action
     }
 
     fn render_form(&mut self, ui: &mut egui::Ui) {
@@ -608,7 +629,8 @@ impl ExercisesPage {
         }
     }
 
-    fn render_list(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+    fn render_list(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) -> PageAction {
+        let mut page_action = PageAction::None;
         ui.with_layout(egui::Layout::bottom_up(egui::Align::Min), |ui| {
             if render_pagination(ui, &mut self.pagination_state) {
                 self.trigger_list_refresh();
@@ -635,6 +657,7 @@ impl ExercisesPage {
                     Details(u32),
                     Edit(u32),
                     Delete(u32),
+                    History(ExerciseHistoryTarget),
                 }
 
                 let mut action = None;
@@ -651,7 +674,7 @@ impl ExercisesPage {
                             ui.end_row();
 
                             for item in &self.list_items {
-                                ui.label(&item.full_name());
+                                ui.label(item.full_name());
                                 ui.label(format!("{:?}", item.upper_or_lower));
                                 ui.label(format!("{:?}", item.compound_or_isolation));
 
@@ -665,6 +688,12 @@ impl ExercisesPage {
                                     if ui.button("Delete").clicked() {
                                         action = Some(ListAction::Delete(item.id));
                                     }
+                                    if ui.button("History").clicked() {
+                                        action = Some(ListAction::History(ExerciseHistoryTarget {
+                                            exercise_library_id: Some(item.id),
+                                            exercise_name: item.full_name(),
+                                        }));
+                                    }
                                 });
                                 ui.end_row();
                             }
@@ -693,13 +722,18 @@ impl ExercisesPage {
                                 }
                             });
                         }
+                        ListAction::History(target) => {
+                            page_action = PageAction::GoToExerciseHistory(target);
+                        }
                     }
                 }
             });
         });
+
+        page_action
     }
 
-    pub fn render_page(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+    pub fn render_page(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) -> PageAction {
         self.handle_async_messages();
 
         self.common_ui_state.show_toasts(ui);
@@ -714,8 +748,14 @@ impl ExercisesPage {
         match self.state {
             ExercisesPageState::DetailsClosed => self.render_list(ctx, ui),
             ExercisesPageState::DetailsOpenView => self.render_details_open_view(ui),
-            ExercisesPageState::DetailsEditView => self.render_details_edit_view(ctx, ui),
-            ExercisesPageState::CreateNew => self.render_create(ctx, ui),
+            ExercisesPageState::DetailsEditView => {
+                self.render_details_edit_view(ctx, ui);
+                PageAction::None
+            }
+            ExercisesPageState::CreateNew => {
+                self.render_create(ctx, ui);
+                PageAction::None
+            }
         }
     }
 }
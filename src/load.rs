@@ -0,0 +1,164 @@
+use crate::enums::{Band, WeightUnit};
+
+// what a set actually moves: a share of bodyweight plus (or minus) external load
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Load {
+    pub weight: f64, // added load, negative for assistance (e.g. assisted machine)
+    pub weight_unit: WeightUnit,
+    pub assistance_band: Option<Band>,
+    pub bodyweight_fraction: f64, // 0.0 for external only, 1.0 for full bodyweight
+}
+
+impl Load {
+    pub fn is_plain(&self) -> bool {
+        self.bodyweight_fraction == 0.0 && self.assistance_band.is_none()
+    }
+
+    // band assistance can't be quantified, so it isn't subtracted
+    pub fn effective_in(&self, unit: WeightUnit, bodyweight_kg: Option<f64>) -> f64 {
+        let bodyweight =
+            WeightUnit::Kg.convert(bodyweight_kg.unwrap_or(0.0), unit) * self.bodyweight_fraction;
+        bodyweight + self.weight_unit.convert(self.weight, unit)
+    }
+
+    // load x reps for one set. a static hold logs seconds, not reps, so it adds no volume;
+    // the stats query follows the same rule
+    pub fn volume_in(
+        &self,
+        unit: WeightUnit,
+        bodyweight_kg: Option<f64>,
+        reps_or_seconds: u8,
+        is_static: bool,
+    ) -> f64 {
+        if is_static {
+            return 0.0;
+        }
+        self.effective_in(unit, bodyweight_kg) * reps_or_seconds as f64
+    }
+
+    // "20 kg", "BW + 20 kg = 95 kg", "0.65 x BW", "BW - 15 kg = 60 kg", "BW - Red band",
+    // "Red band", "-15 kg - Red band"
+    pub fn describe(&self, unit: WeightUnit, bodyweight_kg: Option<f64>) -> String {
+        let weight = self.weight_unit.convert(self.weight, unit);
+        if self.is_plain() {
+            return unit.format(weight);
+        }
+
+        let mut parts = Vec::new();
+        if self.bodyweight_fraction == 1.0 {
+            parts.push("BW".to_string());
+        } else if self.bodyweight_fraction > 0.0 {
+            parts.push(format!("{} x BW", self.bodyweight_fraction));
+        }
+        // without bodyweight the first term stands on its own, a weight keeps its sign
+        if weight != 0.0 {
+            parts.push(match (parts.is_empty(), weight > 0.0) {
+                (true, _) => unit.format(weight),
+                (false, true) => format!("+ {}", unit.format(weight)),
+                (false, false) => format!("- {}", unit.format(-weight)),
+            });
+        }
+        if let Some(band) = self.assistance_band {
+            parts.push(if parts.is_empty() {
+                format!("{} band", band)
+            } else {
+                format!("- {} band", band)
+            });
+        }
+
+        let mut description = parts.join(" ");
+        if self.bodyweight_fraction > 0.0
+            && self.assistance_band.is_none()
+            && bodyweight_kg.is_some()
+        {
+            description = format!(
+                "{} = {}",
+                description,
+                unit.format(self.effective_in(unit, bodyweight_kg))
+            );
+        }
+        description
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(weight: f64, band: Option<Band>, fraction: f64) -> Load {
+        Load {
+            weight,
+            weight_unit: WeightUnit::Kg,
+            assistance_band: band,
+            bodyweight_fraction: fraction,
+        }
+    }
+
+    #[test]
+    fn test_effective_load() {
+        // weighted pull-up
+        assert_eq!(
+            load(20.0, None, 1.0).effective_in(WeightUnit::Kg, Some(75.0)),
+            95.0
+        );
+        // machine assisted pull-up
+        assert_eq!(
+            load(-15.0, None, 1.0).effective_in(WeightUnit::Kg, Some(75.0)),
+            60.0
+        );
+        // barbell lift ignores bodyweight
+        assert_eq!(
+            load(100.0, None, 0.0).effective_in(WeightUnit::Kg, Some(75.0)),
+            100.0
+        );
+        // unknown bodyweight counts as zero
+        assert_eq!(
+            load(20.0, None, 1.0).effective_in(WeightUnit::Kg, None),
+            20.0
+        );
+    }
+
+    #[test]
+    fn test_describe_load() {
+        assert_eq!(
+            load(100.0, None, 0.0).describe(WeightUnit::Kg, None),
+            "100 kg"
+        );
+        assert_eq!(
+            load(20.0, None, 1.0).describe(WeightUnit::Kg, Some(75.0)),
+            "BW + 20 kg = 95 kg"
+        );
+        assert_eq!(
+            load(-15.0, None, 1.0).describe(WeightUnit::Kg, Some(75.0)),
+            "BW - 15 kg = 60 kg"
+        );
+        assert_eq!(
+            load(0.0, Some(Band::Red), 1.0).describe(WeightUnit::Kg, Some(75.0)),
+            "BW - Red band"
+        );
+        assert_eq!(
+            load(0.0, None, 0.65).describe(WeightUnit::Kg, None),
+            "0.65 x BW"
+        );
+    }
+
+    #[test]
+    fn test_describe_load_without_bodyweight() {
+        assert_eq!(
+            load(0.0, Some(Band::Red), 0.0).describe(WeightUnit::Kg, Some(75.0)),
+            "Red band"
+        );
+        assert_eq!(
+            load(-15.0, Some(Band::Red), 0.0).describe(WeightUnit::Kg, Some(75.0)),
+            "-15 kg - Red band"
+        );
+        assert_eq!(
+            load(10.0, Some(Band::Red), 0.0).describe(WeightUnit::Kg, None),
+            "10 kg - Red band"
+        );
+        assert_eq!(
+            load(-15.0, None, 0.0).describe(WeightUnit::Kg, None),
+            "-15 kg"
+        );
+    }
+}
@@ -4,7 +4,13 @@ use std::error::Error;
 pub mod client;
 mod db;
 pub mod enums;
+pub mod error;
 pub mod exercise;
+pub mod load;
+pub mod personal_record;
+pub mod settings;
+pub mod stats;
+pub mod tempo;
 mod timer;
 pub mod workout;
 pub mod workout_log;
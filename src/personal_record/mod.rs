@@ -0,0 +1,5 @@
+pub mod personal_record_dto;
+pub mod personal_record_repo;
+pub mod personal_record_service;
+pub mod personal_records_page;
+pub mod record_detection;
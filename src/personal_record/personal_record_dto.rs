@@ -0,0 +1,89 @@
+use crate::enums::{RecordType, WeightUnit};
+use crate::workout_log::workout_log_dto::ExerciseHistoryTarget;
+use chrono::NaiveDate;
+use sqlx::FromRow;
+
+// a logged set as the record checks see it, the added load in kg with bodyweight left out
+// since bodyweight changes between sessions
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct RecordSetRes {
+    pub workout_log_id: u32,
+    pub exercise_library_id: Option<u32>,
+    pub exercise_name: String,
+    pub is_static: bool, // the linked library exercise is a hold, reps are seconds
+    pub rep_number_or_seconds: u8,
+    pub load_kg: f64,
+}
+
+impl RecordSetRes {
+    pub fn target(&self) -> ExerciseHistoryTarget {
+        ExerciseHistoryTarget {
+            exercise_library_id: self.exercise_library_id,
+            exercise_name: self.exercise_name.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersonalRecordReq {
+    pub workout_log_id: u32,
+    pub exercise_library_id: Option<u32>,
+    pub exercise_name: String,
+    pub record_type: RecordType,
+    pub value: f64,
+    pub load_kg: Option<f64>,
+    pub previous_value: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct PersonalRecordRes {
+    pub id: u32,
+    pub workout_log_id: u32,
+    pub workout_log_group_id: u32,
+    pub date: NaiveDate,
+    pub exercise_library_id: Option<u32>,
+    pub exercise_name: String,
+    pub record_type: RecordType,
+    pub value: f64,
+    pub load_kg: Option<f64>,
+    pub previous_value: f64,
+}
+
+impl PersonalRecordRes {
+    pub fn target(&self) -> ExerciseHistoryTarget {
+        ExerciseHistoryTarget {
+            exercise_library_id: self.exercise_library_id,
+            exercise_name: self.exercise_name.clone(),
+        }
+    }
+
+    // "100 kg", "12 reps @ 60 kg", "45 s"
+    pub fn describe(&self, unit: WeightUnit) -> String {
+        describe_value(self.record_type, self.value, self.load_kg, unit)
+    }
+
+    // "was 95 kg"
+    pub fn describe_previous(&self, unit: WeightUnit) -> String {
+        format!(
+            "was {}",
+            describe_value(self.record_type, self.previous_value, self.load_kg, unit)
+        )
+    }
+}
+
+fn describe_value(
+    record_type: RecordType,
+    value: f64,
+    load_kg: Option<f64>,
+    unit: WeightUnit,
+) -> String {
+    let weight = |kg: f64| unit.format(WeightUnit::Kg.convert(kg, unit));
+    match record_type {
+        RecordType::HeaviestWeight | RecordType::Estimated1rm => weight(value),
+        RecordType::MostReps => match load_kg {
+            Some(load_kg) => format!("{} reps @ {}", value, weight(load_kg)),
+            None => format!("{} reps", value),
+        },
+        RecordType::LongestHold => format!("{} s", value),
+    }
+}
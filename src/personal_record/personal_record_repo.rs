@@ -0,0 +1,423 @@
+use crate::db::{SqliteExecutor, SqliteTx};
+use crate::enums::WeightUnit;
+use crate::error::{AppError, AppResult};
+use crate::personal_record::personal_record_dto::{
+    PersonalRecordReq, PersonalRecordRes, RecordSetRes,
+};
+use crate::workout_log::workout_log_dto::ExerciseHistoryTarget;
+use chrono::Utc;
+use sqlx::QueryBuilder;
+
+const RECORD_SET_SELECT: &str = r#"
+    SELECT wl.id AS workout_log_id,
+           we.exercise_library_id,
+           wl.exercise_name,
+           COALESCE(el.dynamic_or_static = 'Static', 0) AS is_static,
+           wl.rep_number_or_seconds,
+           CASE wl.weight_unit WHEN 'Lb' THEN wl.weight * ? ELSE wl.weight END AS load_kg
+    FROM workout_logs wl
+    JOIN workout_exercises we ON wl.workout_exercise_id = we.id
+    LEFT JOIN exercise_library el ON we.exercise_library_id = el.id
+"#;
+
+const RECORD_SELECT: &str = r#"
+    SELECT pr.id,
+           pr.workout_log_id,
+           wlg.id AS workout_log_group_id,
+           wlg.date,
+           pr.exercise_library_id,
+           pr.exercise_name,
+           pr.record_type,
+           pr.value,
+           pr.load_kg,
+           pr.previous_value
+    FROM personal_records pr
+    JOIN workout_logs wl ON pr.workout_log_id = wl.id
+    JOIN workout_log_groups wlg ON wl.workout_log_group_id = wlg.id
+"#;
+
+#[derive(Clone, Copy)]
+pub struct PersonalRecordRepo {}
+
+impl Default for PersonalRecordRepo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PersonalRecordRepo {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub async fn create_record(
+        &self,
+        tx: &mut SqliteTx<'_>,
+        req: PersonalRecordReq,
+    ) -> AppResult<u32> {
+        let result = sqlx::query(
+            r#"INSERT INTO personal_records (
+            created_at, workout_log_id, exercise_library_id, exercise_name,
+            record_type, value, load_kg, previous_value
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)"#,
+        )
+        .bind(Utc::now())
+        .bind(req.workout_log_id)
+        .bind(req.exercise_library_id)
+        .bind(req.exercise_name)
+        .bind(req.record_type)
+        .bind(req.value)
+        .bind(req.load_kg)
+        .bind(req.previous_value)
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::db("Failed to create personal record"))?;
+
+        Ok(result.last_insert_rowid() as u32)
+    }
+
+    // records are detected again for sets whose session or earlier sessions were edited
+    pub async fn delete_records_by_workout_log_ids(
+        &self,
+        tx: &mut SqliteTx<'_>,
+        workout_log_ids: &[u32],
+    ) -> AppResult<()> {
+        if workout_log_ids.is_empty() {
+            return Ok(());
+        }
+        let mut qb = QueryBuilder::new("DELETE FROM personal_records WHERE workout_log_id IN (");
+        let mut separated = qb.separated(", ");
+        for id in workout_log_ids {
+            separated.push_bind(id);
+        }
+        separated.push_unseparated(")");
+        qb.build()
+            .execute(&mut **tx)
+            .await
+            .map_err(AppError::db_delete("Personal record"))?;
+
+        Ok(())
+    }
+
+    pub async fn get_record_sets_by_log_group_id<'e>(
+        &self,
+        executor: impl SqliteExecutor<'e>,
+        workout_log_group_id: u32,
+    ) -> AppResult<Vec<RecordSetRes>> {
+        let res = sqlx::query_as(&format!(
+            "{} WHERE wl.workout_log_group_id = ? ORDER BY wl.id",
+            RECORD_SET_SELECT
+        ))
+        .bind(WeightUnit::Lb.convert(1.0, WeightUnit::Kg))
+        .bind(workout_log_group_id)
+        .fetch_all(executor)
+        .await
+        .map_err(AppError::db("Database error"))?;
+
+        Ok(res)
+    }
+
+    // every set of the exercise from sessions dated before the given one, matched like the
+    // exercise history; sessions on the same day count as earlier when they were logged first
+    pub async fn get_earlier_record_sets<'e>(
+        &self,
+        executor: impl SqliteExecutor<'e>,
+        exercise: &ExerciseHistoryTarget,
+        log_group_id: u32,
+    ) -> AppResult<Vec<RecordSetRes>> {
+        let res = sqlx::query_as(&format!(
+            r#"{}
+                JOIN workout_log_groups wlg ON wl.workout_log_group_id = wlg.id
+                WHERE (wlg.date, wlg.id) < (SELECT date, id FROM workout_log_groups WHERE id = ?)
+                  AND (wl.exercise_name = ? COLLATE NOCASE OR we.exercise_library_id = ?)
+                "#,
+            RECORD_SET_SELECT
+        ))
+        .bind(WeightUnit::Lb.convert(1.0, WeightUnit::Kg))
+        .bind(log_group_id)
+        .bind(&exercise.exercise_name)
+        .bind(exercise.exercise_library_id)
+        .fetch_all(executor)
+        .await
+        .map_err(AppError::db("Database error"))?;
+
+        Ok(res)
+    }
+
+    pub async fn get_records_by_log_group_id<'e>(
+        &self,
+        executor: impl SqliteExecutor<'e>,
+        workout_log_group_id: u32,
+    ) -> AppResult<Vec<PersonalRecordRes>> {
+        let res = sqlx::query_as(&format!(
+            "{} WHERE wlg.id = ? ORDER BY pr.id",
+            RECORD_SELECT
+        ))
+        .bind(workout_log_group_id)
+        .fetch_all(executor)
+        .await
+        .map_err(AppError::db("Database error"))?;
+
+        Ok(res)
+    }
+
+    // newest first
+    pub async fn get_all_records<'e>(
+        &self,
+        executor: impl SqliteExecutor<'e>,
+    ) -> AppResult<Vec<PersonalRecordRes>> {
+        let res = sqlx::query_as(&format!(
+            "{} ORDER BY wlg.date DESC, pr.id DESC",
+            RECORD_SELECT
+        ))
+        .fetch_all(executor)
+        .await
+        .map_err(AppError::db("Database error"))?;
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::{IN_MEMORY_DB_URL, init_db};
+    use crate::enums::{RecordType, WeightUnit};
+    use crate::personal_record::personal_record_dto::PersonalRecordReq;
+    use crate::personal_record::personal_record_repo::PersonalRecordRepo;
+    use crate::workout_log::workout_log_dto::ExerciseHistoryTarget;
+    use chrono::{NaiveDate, Utc};
+    use sqlx::SqlitePool;
+
+    async fn setup_db() -> SqlitePool {
+        init_db(IN_MEMORY_DB_URL).await.unwrap()
+    }
+
+    // (day, exercise, reps, weight, unit), returns the log ids
+    async fn seed_logs(
+        pool: &SqlitePool,
+        library_id: Option<i64>,
+        sets: &[(u32, &str, u8, f64, WeightUnit)],
+    ) -> Vec<u32> {
+        let workout_id = sqlx::query(
+            "INSERT INTO workouts (created_at, name, active) VALUES (?, 'Test Workout', 1)",
+        )
+        .bind(Utc::now())
+        .execute(pool)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+
+        let mut log_ids = Vec::new();
+        for (day, exercise_name, reps, weight, unit) in sets {
+            let workout_exercise_id = sqlx::query(
+                r#"INSERT INTO workout_exercises (
+                    created_at, workout_id, code, name, sets_target, reps_or_seconds_target,
+                    working_weight, rest_period_seconds, tempo, emom, equipments, bands,
+                    exercise_library_id
+                ) VALUES (?, ?, 'A1', ?, 3, 5, 0, 90, '', 0, '[]', '[]', ?)"#,
+            )
+            .bind(Utc::now())
+            .bind(workout_id)
+            .bind(exercise_name)
+            .bind(library_id)
+            .execute(pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+
+            let date = NaiveDate::from_ymd_opt(2024, 3, *day).unwrap();
+            let group_id: i64 =
+                match sqlx::query_scalar("SELECT id FROM workout_log_groups WHERE date = ?")
+                    .bind(date)
+                    .fetch_optional(pool)
+                    .await
+                    .unwrap()
+                {
+                    Some(id) => id,
+                    None => sqlx::query(
+                        "INSERT INTO workout_log_groups (created_at, date) VALUES (?, ?)",
+                    )
+                    .bind(Utc::now())
+                    .bind(date)
+                    .execute(pool)
+                    .await
+                    .unwrap()
+                    .last_insert_rowid(),
+                };
+
+            let log_id = sqlx::query(
+                r#"INSERT INTO workout_logs (
+                    workout_id, workout_exercise_id, workout_log_group_id, exercise_name,
+                    set_number, rep_number_or_seconds, weight, weight_unit
+                ) VALUES (?, ?, ?, ?, 1, ?, ?, ?)"#,
+            )
+            .bind(workout_id)
+            .bind(workout_exercise_id)
+            .bind(group_id)
+            .bind(exercise_name)
+            .bind(reps)
+            .bind(weight)
+            .bind(unit)
+            .execute(pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+            log_ids.push(log_id as u32);
+        }
+        log_ids
+    }
+
+    #[tokio::test]
+    async fn test_record_sets() {
+        let pool = setup_db().await;
+        let repo = PersonalRecordRepo::new();
+        let library_id = sqlx::query(
+            r#"INSERT INTO exercise_library (name, dynamic_or_static, upper_or_lower, compound_or_isolation)
+               VALUES ('Plank', 'Static', 'Lower', 'Isolation')"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+
+        seed_logs(
+            &pool,
+            Some(library_id),
+            &[(1, "Front Plank", 60, 0.0, WeightUnit::Kg)],
+        )
+        .await;
+        seed_logs(
+            &pool,
+            None,
+            &[
+                (1, "Squat", 5, 100.0, WeightUnit::Kg),
+                (
+                    8,
+                    "squat",
+                    3,
+                    WeightUnit::Kg.convert(110.0, WeightUnit::Lb),
+                    WeightUnit::Lb,
+                ),
+                (8, "Front Squat", 5, 80.0, WeightUnit::Kg),
+            ],
+        )
+        .await;
+
+        let session = repo
+            .get_record_sets_by_log_group_id(&pool, 2)
+            .await
+            .expect("Failed to load session sets");
+        assert_eq!(session.len(), 2);
+        assert!(!session[0].is_static);
+        assert!((session[0].load_kg - 110.0).abs() < 1e-6);
+
+        // the plank matches by its library id under a different name, squat by name only
+        let plank = ExerciseHistoryTarget {
+            exercise_library_id: Some(library_id as u32),
+            exercise_name: "Plank".to_string(),
+        };
+        let earlier = repo
+            .get_earlier_record_sets(&pool, &plank, 2)
+            .await
+            .unwrap();
+        assert_eq!(earlier.len(), 1);
+        assert!(earlier[0].is_static);
+        assert_eq!(earlier[0].rep_number_or_seconds, 60);
+
+        let earlier = repo
+            .get_earlier_record_sets(&pool, &session[0].target(), 2)
+            .await
+            .unwrap();
+        assert_eq!(earlier.len(), 1);
+        assert_eq!(earlier[0].exercise_name, "Squat");
+        assert_eq!(earlier[0].load_kg, 100.0);
+    }
+
+    #[tokio::test]
+    async fn test_earlier_record_sets_go_by_session_date() {
+        let pool = setup_db().await;
+        let repo = PersonalRecordRepo::new();
+        // the 1st is logged after the 8th, it is still the earlier session
+        seed_logs(
+            &pool,
+            None,
+            &[
+                (8, "Squat", 5, 105.0, WeightUnit::Kg),
+                (1, "Squat", 5, 100.0, WeightUnit::Kg),
+            ],
+        )
+        .await;
+        let squat = ExerciseHistoryTarget {
+            exercise_library_id: None,
+            exercise_name: "Squat".to_string(),
+        };
+
+        let before_backdated = repo
+            .get_earlier_record_sets(&pool, &squat, 2)
+            .await
+            .unwrap();
+        assert!(before_backdated.is_empty());
+
+        let before_later = repo
+            .get_earlier_record_sets(&pool, &squat, 1)
+            .await
+            .unwrap();
+        assert_eq!(before_later.len(), 1);
+        assert_eq!(before_later[0].load_kg, 100.0);
+    }
+
+    #[tokio::test]
+    async fn test_records_follow_their_logs() {
+        let pool = setup_db().await;
+        let repo = PersonalRecordRepo::new();
+        let log_ids = seed_logs(
+            &pool,
+            None,
+            &[
+                (1, "Squat", 5, 100.0, WeightUnit::Kg),
+                (8, "Squat", 5, 105.0, WeightUnit::Kg),
+            ],
+        )
+        .await;
+
+        let mut tx = pool.begin().await.unwrap();
+        repo.create_record(
+            &mut tx,
+            PersonalRecordReq {
+                workout_log_id: log_ids[1],
+                exercise_library_id: None,
+                exercise_name: "Squat".to_string(),
+                record_type: RecordType::HeaviestWeight,
+                value: 105.0,
+                load_kg: None,
+                previous_value: 100.0,
+            },
+        )
+        .await
+        .expect("Failed to create personal record");
+        tx.commit().await.unwrap();
+
+        let records = repo.get_records_by_log_group_id(&pool, 2).await.unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].record_type, RecordType::HeaviestWeight);
+        assert_eq!(
+            records[0].date,
+            NaiveDate::from_ymd_opt(2024, 3, 8).unwrap()
+        );
+        assert_eq!(records[0].describe(WeightUnit::Kg), "105 kg");
+        assert!(
+            repo.get_records_by_log_group_id(&pool, 1)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // deleting the set takes its record with it
+        sqlx::query("DELETE FROM workout_logs WHERE id = ?")
+            .bind(log_ids[1])
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(repo.get_all_records(&pool).await.unwrap().is_empty());
+    }
+}
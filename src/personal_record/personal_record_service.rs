@@ -0,0 +1,34 @@
+use crate::error::AppResult;
+use crate::personal_record::personal_record_dto::PersonalRecordRes;
+use crate::personal_record::personal_record_repo::PersonalRecordRepo;
+use sqlx::{Pool, Sqlite};
+
+#[derive(Clone)]
+pub struct PersonalRecordService {
+    pool: Pool<Sqlite>,
+    repo: PersonalRecordRepo,
+}
+
+impl PersonalRecordService {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self {
+            pool,
+            repo: PersonalRecordRepo::new(),
+        }
+    }
+
+    pub async fn get_all_records(&self) -> AppResult<Vec<PersonalRecordRes>> {
+        self.repo
+            .get_all_records(&mut *self.pool.acquire().await?)
+            .await
+    }
+
+    pub async fn get_records_by_log_group_id(
+        &self,
+        workout_log_group_id: u32,
+    ) -> AppResult<Vec<PersonalRecordRes>> {
+        self.repo
+            .get_records_by_log_group_id(&mut *self.pool.acquire().await?, workout_log_group_id)
+            .await
+    }
+}
@@ -0,0 +1,240 @@
+use crate::client::app::PageAction;
+use crate::client::app_utils::CommonUiState;
+use crate::enums::{RecordType, WeightUnit};
+use crate::error::AppError;
+use crate::personal_record::personal_record_dto::PersonalRecordRes;
+use crate::personal_record::personal_record_service::PersonalRecordService;
+use crate::settings::settings_dto::SettingsRes;
+use eframe::egui;
+use sqlx::{Pool, Sqlite};
+use std::collections::BTreeMap;
+use std::sync::mpsc::{Receiver, Sender, channel};
+
+const RECENT_RECORDS: usize = 20;
+
+// the standing record of each type for one exercise
+struct ExerciseRecords {
+    exercise_name: String,
+    latest: Vec<Option<PersonalRecordRes>>, // indexed like RecordType::ALL
+}
+
+pub struct PersonalRecordsPage {
+    service: PersonalRecordService,
+    // Data
+    records: Vec<PersonalRecordRes>, // newest first
+    board: Vec<ExerciseRecords>,
+    // Display unit for weights
+    weight_unit: WeightUnit,
+    // Async Communication
+    receiver: Receiver<PersonalRecordsPageMsg>,
+    sender: Sender<PersonalRecordsPageMsg>,
+    // UI Status
+    common_ui_state: CommonUiState,
+}
+
+pub enum PersonalRecordsPageMsg {
+    Loaded(Vec<PersonalRecordRes>),
+    Error(AppError),
+}
+
+impl PersonalRecordsPage {
+    pub fn default(pool: Pool<Sqlite>) -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            service: PersonalRecordService::new(pool),
+            records: Vec::new(),
+            board: Vec::new(),
+            weight_unit: WeightUnit::default(),
+            receiver,
+            sender,
+            common_ui_state: CommonUiState::default(),
+        }
+    }
+}
+
+impl PersonalRecordsPage {
+    pub fn apply_settings(&mut self, settings: &SettingsRes) {
+        self.weight_unit = settings.weight_unit;
+    }
+
+    // sessions may have set records since the board was loaded
+    pub fn refresh(&mut self) {
+        self.common_ui_state.not_initialized();
+    }
+}
+
+impl PersonalRecordsPage {
+    fn handle_async_messages(&mut self) {
+        while let Ok(msg) = self.receiver.try_recv() {
+            self.common_ui_state.set_as_not_loading();
+            match msg {
+                PersonalRecordsPageMsg::Loaded(records) => {
+                    self.board = build_board(&records);
+                    self.records = records;
+                }
+                PersonalRecordsPageMsg::Error(err) => {
+                    self.common_ui_state.show_app_error(&err);
+                }
+            }
+        }
+    }
+
+    fn fetch_records(&mut self, ctx: &egui::Context) {
+        if self.common_ui_state.initialized || self.common_ui_state.loading {
+            return;
+        }
+        self.common_ui_state.set_as_loading();
+        self.common_ui_state.initialize();
+
+        let service = self.service.clone();
+        let sender = self.sender.clone();
+        let ctx = ctx.clone();
+
+        tokio::spawn(async move {
+            match service.get_all_records().await {
+                Ok(records) => {
+                    let _ = sender.send(PersonalRecordsPageMsg::Loaded(records));
+                }
+                Err(e) => {
+                    let _ = sender.send(PersonalRecordsPageMsg::Error(e));
+                }
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    fn render_board(&self, ui: &mut egui::Ui) -> PageAction {
+        let mut page_action = PageAction::None;
+
+        ui.heading("Current Records");
+        egui::Grid::new("personal_records_board_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Exercise");
+                for record_type in RecordType::ALL {
+                    ui.label(record_type.to_string());
+                }
+                ui.label("");
+                ui.end_row();
+
+                for row in &self.board {
+                    ui.label(&row.exercise_name);
+                    for record in &row.latest {
+                        match record {
+                            Some(record) => {
+                                ui.label(record.describe(self.weight_unit))
+                                    .on_hover_text(format!(
+                                        "{}, {}",
+                                        record.date,
+                                        record.describe_previous(self.weight_unit)
+                                    ));
+                            }
+                            None => {
+                                ui.label("-");
+                            }
+                        }
+                    }
+                    if let Some(record) = row.latest.iter().flatten().next()
+                        && ui.button("History").clicked()
+                    {
+                        page_action = PageAction::GoToExerciseHistory(record.target());
+                    }
+                    ui.end_row();
+                }
+            });
+
+        page_action
+    }
+
+    fn render_recent(&self, ui: &mut egui::Ui) -> PageAction {
+        let mut page_action = PageAction::None;
+
+        ui.heading("Recent PRs");
+        egui::Grid::new("personal_records_recent_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Date");
+                ui.label("Exercise");
+                ui.label("Record");
+                ui.label("Value");
+                ui.label("Previous");
+                ui.label("");
+                ui.end_row();
+
+                for record in self.records.iter().take(RECENT_RECORDS) {
+                    ui.label(record.date.to_string());
+                    ui.label(&record.exercise_name);
+                    ui.label(record.record_type.to_string());
+                    ui.label(
+                        egui::RichText::new(record.describe(self.weight_unit))
+                            .color(egui::Color32::GOLD)
+                            .strong(),
+                    );
+                    ui.label(record.describe_previous(self.weight_unit));
+                    if ui.button("View").clicked() {
+                        page_action = PageAction::GoToWorkoutLog(record.workout_log_group_id);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        page_action
+    }
+
+    pub fn render_page(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) -> PageAction {
+        self.handle_async_messages();
+        self.fetch_records(ctx);
+
+        let mut page_action = PageAction::None;
+
+        ui.horizontal(|ui| {
+            ui.heading("Personal Records");
+            if ui.button("Refresh").clicked() {
+                self.refresh();
+            }
+            if self.common_ui_state.loading {
+                ui.spinner();
+            }
+        });
+        ui.separator();
+        self.common_ui_state.show_toasts(ui);
+
+        if self.records.is_empty() {
+            ui.label("Records show up here once a session beats an earlier one.");
+            return page_action;
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            if let action @ PageAction::GoToExerciseHistory(_) = self.render_board(ui) {
+                page_action = action;
+            }
+            ui.add_space(20.0);
+            if let action @ PageAction::GoToWorkoutLog(_) = self.render_recent(ui) {
+                page_action = action;
+            }
+        });
+
+        page_action
+    }
+}
+
+// records come newest first, so the first of each type per exercise is the standing one
+fn build_board(records: &[PersonalRecordRes]) -> Vec<ExerciseRecords> {
+    let mut board: BTreeMap<String, ExerciseRecords> = BTreeMap::new();
+    for record in records {
+        let row = board
+            .entry(record.exercise_name.to_lowercase())
+            .or_insert_with(|| ExerciseRecords {
+                exercise_name: record.exercise_name.clone(),
+                latest: vec![None; RecordType::ALL.len()],
+            });
+        let index = RecordType::ALL
+            .iter()
+            .position(|t| *t == record.record_type)
+            .unwrap_or_default();
+        if row.latest[index].is_none() {
+            row.latest[index] = Some(record.clone());
+        }
+    }
+    board.into_values().collect()
+}
@@ -0,0 +1,195 @@
+use crate::enums::RecordType;
+use crate::personal_record::personal_record_dto::{PersonalRecordReq, RecordSetRes};
+use std::collections::BTreeMap;
+
+// loads within this are the same load, lb entries don't convert to round kg
+const LOAD_EPSILON: f64 = 1e-6;
+
+pub fn epley_1rm(load_kg: f64, reps: u8) -> Option<f64> {
+    match reps {
+        0 => None,
+        _ if load_kg <= 0.0 => None,
+        1 => Some(load_kg),
+        reps => Some(load_kg * (1.0 + reps as f64 / 30.0)),
+    }
+}
+
+// the records one exercise's session sets beat, against that exercise's earlier sets.
+// a record needs something to beat, so the first session of an exercise sets none
+pub fn detect_records(
+    session: &[RecordSetRes],
+    history: &[RecordSetRes],
+) -> Vec<PersonalRecordReq> {
+    let Some(first) = session.first() else {
+        return Vec::new();
+    };
+    let is_static = session.iter().any(|set| set.is_static);
+    let mut records = Vec::new();
+
+    let mut push_best = |record_type: RecordType,
+                         value: fn(&RecordSetRes) -> Option<f64>,
+                         load_kg: Option<f64>,
+                         session: &[&RecordSetRes],
+                         history: &[&RecordSetRes]| {
+        let previous = history.iter().filter_map(|set| value(set)).reduce(f64::max);
+        let best = session
+            .iter()
+            .filter_map(|set| value(set).map(|v| (*set, v)))
+            .reduce(|best, next| if next.1 > best.1 { next } else { best });
+        if let (Some(previous), Some((set, value))) = (previous, best)
+            && value > previous + LOAD_EPSILON
+        {
+            records.push(PersonalRecordReq {
+                workout_log_id: set.workout_log_id,
+                exercise_library_id: first.exercise_library_id,
+                exercise_name: first.exercise_name.clone(),
+                record_type,
+                value,
+                load_kg,
+                previous_value: previous,
+            });
+        }
+    };
+
+    let session_sets: Vec<&RecordSetRes> = session.iter().collect();
+    let history_sets: Vec<&RecordSetRes> = history.iter().collect();
+
+    push_best(
+        RecordType::HeaviestWeight,
+        |set| (set.rep_number_or_seconds > 0).then_some(set.load_kg),
+        None,
+        &session_sets,
+        &history_sets,
+    );
+
+    if is_static {
+        push_best(
+            RecordType::LongestHold,
+            |set| Some(set.rep_number_or_seconds as f64),
+            None,
+            &session_sets,
+            &history_sets,
+        );
+        return records;
+    }
+
+    push_best(
+        RecordType::Estimated1rm,
+        |set| epley_1rm(set.load_kg, set.rep_number_or_seconds),
+        None,
+        &session_sets,
+        &history_sets,
+    );
+
+    // most reps is per load, keyed to the hundredth like the stats rep records
+    let mut by_load: BTreeMap<i64, (Vec<&RecordSetRes>, Vec<&RecordSetRes>)> = BTreeMap::new();
+    for set in session {
+        by_load
+            .entry(load_key(set.load_kg))
+            .or_default()
+            .0
+            .push(set);
+    }
+    for set in history {
+        if let Some((_, history_at_load)) = by_load.get_mut(&load_key(set.load_kg)) {
+            history_at_load.push(set);
+        }
+    }
+    for (key, (session_at_load, history_at_load)) in by_load {
+        push_best(
+            RecordType::MostReps,
+            |set| (set.rep_number_or_seconds > 0).then_some(set.rep_number_or_seconds as f64),
+            Some(key as f64 / 100.0),
+            &session_at_load,
+            &history_at_load,
+        );
+    }
+
+    records
+}
+
+fn load_key(load_kg: f64) -> i64 {
+    (load_kg * 100.0).round() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(workout_log_id: u32, reps: u8, load_kg: f64) -> RecordSetRes {
+        RecordSetRes {
+            workout_log_id,
+            exercise_library_id: None,
+            exercise_name: "Squat".to_string(),
+            is_static: false,
+            rep_number_or_seconds: reps,
+            load_kg,
+        }
+    }
+
+    fn types(records: &[PersonalRecordReq]) -> Vec<RecordType> {
+        records.iter().map(|r| r.record_type).collect()
+    }
+
+    #[test]
+    fn test_epley() {
+        assert_eq!(epley_1rm(100.0, 1), Some(100.0));
+        assert_eq!(epley_1rm(60.0, 15), Some(90.0));
+        assert_eq!(epley_1rm(100.0, 0), None);
+        assert_eq!(epley_1rm(-10.0, 5), None);
+    }
+
+    #[test]
+    fn test_first_session_sets_no_records() {
+        assert!(detect_records(&[set(1, 5, 100.0)], &[]).is_empty());
+    }
+
+    #[test]
+    fn test_heavier_set_and_more_reps() {
+        let history = [set(1, 5, 100.0), set(2, 8, 80.0)];
+        let session = [set(3, 3, 105.0), set(4, 10, 80.0), set(5, 4, 100.0)];
+        let records = detect_records(&session, &history);
+        assert_eq!(
+            types(&records),
+            vec![RecordType::HeaviestWeight, RecordType::MostReps]
+        );
+
+        let heaviest = &records[0];
+        assert_eq!(heaviest.workout_log_id, 3);
+        assert_eq!(heaviest.value, 105.0);
+        assert_eq!(heaviest.previous_value, 100.0);
+
+        // 4 reps at 100 doesn't beat 5, 10 at 80 beats 8
+        let reps = &records[1];
+        assert_eq!(reps.workout_log_id, 4);
+        assert_eq!(reps.value, 10.0);
+        assert_eq!(reps.load_kg, Some(80.0));
+    }
+
+    #[test]
+    fn test_estimated_1rm_without_a_heavier_set() {
+        // 100 x 5 = 116.7 beats 110 x 1 = 110
+        let records = detect_records(&[set(2, 5, 100.0)], &[set(1, 1, 110.0)]);
+        assert_eq!(types(&records), vec![RecordType::Estimated1rm]);
+        assert!((records[0].value - 100.0 * (1.0 + 5.0 / 30.0)).abs() < 1e-9);
+        assert_eq!(records[0].previous_value, 110.0);
+    }
+
+    #[test]
+    fn test_ties_are_not_records() {
+        let records = detect_records(&[set(2, 5, 100.0)], &[set(1, 5, 100.0)]);
+        assert!(records.is_empty());
+    }
+
+    #[test]
+    fn test_static_holds() {
+        let hold = |id, seconds, load_kg| RecordSetRes {
+            is_static: true,
+            ..set(id, seconds, load_kg)
+        };
+        let records = detect_records(&[hold(3, 30, 0.0)], &[hold(1, 20, 0.0), hold(2, 25, 0.0)]);
+        assert_eq!(types(&records), vec![RecordType::LongestHold]);
+        assert_eq!(records[0].value, 30.0);
+        assert_eq!(records[0].previous_value, 25.0);
+    }
+}
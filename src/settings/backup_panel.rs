@@ -0,0 +1,205 @@
+use crate::client::app_utils::CommonUiState;
+use crate::db::backup::{BackupInfo, BackupService};
+use crate::error::AppError;
+use eframe::egui;
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, Sender, channel};
+
+pub enum BackupPanelMsg {
+    Listed(Vec<BackupInfo>),
+    BackedUp(PathBuf),
+    RestoreStaged(String),
+    Error(AppError),
+}
+
+// lists the backups next to the database and stages a restore for the next start
+pub struct BackupPanel {
+    service: BackupService,
+    backups: Vec<BackupInfo>,
+    confirm_restore: Option<BackupInfo>,
+    restore_staged: Option<String>, // name of the backup restored on the next start
+    receiver: Receiver<BackupPanelMsg>,
+    sender: Sender<BackupPanelMsg>,
+    common_ui_state: CommonUiState,
+}
+
+impl BackupPanel {
+    pub fn new(service: BackupService) -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            service,
+            backups: Vec::new(),
+            confirm_restore: None,
+            restore_staged: None,
+            receiver,
+            sender,
+            common_ui_state: CommonUiState::default(),
+        }
+    }
+
+    fn handle_async_messages(&mut self) {
+        while let Ok(msg) = self.receiver.try_recv() {
+            self.common_ui_state.set_as_not_loading();
+            match msg {
+                BackupPanelMsg::Listed(backups) => {
+                    self.backups = backups;
+                }
+                BackupPanelMsg::BackedUp(path) => {
+                    self.common_ui_state
+                        .show_success(&format!("Backed up to {}", path.display()));
+                    self.common_ui_state.not_initialized();
+                }
+                BackupPanelMsg::RestoreStaged(name) => {
+                    self.restore_staged = Some(name);
+                }
+                BackupPanelMsg::Error(e) => {
+                    self.common_ui_state.show_app_error(&e);
+                }
+            }
+        }
+    }
+
+    fn fetch_list(&mut self, ctx: &egui::Context) {
+        if self.common_ui_state.loading {
+            return;
+        }
+        self.common_ui_state.set_as_loading();
+        self.common_ui_state.initialize();
+
+        let sender = self.sender.clone();
+        let ctx = ctx.clone();
+        let service = self.service.clone();
+        tokio::spawn(async move {
+            match service.list_backups().await {
+                Ok(backups) => {
+                    let _ = sender.send(BackupPanelMsg::Listed(backups));
+                }
+                Err(e) => {
+                    let _ = sender.send(BackupPanelMsg::Error(e));
+                }
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    fn backup_now(&mut self, ctx: &egui::Context) {
+        self.common_ui_state.set_as_loading();
+        let sender = self.sender.clone();
+        let ctx = ctx.clone();
+        let service = self.service.clone();
+        tokio::spawn(async move {
+            match service.backup_now().await {
+                Ok(path) => {
+                    let _ = sender.send(BackupPanelMsg::BackedUp(path));
+                }
+                Err(e) => {
+                    let _ = sender.send(BackupPanelMsg::Error(e));
+                }
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    fn stage_restore(&mut self, ctx: &egui::Context, backup: BackupInfo) {
+        self.common_ui_state.set_as_loading();
+        let sender = self.sender.clone();
+        let ctx = ctx.clone();
+        let service = self.service.clone();
+        tokio::spawn(async move {
+            match service.stage_restore(backup.path).await {
+                Ok(_) => {
+                    let _ = sender.send(BackupPanelMsg::RestoreStaged(backup.name));
+                }
+                Err(e) => {
+                    let _ = sender.send(BackupPanelMsg::Error(e));
+                }
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        self.handle_async_messages();
+        if !self.common_ui_state.initialized {
+            self.fetch_list(ctx);
+        }
+
+        if let Some(name) = &self.restore_staged {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                format!("{} will be restored when the app restarts", name),
+            );
+            if ui.button("Quit Now").clicked() {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+            ui.separator();
+        }
+
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    !self.common_ui_state.loading,
+                    egui::Button::new("Back Up Now"),
+                )
+                .clicked()
+            {
+                self.backup_now(ctx);
+            }
+            if ui.button("Refresh").clicked() {
+                self.common_ui_state.not_initialized();
+            }
+            if self.common_ui_state.loading {
+                ui.spinner();
+            }
+        });
+        self.common_ui_state.show_toasts(ui);
+
+        if self.backups.is_empty() {
+            ui.label("No backups yet.");
+        }
+        egui::Grid::new("backups_grid")
+            .striped(true)
+            .num_columns(4)
+            .show(ui, |ui| {
+                for backup in &self.backups {
+                    ui.label(&backup.name);
+                    ui.label(
+                        backup
+                            .modified
+                            .map(|at| at.format("%Y-%m-%d %H:%M").to_string())
+                            .unwrap_or_default(),
+                    );
+                    ui.label(format!("{:.1} MB", backup.size_bytes as f64 / 1_000_000.0));
+                    if ui.button("Restore").clicked() {
+                        self.confirm_restore = Some(backup.clone());
+                    }
+                    ui.end_row();
+                }
+            });
+
+        self.render_restore_confirmation(ctx);
+    }
+
+    fn render_restore_confirmation(&mut self, ctx: &egui::Context) {
+        let Some(backup) = self.confirm_restore.clone() else {
+            return;
+        };
+        egui::Window::new("Restore from backup")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(format!("Replace the current database with {}?", backup.name));
+                ui.label("The current database is backed up first. The restore finishes when the app restarts.");
+                ui.horizontal(|ui| {
+                    if ui.button("Restore").clicked() {
+                        self.confirm_restore = None;
+                        self.stage_restore(ctx, backup.clone());
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.confirm_restore = None;
+                    }
+                });
+            });
+    }
+}
@@ -0,0 +1,106 @@
+use crate::settings::settings_dto::SettingsRes;
+use crate::timer::audio_engine::AudioEngine;
+use crate::timer::cue::{Cue, CueSound, Tone, load_sample};
+use eframe::egui;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const PREVIEW_VOLUME: f32 = 0.5;
+
+// picks a tone or a WAV/OGG sample for every timer cue
+#[derive(Default)]
+pub struct CueSettingsPanel {
+    path_inputs: HashMap<Cue, String>, // present while a custom file is being chosen
+    errors: HashMap<Cue, String>,
+}
+
+impl CueSettingsPanel {
+    // returns true when a choice changed and should be saved
+    pub fn render(
+        &mut self,
+        ui: &mut egui::Ui,
+        settings: &mut SettingsRes,
+        audio: &AudioEngine,
+    ) -> bool {
+        let mut changed = ui
+            .checkbox(
+                &mut settings.countdown_beeps,
+                "3-2-1 countdown before a timer finishes",
+            )
+            .changed();
+        ui.separator();
+
+        egui::Grid::new("cue_settings_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                for cue in Cue::ALL {
+                    let current = settings.cue_sounds.sound_for(cue);
+                    ui.label(cue.description());
+
+                    let selected_text = match &current {
+                        _ if self.path_inputs.contains_key(&cue) => "Custom file...".to_string(),
+                        CueSound::Tone(tone) => tone.to_string(),
+                        CueSound::File(path) => path
+                            .file_name()
+                            .map(|name| name.to_string_lossy().to_string())
+                            .unwrap_or_default(),
+                    };
+                    egui::ComboBox::from_id_salt(("cue_sound", cue))
+                        .selected_text(selected_text)
+                        .width(220.0)
+                        .show_ui(ui, |ui| {
+                            for tone in Tone::ALL {
+                                let is_selected = current == CueSound::Tone(tone)
+                                    && !self.path_inputs.contains_key(&cue);
+                                if ui.selectable_label(is_selected, tone.to_string()).clicked() {
+                                    settings.cue_sounds.set(cue, CueSound::Tone(tone));
+                                    self.path_inputs.remove(&cue);
+                                    self.errors.remove(&cue);
+                                    changed = true;
+                                }
+                            }
+                            if ui.selectable_label(false, "Custom file...").clicked() {
+                                let path = match &current {
+                                    CueSound::File(path) => path.display().to_string(),
+                                    CueSound::Tone(_) => String::new(),
+                                };
+                                self.path_inputs.insert(cue, path);
+                            }
+                        });
+
+                    if ui.button("Play").clicked() {
+                        audio.preview(cue, PREVIEW_VOLUME);
+                    }
+                    ui.end_row();
+
+                    if let Some(path) = self.path_inputs.get_mut(&cue) {
+                        ui.label("");
+                        ui.add(egui::TextEdit::singleline(path).hint_text("/path/to/sound.wav"));
+                        if ui.button("Load").clicked() {
+                            let path = PathBuf::from(path.trim());
+                            match load_sample(&path) {
+                                Ok(_) => {
+                                    settings.cue_sounds.set(cue, CueSound::File(path));
+                                    self.path_inputs.remove(&cue);
+                                    self.errors.remove(&cue);
+                                    changed = true;
+                                }
+                                Err(e) => {
+                                    self.errors.insert(cue, e);
+                                }
+                            }
+                        }
+                        ui.end_row();
+                    }
+
+                    if let Some(error) = self.errors.get(&cue) {
+                        ui.label("");
+                        ui.colored_label(egui::Color32::RED, error);
+                        ui.end_row();
+                    }
+                }
+            });
+
+        changed
+    }
+}
@@ -0,0 +1,7 @@
+pub mod backup_panel;
+pub mod cue_settings_panel;
+pub mod settings_dto;
+pub mod settings_entity;
+pub mod settings_page;
+mod settings_repo;
+pub mod settings_service;
@@ -0,0 +1,169 @@
+use crate::enums::{Theme, WeightUnit};
+use crate::error::{AppError, AppResult};
+use crate::settings::settings_entity::SettingEntity;
+use crate::timer::cue::CueSounds;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::ops::RangeInclusive;
+
+pub const VOLUME_RANGE: RangeInclusive<f32> = 0.0..=100.0;
+pub const TEXT_SCALE_RANGE: RangeInclusive<f32> = 0.75..=1.5;
+pub const DEFAULT_REST_RANGE: RangeInclusive<u16> = 0..=600;
+pub const PAGINATION_LIMIT_RANGE: RangeInclusive<u32> = 1..=100;
+pub const DAILY_BACKUPS_RANGE: RangeInclusive<u32> = 0..=365;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingsReq {
+    pub weight_unit: WeightUnit,
+    pub bodyweight_kg: Option<f64>,
+    pub cue_sounds: CueSounds,
+    pub countdown_beeps: bool,
+    pub volume: f32, // percent
+    pub theme: Theme,
+    pub text_scale: f32,
+    pub default_rest_seconds: u16,
+    pub pagination_limit: u32,
+    pub daily_backups: u32, // 0 turns daily backups off
+}
+
+impl SettingsReq {
+    pub fn validate(&self) -> AppResult<()> {
+        if !VOLUME_RANGE.contains(&self.volume) {
+            return Err(AppError::Validation(format!(
+                "Volume must be between 0 and 100, got {}",
+                self.volume
+            )));
+        }
+        if !TEXT_SCALE_RANGE.contains(&self.text_scale) {
+            return Err(AppError::Validation(format!(
+                "Text scale must be between 0.75 and 1.5, got {}",
+                self.text_scale
+            )));
+        }
+        if !DEFAULT_REST_RANGE.contains(&self.default_rest_seconds) {
+            return Err(AppError::Validation(format!(
+                "Default rest must be at most 600 seconds, got {}",
+                self.default_rest_seconds
+            )));
+        }
+        if !PAGINATION_LIMIT_RANGE.contains(&self.pagination_limit) {
+            return Err(AppError::Validation(format!(
+                "Page size must be between 1 and 100, got {}",
+                self.pagination_limit
+            )));
+        }
+        if !DAILY_BACKUPS_RANGE.contains(&self.daily_backups) {
+            return Err(AppError::Validation(format!(
+                "Daily backups must be at most 365, got {}",
+                self.daily_backups
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn to_entities(&self) -> Vec<SettingEntity> {
+        vec![
+            to_entity("weight_unit", &self.weight_unit),
+            to_entity("bodyweight_kg", &self.bodyweight_kg),
+            to_entity("cue_sounds", &self.cue_sounds),
+            to_entity("countdown_beeps", &self.countdown_beeps),
+            to_entity("volume", &self.volume),
+            to_entity("theme", &self.theme),
+            to_entity("text_scale", &self.text_scale),
+            to_entity("default_rest_seconds", &self.default_rest_seconds),
+            to_entity("pagination_limit", &self.pagination_limit),
+            to_entity("daily_backups", &self.daily_backups),
+        ]
+    }
+}
+
+fn to_entity(key: &str, value: &impl Serialize) -> SettingEntity {
+    SettingEntity {
+        key: key.to_string(),
+        value: serde_json::json!(value).to_string(),
+    }
+}
+
+fn parse_into<T: DeserializeOwned>(entity: &SettingEntity, target: &mut T) -> AppResult<()> {
+    *target = serde_json::from_str(&entity.value).map_err(|e| {
+        AppError::Validation(format!(
+            "Setting {} has an invalid value: {}",
+            entity.key, e
+        ))
+    })?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SettingsRes {
+    pub weight_unit: WeightUnit,
+    pub bodyweight_kg: Option<f64>,
+    pub cue_sounds: CueSounds,
+    pub countdown_beeps: bool,
+    pub volume: f32, // percent
+    pub theme: Theme,
+    pub text_scale: f32,
+    pub default_rest_seconds: u16,
+    pub pagination_limit: u32,
+    pub daily_backups: u32, // 0 turns daily backups off
+}
+
+// used for settings without a row, and when the settings can't be loaded
+impl Default for SettingsRes {
+    fn default() -> Self {
+        Self {
+            weight_unit: WeightUnit::default(),
+            bodyweight_kg: None,
+            cue_sounds: CueSounds::default(),
+            countdown_beeps: true,
+            volume: 50.0,
+            theme: Theme::default(),
+            text_scale: 1.0,
+            default_rest_seconds: 60,
+            pagination_limit: 50,
+            daily_backups: 7,
+        }
+    }
+}
+
+impl SettingsRes {
+    // a value that doesn't parse keeps its default and is reported,
+    // so one bad row can't reset every other setting
+    pub fn from_entities(entities: &[SettingEntity]) -> Self {
+        let mut res = Self::default();
+        for entity in entities {
+            let parsed = match entity.key.as_str() {
+                "weight_unit" => parse_into(entity, &mut res.weight_unit),
+                "bodyweight_kg" => parse_into(entity, &mut res.bodyweight_kg),
+                "cue_sounds" => parse_into(entity, &mut res.cue_sounds),
+                "countdown_beeps" => parse_into(entity, &mut res.countdown_beeps),
+                "volume" => parse_into(entity, &mut res.volume),
+                "theme" => parse_into(entity, &mut res.theme),
+                "text_scale" => parse_into(entity, &mut res.text_scale),
+                "default_rest_seconds" => parse_into(entity, &mut res.default_rest_seconds),
+                "pagination_limit" => parse_into(entity, &mut res.pagination_limit),
+                "daily_backups" => parse_into(entity, &mut res.daily_backups),
+                _ => Ok(()), // unknown keys are left for whichever version wrote them
+            };
+            if let Err(error) = parsed {
+                println!("{}, using the default", error);
+            }
+        }
+        res
+    }
+}
+
+pub fn settings_to_req(res: &SettingsRes) -> SettingsReq {
+    SettingsReq {
+        weight_unit: res.weight_unit,
+        bodyweight_kg: res.bodyweight_kg,
+        cue_sounds: res.cue_sounds.clone(),
+        countdown_beeps: res.countdown_beeps,
+        volume: res.volume,
+        theme: res.theme,
+        text_scale: res.text_scale,
+        default_rest_seconds: res.default_rest_seconds,
+        pagination_limit: res.pagination_limit,
+        daily_backups: res.daily_backups,
+    }
+}
@@ -0,0 +1,8 @@
+use sqlx::FromRow;
+
+// one row per setting, the value is JSON. settings without a row use their default
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct SettingEntity {
+    pub key: String,
+    pub value: String,
+}
@@ -0,0 +1,184 @@
+use crate::db::backup::BackupService;
+use crate::enums::{Theme, WeightUnit};
+use crate::settings::backup_panel::BackupPanel;
+use crate::settings::cue_settings_panel::CueSettingsPanel;
+use crate::settings::settings_dto::{
+    DAILY_BACKUPS_RANGE, DEFAULT_REST_RANGE, PAGINATION_LIMIT_RANGE, SettingsRes, TEXT_SCALE_RANGE,
+    VOLUME_RANGE,
+};
+use crate::timer::audio_engine::AudioEngine;
+use eframe::egui;
+
+// what the app should do after a settings widget was touched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SettingsChange {
+    None,
+    Preview, // apply now, save once the drag or edit ends
+    Save,
+}
+
+impl SettingsChange {
+    pub fn from_response(response: &egui::Response) -> Self {
+        if response.drag_stopped() || response.lost_focus() {
+            SettingsChange::Save
+        } else if response.changed() && (response.dragged() || response.has_focus()) {
+            SettingsChange::Preview
+        } else if response.changed() {
+            SettingsChange::Save
+        } else {
+            SettingsChange::None
+        }
+    }
+}
+
+pub struct SettingsPage {
+    cue_settings_panel: CueSettingsPanel,
+    backup_panel: BackupPanel,
+}
+
+impl SettingsPage {
+    pub fn new(backup_service: BackupService) -> Self {
+        Self {
+            cue_settings_panel: CueSettingsPanel::default(),
+            backup_panel: BackupPanel::new(backup_service),
+        }
+    }
+
+    pub fn render_page(
+        &mut self,
+        ctx: &egui::Context,
+        ui: &mut egui::Ui,
+        settings: &mut SettingsRes,
+        audio: &AudioEngine,
+    ) -> SettingsChange {
+        let mut change = SettingsChange::None;
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.heading("Settings");
+            ui.separator();
+
+            egui::Grid::new("settings_grid")
+                .num_columns(2)
+                .spacing([40.0, 8.0])
+                .show(ui, |ui| {
+                    change = change.max(Self::render_weight_settings(ui, settings));
+
+                    ui.label("Volume");
+                    let response = ui.add(
+                        egui::Slider::new(&mut settings.volume, VOLUME_RANGE)
+                            .suffix("%")
+                            .integer(),
+                    );
+                    change = change.max(SettingsChange::from_response(&response));
+                    ui.end_row();
+
+                    ui.label("Theme");
+                    let mut theme = settings.theme;
+                    egui::ComboBox::from_id_salt("settings_theme")
+                        .selected_text(theme.to_string())
+                        .show_ui(ui, |ui| {
+                            for option in [Theme::Dark, Theme::Light] {
+                                ui.selectable_value(&mut theme, option, option.to_string());
+                            }
+                        });
+                    if theme != settings.theme {
+                        settings.theme = theme;
+                        change = SettingsChange::Save;
+                    }
+                    ui.end_row();
+
+                    ui.label("Text size");
+                    let response = ui.add(
+                        egui::Slider::new(&mut settings.text_scale, TEXT_SCALE_RANGE)
+                            .step_by(0.05)
+                            .custom_formatter(|scale, _| format!("{:.0}%", scale * 100.0)),
+                    );
+                    change = change.max(SettingsChange::from_response(&response));
+                    ui.end_row();
+
+                    ui.label("Default rest");
+                    let response = ui.add(
+                        egui::DragValue::new(&mut settings.default_rest_seconds)
+                            .suffix(" s")
+                            .range(DEFAULT_REST_RANGE),
+                    );
+                    change = change.max(SettingsChange::from_response(&response));
+                    ui.end_row();
+
+                    ui.label("Rows per page");
+                    let response = ui.add(
+                        egui::DragValue::new(&mut settings.pagination_limit)
+                            .range(PAGINATION_LIMIT_RANGE),
+                    );
+                    change = change.max(SettingsChange::from_response(&response));
+                    ui.end_row();
+                });
+
+            ui.add_space(20.0);
+            ui.heading("Sounds");
+            ui.separator();
+            if self.cue_settings_panel.render(ui, settings, audio) {
+                change = SettingsChange::Save;
+            }
+
+            ui.add_space(20.0);
+            ui.heading("Backups");
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label("Daily backups to keep");
+                let response = ui.add(
+                    egui::DragValue::new(&mut settings.daily_backups).range(DAILY_BACKUPS_RANGE),
+                );
+                change = change.max(SettingsChange::from_response(&response));
+                if settings.daily_backups == 0 {
+                    ui.label("(off)");
+                }
+            });
+            self.backup_panel.render(ctx, ui);
+        });
+
+        change
+    }
+
+    fn render_weight_settings(ui: &mut egui::Ui, settings: &mut SettingsRes) -> SettingsChange {
+        let mut change = SettingsChange::None;
+
+        ui.label("Weight unit");
+        let mut weight_unit = settings.weight_unit;
+        egui::ComboBox::from_id_salt("settings_weight_unit")
+            .selected_text(weight_unit.to_string())
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut weight_unit, WeightUnit::Kg, "kg");
+                ui.selectable_value(&mut weight_unit, WeightUnit::Lb, "lb");
+            });
+        if weight_unit != settings.weight_unit {
+            settings.weight_unit = weight_unit;
+            change = SettingsChange::Save;
+        }
+        ui.end_row();
+
+        // edited in the display unit, stored in kg
+        ui.label("Bodyweight");
+        let mut bodyweight = settings
+            .bodyweight_kg
+            .map(|kg| WeightUnit::Kg.convert(kg, weight_unit))
+            .unwrap_or(0.0);
+        let response = ui.add(
+            egui::DragValue::new(&mut bodyweight)
+                .suffix(format!(" {}", weight_unit))
+                .speed(0.1)
+                .range(0.0..=500.0)
+                .max_decimals(2),
+        );
+        if response.changed() {
+            settings.bodyweight_kg = if bodyweight > 0.0 {
+                Some(weight_unit.convert(bodyweight, WeightUnit::Kg))
+            } else {
+                None
+            };
+        }
+        ui.end_row();
+
+        change.max(SettingsChange::from_response(&response))
+    }
+}
@@ -0,0 +1,173 @@
+use crate::db::{SqliteExecutor, SqliteTx};
+use crate::error::{AppError, AppResult};
+use crate::settings::settings_dto::{SettingsReq, SettingsRes};
+use crate::settings::settings_entity::SettingEntity;
+
+#[derive(Clone, Copy)]
+pub struct SettingsRepo {}
+
+impl Default for SettingsRepo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SettingsRepo {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub async fn get_settings<'e>(
+        &self,
+        executor: impl SqliteExecutor<'e>,
+    ) -> AppResult<SettingsRes> {
+        let entities: Vec<SettingEntity> = sqlx::query_as("SELECT key, value FROM app_settings")
+            .fetch_all(executor)
+            .await
+            .map_err(AppError::db("Database error"))?;
+        Ok(SettingsRes::from_entities(&entities))
+    }
+
+    pub async fn update_settings(&self, tx: &mut SqliteTx<'_>, req: SettingsReq) -> AppResult<()> {
+        req.validate()?;
+
+        for entity in req.to_entities() {
+            self.set_value(tx, &entity.key, &entity.value).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn set_value(&self, tx: &mut SqliteTx<'_>, key: &str, value: &str) -> AppResult<()> {
+        sqlx::query(
+            r#"
+        INSERT INTO app_settings (key, value) VALUES (?, ?)
+        ON CONFLICT (key) DO UPDATE SET value = excluded.value
+        "#,
+        )
+        .bind(key)
+        .bind(value)
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::db("Failed to update settings"))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::{IN_MEMORY_DB_URL, init_db};
+    use crate::enums::{Theme, WeightUnit};
+    use crate::settings::settings_dto::{SettingsReq, SettingsRes, settings_to_req};
+    use crate::settings::settings_repo::SettingsRepo;
+    use crate::timer::cue::{Cue, CueSound, CueSounds, Tone};
+
+    #[tokio::test]
+    async fn test_settings_get_and_update() {
+        let pool = init_db(IN_MEMORY_DB_URL).await.unwrap();
+        let mut tx = pool.begin().await.unwrap();
+        let repository = SettingsRepo::new();
+
+        let settings = repository.get_settings(&mut *tx).await.unwrap();
+        assert_eq!(settings.weight_unit, WeightUnit::Kg);
+        assert_eq!(settings.bodyweight_kg, None);
+        assert_eq!(settings.cue_sounds, CueSounds::default());
+        assert!(settings.countdown_beeps);
+        // values carried over from the old single row match the fallback used when loading fails
+        assert_eq!(settings, SettingsRes::default());
+
+        let mut cue_sounds = CueSounds::default();
+        cue_sounds.set(Cue::RestFinished, CueSound::File("/tmp/gong.ogg".into()));
+        cue_sounds.set(Cue::MetronomeBeat, CueSound::Tone(Tone::Click));
+        repository
+            .update_settings(
+                &mut tx,
+                SettingsReq {
+                    weight_unit: WeightUnit::Lb,
+                    bodyweight_kg: Some(72.5),
+                    cue_sounds: cue_sounds.clone(),
+                    countdown_beeps: false,
+                    volume: 80.0,
+                    theme: Theme::Light,
+                    text_scale: 1.25,
+                    default_rest_seconds: 120,
+                    pagination_limit: 20,
+                    daily_backups: 0,
+                },
+            )
+            .await
+            .expect("Failed to update settings");
+
+        let settings = repository.get_settings(&mut *tx).await.unwrap();
+        assert_eq!(settings.weight_unit, WeightUnit::Lb);
+        assert_eq!(settings.bodyweight_kg, Some(72.5));
+        assert_eq!(settings.cue_sounds, cue_sounds);
+        assert!(!settings.countdown_beeps);
+        assert_eq!(settings.volume, 80.0);
+        assert_eq!(settings.theme, Theme::Light);
+        assert_eq!(settings.text_scale, 1.25);
+        assert_eq!(settings.default_rest_seconds, 120);
+        assert_eq!(settings.pagination_limit, 20);
+        assert_eq!(settings.daily_backups, 0);
+
+        tx.commit().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_settings_rejects_out_of_range_values() {
+        let pool = init_db(IN_MEMORY_DB_URL).await.unwrap();
+        let mut tx = pool.begin().await.unwrap();
+        let repository = SettingsRepo::new();
+
+        let defaults = settings_to_req(&SettingsRes::default());
+        for req in [
+            SettingsReq {
+                volume: 150.0,
+                ..defaults.clone()
+            },
+            SettingsReq {
+                text_scale: 3.0,
+                ..defaults.clone()
+            },
+            SettingsReq {
+                pagination_limit: 0,
+                ..defaults.clone()
+            },
+        ] {
+            assert!(repository.update_settings(&mut tx, req).await.is_err());
+        }
+
+        let settings = repository.get_settings(&mut *tx).await.unwrap();
+        assert_eq!(settings, SettingsRes::default());
+    }
+
+    #[tokio::test]
+    async fn test_settings_without_a_row_use_defaults() {
+        let pool = init_db(IN_MEMORY_DB_URL).await.unwrap();
+        let mut tx = pool.begin().await.unwrap();
+        let repository = SettingsRepo::new();
+
+        sqlx::query("DELETE FROM app_settings WHERE key = 'volume'")
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        repository
+            .set_value(&mut tx, "theme", r#""Light""#)
+            .await
+            .unwrap();
+
+        let settings = repository.get_settings(&mut *tx).await.unwrap();
+        assert_eq!(settings.volume, SettingsRes::default().volume);
+        assert_eq!(settings.theme, Theme::Light);
+
+        // a bad value only resets its own setting
+        repository
+            .set_value(&mut tx, "volume", "loud")
+            .await
+            .unwrap();
+        let settings = repository.get_settings(&mut *tx).await.unwrap();
+        assert_eq!(settings.volume, SettingsRes::default().volume);
+        assert_eq!(settings.theme, Theme::Light);
+    }
+}
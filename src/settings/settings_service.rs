@@ -0,0 +1,32 @@
+use crate::error::AppResult;
+use crate::settings::settings_dto::{SettingsReq, SettingsRes};
+use crate::settings::settings_repo::SettingsRepo;
+use sqlx::{Pool, Sqlite};
+
+#[derive(Clone)]
+pub struct SettingsService {
+    pool: Pool<Sqlite>,
+    repo: SettingsRepo,
+}
+
+impl SettingsService {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self {
+            pool,
+            repo: SettingsRepo::new(),
+        }
+    }
+
+    pub async fn get_settings(&self) -> AppResult<SettingsRes> {
+        self.repo
+            .get_settings(&mut *self.pool.acquire().await?)
+            .await
+    }
+
+    pub async fn update_settings(&self, req: SettingsReq) -> AppResult<()> {
+        let mut conn = self.pool.begin().await?;
+        self.repo.update_settings(&mut conn, req).await?;
+        conn.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use chrono::NaiveDate;
+use eframe::egui;
+use egui::{Align2, FontId, Pos2, Rect, Sense, Shape, Stroke, StrokeKind};
+
+const CHART_HEIGHT: f32 = 260.0;
+const Y_LABEL_WIDTH: f32 = 70.0;
+const X_LABEL_HEIGHT: f32 = 22.0;
+const GRID_LINES: usize = 4;
+
+pub struct ChartPoint {
+    pub date: NaiveDate,
+    pub value: f64,
+}
+
+// dates on x spaced by days, values on y, painted directly since egui has no plots built in
+pub fn line_chart(
+    ui: &mut egui::Ui,
+    points: &[ChartPoint],
+    format_value: impl Fn(f64) -> String,
+) -> egui::Response {
+    let size = egui::vec2(ui.available_width(), CHART_HEIGHT);
+    let (rect, response) = ui.allocate_exact_size(size, Sense::hover());
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals();
+    let text_color = visuals.weak_text_color();
+    let grid_stroke = Stroke::new(1.0, visuals.widgets.noninteractive.bg_stroke.color);
+    let line_color = visuals.selection.bg_fill;
+    let font = FontId::proportional(12.0);
+
+    let plot = Rect::from_min_max(
+        rect.min + egui::vec2(Y_LABEL_WIDTH, 8.0),
+        rect.max - egui::vec2(12.0, X_LABEL_HEIGHT),
+    );
+    painter.rect_stroke(plot, 0.0, grid_stroke, StrokeKind::Inside);
+
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        painter.text(
+            plot.center(),
+            Align2::CENTER_CENTER,
+            "No sets logged in this range",
+            font,
+            text_color,
+        );
+        return response;
+    };
+
+    let days = (last.date - first.date).num_days().max(1) as f32;
+    let (min, max) = value_range(points.iter().map(|p| p.value));
+    let to_screen = |point: &ChartPoint| {
+        let x = if points.len() == 1 {
+            plot.center().x
+        } else {
+            plot.left() + plot.width() * (point.date - first.date).num_days() as f32 / days
+        };
+        let y = plot.bottom() - plot.height() * ((point.value - min) / (max - min)) as f32;
+        Pos2::new(x, y)
+    };
+
+    for i in 0..=GRID_LINES {
+        let fraction = i as f32 / GRID_LINES as f32;
+        let y = plot.bottom() - plot.height() * fraction;
+        if i > 0 && i < GRID_LINES {
+            painter.line_segment(
+                [Pos2::new(plot.left(), y), Pos2::new(plot.right(), y)],
+                grid_stroke,
+            );
+        }
+        painter.text(
+            Pos2::new(plot.left() - 6.0, y),
+            Align2::RIGHT_CENTER,
+            format_value(min + (max - min) * fraction as f64),
+            font.clone(),
+            text_color,
+        );
+    }
+
+    let label_y = plot.bottom() + 4.0;
+    painter.text(
+        Pos2::new(plot.left(), label_y),
+        Align2::LEFT_TOP,
+        first.date.to_string(),
+        font.clone(),
+        text_color,
+    );
+    if points.len() > 1 {
+        painter.text(
+            Pos2::new(plot.right(), label_y),
+            Align2::RIGHT_TOP,
+            last.date.to_string(),
+            font.clone(),
+            text_color,
+        );
+    }
+
+    let screen_points: Vec<Pos2> = points.iter().map(to_screen).collect();
+    painter.add(Shape::line(
+        screen_points.clone(),
+        Stroke::new(2.0, line_color),
+    ));
+    for pos in &screen_points {
+        painter.circle_filled(*pos, 3.5, line_color);
+    }
+
+    // the point closest to the pointer horizontally gets its value shown
+    if let Some(hover) = response.hover_pos()
+        && let Some((pos, point)) = screen_points
+            .iter()
+            .zip(points)
+            .min_by(|(a, _), (b, _)| (a.x - hover.x).abs().total_cmp(&(b.x - hover.x).abs()))
+    {
+        painter.circle_stroke(*pos, 6.0, Stroke::new(2.0, visuals.strong_text_color()));
+        let label = format!("{}: {}", point.date, format_value(point.value));
+        let align = if pos.x > plot.center().x {
+            Align2::RIGHT_BOTTOM
+        } else {
+            Align2::LEFT_BOTTOM
+        };
+        let label_color = visuals.strong_text_color();
+        let galley = painter.layout_no_wrap(label, font, label_color);
+        let label_rect = align.anchor_size(*pos + egui::vec2(0.0, -10.0), galley.size());
+        painter.rect_filled(label_rect.expand(3.0), 3.0, visuals.extreme_bg_color);
+        painter.galley(label_rect.min, galley, label_color);
+    }
+
+    response
+}
+
+// padded so the line doesn't touch the frame, a flat line sits in the middle
+fn value_range(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+        (min.min(v), max.max(v))
+    });
+    if !min.is_finite() || !max.is_finite() {
+        return (0.0, 1.0);
+    }
+    if min == max {
+        let pad = (min.abs() * 0.1).max(1.0);
+        return (min - pad, max + pad);
+    }
+    let pad = (max - min) * 0.1;
+    (min - pad, max + pad)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_range_is_padded() {
+        assert_eq!(value_range([10.0, 20.0].into_iter()), (9.0, 21.0));
+        assert_eq!(value_range([50.0].into_iter()), (45.0, 55.0));
+        assert_eq!(value_range([0.0].into_iter()), (-1.0, 1.0));
+        assert_eq!(value_range(std::iter::empty()), (0.0, 1.0));
+    }
+}
@@ -0,0 +1,5 @@
+pub mod line_chart;
+pub mod stats_dto;
+pub mod stats_page;
+mod stats_repo;
+pub mod stats_service;
@@ -0,0 +1,142 @@
+use crate::enums::WeightUnit;
+use crate::workout_log::workout_log_dto::ExerciseHistoryTarget;
+use chrono::{Months, NaiveDate};
+use sqlx::FromRow;
+use std::fmt;
+
+// exercise and date range the stats are aggregated over
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsFilterReq {
+    pub exercise: ExerciseHistoryTarget,
+    pub date_gte: Option<NaiveDate>,
+    pub date_lte: Option<NaiveDate>,
+    pub bodyweight_kg: Option<f64>, // added to bodyweight exercises, like Load::effective_in
+}
+
+// an exercise that has logged sets, matched like the exercise history
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct StatsExerciseRes {
+    pub exercise_library_id: Option<u32>,
+    pub exercise_name: String,
+    pub session_count: u32,
+    pub last_date: NaiveDate,
+}
+
+impl StatsExerciseRes {
+    pub fn target(&self) -> ExerciseHistoryTarget {
+        ExerciseHistoryTarget {
+            exercise_library_id: self.exercise_library_id,
+            exercise_name: self.exercise_name.clone(),
+        }
+    }
+}
+
+// one session of one exercise, loads in kg
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct SessionStatsRes {
+    pub workout_log_group_id: u32,
+    pub date: NaiveDate,
+    pub set_count: u32,
+    pub top_set_kg: f64,
+    pub volume_kg: Option<f64>, // None for holds, their reps are seconds
+    pub best_reps: u8,
+    pub epley_1rm_kg: Option<f64>,
+    pub brzycki_1rm_kg: Option<f64>, // only sets under 37 reps, the formula breaks down past that
+}
+
+// most reps done at a load, loads in kg
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct RepRecordRes {
+    pub load_kg: f64,
+    pub best_reps: u8,
+    pub date: NaiveDate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatsMetric {
+    TopSet,
+    Volume,
+    BestReps,
+    Epley1rm,
+    Brzycki1rm,
+}
+
+impl StatsMetric {
+    pub const ALL: [StatsMetric; 5] = [
+        StatsMetric::TopSet,
+        StatsMetric::Volume,
+        StatsMetric::BestReps,
+        StatsMetric::Epley1rm,
+        StatsMetric::Brzycki1rm,
+    ];
+
+    // None when the session has nothing to plot for this metric
+    pub fn value(self, stats: &SessionStatsRes, unit: WeightUnit) -> Option<f64> {
+        let in_unit = |kg: f64| WeightUnit::Kg.convert(kg, unit);
+        match self {
+            StatsMetric::TopSet => Some(in_unit(stats.top_set_kg)),
+            StatsMetric::Volume => stats.volume_kg.map(in_unit),
+            StatsMetric::BestReps => Some(stats.best_reps as f64),
+            StatsMetric::Epley1rm => stats.epley_1rm_kg.map(in_unit),
+            StatsMetric::Brzycki1rm => stats.brzycki_1rm_kg.map(in_unit),
+        }
+    }
+
+    pub fn is_weight(self) -> bool {
+        !matches!(self, StatsMetric::BestReps)
+    }
+}
+
+impl fmt::Display for StatsMetric {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StatsMetric::TopSet => write!(f, "Top set"),
+            StatsMetric::Volume => write!(f, "Total volume"),
+            StatsMetric::BestReps => write!(f, "Best reps"),
+            StatsMetric::Epley1rm => write!(f, "Estimated 1RM (Epley)"),
+            StatsMetric::Brzycki1rm => write!(f, "Estimated 1RM (Brzycki)"),
+        }
+    }
+}
+
+// quick picks for the date range, the dates can still be edited after
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StatsRange {
+    Weeks4,
+    Months3,
+    Months6,
+    Year,
+    AllTime,
+}
+
+impl StatsRange {
+    pub const ALL: [StatsRange; 5] = [
+        StatsRange::Weeks4,
+        StatsRange::Months3,
+        StatsRange::Months6,
+        StatsRange::Year,
+        StatsRange::AllTime,
+    ];
+
+    pub fn start_date(self, today: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            StatsRange::Weeks4 => today.checked_sub_days(chrono::Days::new(28)),
+            StatsRange::Months3 => today.checked_sub_months(Months::new(3)),
+            StatsRange::Months6 => today.checked_sub_months(Months::new(6)),
+            StatsRange::Year => today.checked_sub_months(Months::new(12)),
+            StatsRange::AllTime => None,
+        }
+    }
+}
+
+impl fmt::Display for StatsRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StatsRange::Weeks4 => write!(f, "Last 4 weeks"),
+            StatsRange::Months3 => write!(f, "Last 3 months"),
+            StatsRange::Months6 => write!(f, "Last 6 months"),
+            StatsRange::Year => write!(f, "Last year"),
+            StatsRange::AllTime => write!(f, "All time"),
+        }
+    }
+}
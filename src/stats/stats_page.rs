@@ -0,0 +1,338 @@
+use crate::client::app::PageAction;
+use crate::client::app_utils::CommonUiState;
+use crate::enums::WeightUnit;
+use crate::error::AppError;
+use crate::settings::settings_dto::SettingsRes;
+use crate::stats::line_chart::{ChartPoint, line_chart};
+use crate::stats::stats_dto::{
+    RepRecordRes, SessionStatsRes, StatsExerciseRes, StatsFilterReq, StatsMetric, StatsRange,
+};
+use crate::stats::stats_service::StatsService;
+use chrono::NaiveDate;
+use eframe::egui;
+use sqlx::{Pool, Sqlite};
+use std::sync::mpsc::{Receiver, Sender, channel};
+
+pub struct StatsPage {
+    service: StatsService,
+    // Data
+    exercises: Vec<StatsExerciseRes>,
+    selected_exercise: Option<usize>, // index into exercises
+    sessions: Vec<SessionStatsRes>,
+    rep_records: Vec<RepRecordRes>,
+    // Chart options
+    metric: StatsMetric,
+    range: Option<StatsRange>, // None once the dates are edited by hand
+    date_gte_str: String,
+    date_lte_str: String,
+    // Display unit for weights, bodyweight is added to bodyweight exercises
+    weight_unit: WeightUnit,
+    bodyweight_kg: Option<f64>,
+    stats_need_fetch: bool,
+    // Async Communication
+    receiver: Receiver<StatsPageMsg>,
+    sender: Sender<StatsPageMsg>,
+    // UI Status
+    common_ui_state: CommonUiState,
+}
+
+pub enum StatsPageMsg {
+    ExercisesLoaded(Vec<StatsExerciseRes>),
+    StatsLoaded(Vec<SessionStatsRes>, Vec<RepRecordRes>),
+    Error(AppError),
+}
+
+impl StatsPage {
+    pub fn default(pool: Pool<Sqlite>) -> Self {
+        let (sender, receiver) = channel();
+        let mut page = Self {
+            service: StatsService::new(pool),
+            exercises: Vec::new(),
+            selected_exercise: None,
+            sessions: Vec::new(),
+            rep_records: Vec::new(),
+            metric: StatsMetric::TopSet,
+            range: None,
+            date_gte_str: String::new(),
+            date_lte_str: String::new(),
+            weight_unit: WeightUnit::default(),
+            bodyweight_kg: None,
+            stats_need_fetch: false,
+            receiver,
+            sender,
+            common_ui_state: CommonUiState::default(),
+        };
+        page.select_range(StatsRange::Months6);
+        page
+    }
+}
+
+impl StatsPage {
+    pub fn apply_settings(&mut self, settings: &SettingsRes) {
+        self.weight_unit = settings.weight_unit;
+        if self.bodyweight_kg != settings.bodyweight_kg {
+            self.bodyweight_kg = settings.bodyweight_kg;
+            self.stats_need_fetch = true;
+        }
+    }
+}
+
+impl StatsPage {
+    fn handle_async_messages(&mut self) {
+        while let Ok(msg) = self.receiver.try_recv() {
+            self.common_ui_state.set_as_not_loading();
+            match msg {
+                StatsPageMsg::ExercisesLoaded(exercises) => {
+                    // keep the selection across refreshes when the exercise is still there
+                    let selected_name = self
+                        .selected_exercise
+                        .and_then(|i| self.exercises.get(i))
+                        .map(|e| e.exercise_name.clone());
+                    self.exercises = exercises;
+                    self.selected_exercise = selected_name
+                        .and_then(|name| {
+                            self.exercises.iter().position(|e| e.exercise_name == name)
+                        })
+                        .or((!self.exercises.is_empty()).then_some(0));
+                    self.stats_need_fetch = true;
+                }
+                StatsPageMsg::StatsLoaded(sessions, rep_records) => {
+                    self.sessions = sessions;
+                    self.rep_records = rep_records;
+                }
+                StatsPageMsg::Error(err) => {
+                    self.common_ui_state.show_app_error(&err);
+                }
+            }
+        }
+    }
+
+    fn fetch_exercises(&mut self, ctx: &egui::Context) {
+        if self.common_ui_state.initialized || self.common_ui_state.loading {
+            return;
+        }
+        self.common_ui_state.set_as_loading();
+        self.common_ui_state.initialize();
+
+        let service = self.service.clone();
+        let sender = self.sender.clone();
+        let ctx = ctx.clone();
+
+        tokio::spawn(async move {
+            match service.get_logged_exercises().await {
+                Ok(exercises) => {
+                    let _ = sender.send(StatsPageMsg::ExercisesLoaded(exercises));
+                }
+                Err(e) => {
+                    let _ = sender.send(StatsPageMsg::Error(e));
+                }
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    fn fetch_stats(&mut self, ctx: &egui::Context) {
+        if !self.stats_need_fetch || self.common_ui_state.loading {
+            return;
+        }
+        self.stats_need_fetch = false;
+        let Some(exercise) = self.selected_exercise.and_then(|i| self.exercises.get(i)) else {
+            self.sessions.clear();
+            self.rep_records.clear();
+            return;
+        };
+        self.common_ui_state.set_as_loading();
+
+        let filter = StatsFilterReq {
+            exercise: exercise.target(),
+            date_gte: parse_date(&self.date_gte_str),
+            date_lte: parse_date(&self.date_lte_str),
+            bodyweight_kg: self.bodyweight_kg,
+        };
+        let service = self.service.clone();
+        let sender = self.sender.clone();
+        let ctx = ctx.clone();
+
+        tokio::spawn(async move {
+            match service.get_exercise_stats(filter).await {
+                Ok((sessions, rep_records)) => {
+                    let _ = sender.send(StatsPageMsg::StatsLoaded(sessions, rep_records));
+                }
+                Err(e) => {
+                    let _ = sender.send(StatsPageMsg::Error(e));
+                }
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    fn select_range(&mut self, range: StatsRange) {
+        let today = chrono::Local::now().date_naive();
+        self.range = Some(range);
+        self.date_gte_str = range
+            .start_date(today)
+            .map(|date| date.to_string())
+            .unwrap_or_default();
+        self.date_lte_str.clear();
+        self.stats_need_fetch = true;
+    }
+
+    fn render_controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Exercise:");
+            let selected_text = self
+                .selected_exercise
+                .and_then(|i| self.exercises.get(i))
+                .map(|e| e.exercise_name.clone())
+                .unwrap_or("Select...".to_string());
+            let mut selected = self.selected_exercise;
+            egui::ComboBox::from_id_salt("stats_exercise")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    for (i, exercise) in self.exercises.iter().enumerate() {
+                        ui.selectable_value(
+                            &mut selected,
+                            Some(i),
+                            format!(
+                                "{} ({} sessions)",
+                                exercise.exercise_name, exercise.session_count
+                            ),
+                        );
+                    }
+                });
+            if selected != self.selected_exercise {
+                self.selected_exercise = selected;
+                self.stats_need_fetch = true;
+            }
+
+            ui.label("Chart:");
+            egui::ComboBox::from_id_salt("stats_metric")
+                .selected_text(self.metric.to_string())
+                .show_ui(ui, |ui| {
+                    for metric in StatsMetric::ALL {
+                        ui.selectable_value(&mut self.metric, metric, metric.to_string());
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Range:");
+            let mut range = self.range;
+            egui::ComboBox::from_id_salt("stats_range")
+                .selected_text(range.map(|r| r.to_string()).unwrap_or("Custom".to_string()))
+                .show_ui(ui, |ui| {
+                    for option in StatsRange::ALL {
+                        ui.selectable_value(&mut range, Some(option), option.to_string());
+                    }
+                });
+            if let Some(range) = range
+                && self.range != Some(range)
+            {
+                self.select_range(range);
+            }
+
+            ui.label("Date From:");
+            let from = ui.text_edit_singleline(&mut self.date_gte_str);
+            ui.label("Date To:");
+            let to = ui.text_edit_singleline(&mut self.date_lte_str);
+            if from.changed() || to.changed() {
+                self.range = None;
+                self.stats_need_fetch = true;
+            }
+        });
+    }
+
+    fn render_chart(&self, ui: &mut egui::Ui) {
+        let points: Vec<ChartPoint> = self
+            .sessions
+            .iter()
+            .filter_map(|session| {
+                self.metric
+                    .value(session, self.weight_unit)
+                    .map(|value| ChartPoint {
+                        date: session.date,
+                        value,
+                    })
+            })
+            .collect();
+
+        let unit = self.weight_unit;
+        let response = if self.metric.is_weight() {
+            line_chart(ui, &points, |value| unit.format(value))
+        } else {
+            line_chart(ui, &points, |value| format!("{:.0}", value))
+        };
+        if matches!(self.metric, StatsMetric::Volume) {
+            response.on_hover_text("Load times reps, summed over every set of the session");
+        }
+    }
+
+    fn render_rep_records(&self, ui: &mut egui::Ui) {
+        ui.heading("Best reps at each load");
+        if self.rep_records.is_empty() {
+            ui.label("-");
+            return;
+        }
+        egui::Grid::new("stats_rep_records_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("Load");
+                ui.label("Reps/Secs");
+                ui.label("Date");
+                ui.end_row();
+
+                for record in &self.rep_records {
+                    ui.label(
+                        self.weight_unit
+                            .format(WeightUnit::Kg.convert(record.load_kg, self.weight_unit)),
+                    );
+                    ui.label(record.best_reps.to_string());
+                    ui.label(record.date.to_string());
+                    ui.end_row();
+                }
+            });
+    }
+
+    pub fn render_page(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) -> PageAction {
+        self.handle_async_messages();
+        self.fetch_exercises(ctx);
+        self.fetch_stats(ctx);
+
+        let mut page_action = PageAction::None;
+
+        ui.horizontal(|ui| {
+            ui.heading("Stats");
+            if ui.button("Refresh").clicked() {
+                self.common_ui_state.not_initialized();
+            }
+            if let Some(exercise) = self.selected_exercise.and_then(|i| self.exercises.get(i))
+                && ui.button("History").clicked()
+            {
+                page_action = PageAction::GoToExerciseHistory(exercise.target());
+            }
+            if self.common_ui_state.loading {
+                ui.spinner();
+            }
+        });
+        self.render_controls(ui);
+        ui.separator();
+        self.common_ui_state.show_toasts(ui);
+
+        if self.exercises.is_empty() {
+            ui.label("Log a workout to see stats here.");
+            return page_action;
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            self.render_chart(ui);
+            ui.add_space(20.0);
+            self.render_rep_records(ui);
+        });
+
+        page_action
+    }
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()
+}
@@ -0,0 +1,373 @@
+use crate::db::SqliteExecutor;
+use crate::enums::WeightUnit;
+use crate::error::{AppError, AppResult};
+use crate::stats::stats_dto::{RepRecordRes, SessionStatsRes, StatsExerciseRes, StatsFilterReq};
+use sqlx::{QueryBuilder, Sqlite};
+
+#[derive(Clone, Copy)]
+pub struct StatsRepo {}
+
+impl StatsRepo {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub async fn get_logged_exercises<'e>(
+        &self,
+        executor: impl SqliteExecutor<'e>,
+    ) -> AppResult<Vec<StatsExerciseRes>> {
+        let res = sqlx::query_as(
+            r#"
+                SELECT MAX(we.exercise_library_id) AS exercise_library_id,
+                       MAX(wl.exercise_name) AS exercise_name,
+                       COUNT(DISTINCT wl.workout_log_group_id) AS session_count,
+                       MAX(wlg.date) AS last_date
+                FROM workout_logs wl
+                JOIN workout_exercises we ON wl.workout_exercise_id = we.id
+                JOIN workout_log_groups wlg ON wl.workout_log_group_id = wlg.id
+                GROUP BY wl.exercise_name COLLATE NOCASE
+                ORDER BY exercise_name COLLATE NOCASE
+                "#,
+        )
+        .fetch_all(executor)
+        .await
+        .map_err(AppError::db("Database error"))?;
+
+        Ok(res)
+    }
+
+    // one row per session, oldest first; holds log seconds so they get no volume or 1rm,
+    // like Load::volume_in
+    pub async fn get_session_stats<'e>(
+        &self,
+        executor: impl SqliteExecutor<'e>,
+        filter: StatsFilterReq,
+    ) -> AppResult<Vec<SessionStatsRes>> {
+        let mut qb = self.sets_query(filter);
+        qb.push(
+            r#"
+                SELECT group_id AS workout_log_group_id,
+                       date,
+                       COUNT(*) AS set_count,
+                       MAX(load_kg) AS top_set_kg,
+                       SUM(CASE WHEN NOT is_static THEN load_kg * reps END) AS volume_kg,
+                       MAX(reps) AS best_reps,
+                       MAX(CASE WHEN is_static THEN NULL
+                                WHEN reps = 1 THEN load_kg
+                                WHEN reps > 1 THEN load_kg * (1 + reps / 30.0) END) AS epley_1rm_kg,
+                       MAX(CASE WHEN NOT is_static AND reps BETWEEN 1 AND 36
+                                THEN load_kg * 36.0 / (37 - reps) END) AS brzycki_1rm_kg
+                FROM sets
+                GROUP BY group_id
+                ORDER BY date, group_id
+                "#,
+        );
+
+        let res = qb
+            .build_query_as()
+            .fetch_all(executor)
+            .await
+            .map_err(AppError::db("Failed to load session stats"))?;
+
+        Ok(res)
+    }
+
+    // heaviest first, the date is a session where the record was set
+    pub async fn get_rep_records<'e>(
+        &self,
+        executor: impl SqliteExecutor<'e>,
+        filter: StatsFilterReq,
+    ) -> AppResult<Vec<RepRecordRes>> {
+        let mut qb = self.sets_query(filter);
+        // sqlite takes bare columns from the row that had the MAX
+        qb.push(
+            r#"
+                SELECT ROUND(load_kg, 2) AS load_kg,
+                       MAX(reps) AS best_reps,
+                       date
+                FROM sets
+                WHERE reps > 0
+                GROUP BY ROUND(load_kg, 2)
+                ORDER BY load_kg DESC
+                "#,
+        );
+
+        let res = qb
+            .build_query_as()
+            .fetch_all(executor)
+            .await
+            .map_err(AppError::db("Failed to load rep records"))?;
+
+        Ok(res)
+    }
+
+    // the matching sets as a "sets" CTE, loads converted to kg with bodyweight added
+    fn sets_query(&self, filter: StatsFilterReq) -> QueryBuilder<'static, Sqlite> {
+        let mut qb = QueryBuilder::new(
+            r#"WITH sets AS (
+                SELECT wlg.id AS group_id,
+                       wlg.date,
+                       wl.rep_number_or_seconds AS reps,
+                       COALESCE(el.dynamic_or_static = 'Static', 0) AS is_static,
+                       CASE wl.weight_unit WHEN 'Lb' THEN wl.weight * "#,
+        );
+        qb.push_bind(WeightUnit::Lb.convert(1.0, WeightUnit::Kg));
+        qb.push(" ELSE wl.weight END + wl.bodyweight_fraction * ");
+        qb.push_bind(filter.bodyweight_kg.unwrap_or(0.0));
+        qb.push(
+            r#" AS load_kg
+                FROM workout_logs wl
+                JOIN workout_exercises we ON wl.workout_exercise_id = we.id
+                JOIN workout_log_groups wlg ON wl.workout_log_group_id = wlg.id
+                LEFT JOIN exercise_library el ON we.exercise_library_id = el.id
+                WHERE (wl.exercise_name = "#,
+        );
+        qb.push_bind(filter.exercise.exercise_name);
+        qb.push(" COLLATE NOCASE");
+        if let Some(library_id) = filter.exercise.exercise_library_id {
+            qb.push(" OR we.exercise_library_id = ");
+            qb.push_bind(library_id);
+        }
+        qb.push(")");
+
+        if let Some(date_gte) = filter.date_gte {
+            qb.push(" AND wlg.date >= ");
+            qb.push_bind(date_gte);
+        }
+        if let Some(date_lte) = filter.date_lte {
+            qb.push(" AND wlg.date <= ");
+            qb.push_bind(date_lte);
+        }
+        qb.push(")");
+        qb
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::{IN_MEMORY_DB_URL, init_db};
+    use crate::enums::WeightUnit;
+    use crate::stats::stats_dto::StatsFilterReq;
+    use crate::stats::stats_repo::StatsRepo;
+    use crate::workout_log::workout_log_dto::ExerciseHistoryTarget;
+    use chrono::{NaiveDate, Utc};
+    use sqlx::SqlitePool;
+
+    async fn setup_db() -> SqlitePool {
+        init_db(IN_MEMORY_DB_URL).await.unwrap()
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap()
+    }
+
+    // (day, exercise, reps, weight, unit, bodyweight fraction)
+    async fn seed_logs(pool: &SqlitePool, sets: &[(u32, &str, u8, f64, WeightUnit, f64)]) {
+        let workout_id = sqlx::query(
+            "INSERT INTO workouts (created_at, name, active) VALUES (?, 'Test Workout', 1)",
+        )
+        .bind(Utc::now())
+        .execute(pool)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+
+        for (day, exercise_name, reps, weight, unit, fraction) in sets {
+            let workout_exercise_id = sqlx::query(
+                r#"INSERT INTO workout_exercises (
+                    created_at, workout_id, code, name, sets_target, reps_or_seconds_target,
+                    working_weight, rest_period_seconds, tempo, emom, equipments, bands
+                ) VALUES (?, ?, 'A1', ?, 3, 5, 0, 90, '', 0, '[]', '[]')"#,
+            )
+            .bind(Utc::now())
+            .bind(workout_id)
+            .bind(exercise_name)
+            .execute(pool)
+            .await
+            .unwrap()
+            .last_insert_rowid();
+
+            let group_id: i64 =
+                match sqlx::query_scalar("SELECT id FROM workout_log_groups WHERE date = ?")
+                    .bind(date(*day))
+                    .fetch_optional(pool)
+                    .await
+                    .unwrap()
+                {
+                    Some(id) => id,
+                    None => sqlx::query(
+                        "INSERT INTO workout_log_groups (created_at, date) VALUES (?, ?)",
+                    )
+                    .bind(Utc::now())
+                    .bind(date(*day))
+                    .execute(pool)
+                    .await
+                    .unwrap()
+                    .last_insert_rowid(),
+                };
+
+            sqlx::query(
+                r#"INSERT INTO workout_logs (
+                    workout_id, workout_exercise_id, workout_log_group_id, exercise_name,
+                    set_number, rep_number_or_seconds, weight, weight_unit, bodyweight_fraction
+                ) VALUES (?, ?, ?, ?, 1, ?, ?, ?, ?)"#,
+            )
+            .bind(workout_id)
+            .bind(workout_exercise_id)
+            .bind(group_id)
+            .bind(exercise_name)
+            .bind(reps)
+            .bind(weight)
+            .bind(unit)
+            .bind(fraction)
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+    }
+
+    fn filter(exercise_name: &str) -> StatsFilterReq {
+        StatsFilterReq {
+            exercise: ExerciseHistoryTarget {
+                exercise_library_id: None,
+                exercise_name: exercise_name.to_string(),
+            },
+            date_gte: None,
+            date_lte: None,
+            bodyweight_kg: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_stats() {
+        let pool = setup_db().await;
+        let repo = StatsRepo::new();
+        seed_logs(
+            &pool,
+            &[
+                (1, "Squat", 5, 100.0, WeightUnit::Kg, 0.0),
+                (1, "Squat", 3, 110.0, WeightUnit::Kg, 0.0),
+                (1, "Bench", 5, 80.0, WeightUnit::Kg, 0.0),
+                (
+                    8,
+                    "squat",
+                    1,
+                    WeightUnit::Kg.convert(125.0, WeightUnit::Lb),
+                    WeightUnit::Lb,
+                    0.0,
+                ),
+            ],
+        )
+        .await;
+
+        let stats = repo
+            .get_session_stats(&pool, filter("Squat"))
+            .await
+            .expect("Failed to load session stats");
+        assert_eq!(stats.len(), 2);
+
+        let first = &stats[0];
+        assert_eq!(first.date, date(1));
+        assert_eq!(first.set_count, 2);
+        assert_eq!(first.top_set_kg, 110.0);
+        assert_eq!(first.volume_kg, Some(830.0));
+        assert_eq!(first.best_reps, 5);
+        // epley: 110 * (1 + 3/30) = 121, beats 100 * (1 + 5/30)
+        assert!((first.epley_1rm_kg.unwrap() - 121.0).abs() < 1e-9);
+        // brzycki: 110 * 36 / 34 beats 100 * 36 / 32 = 112.5
+        assert!((first.brzycki_1rm_kg.unwrap() - 110.0 * 36.0 / 34.0).abs() < 1e-9);
+
+        // the single lb set converts to kg and a single is its own 1rm
+        let second = &stats[1];
+        assert_eq!(second.date, date(8));
+        assert!((second.top_set_kg - 125.0).abs() < 1e-6);
+        assert!((second.epley_1rm_kg.unwrap() - 125.0).abs() < 1e-6);
+
+        let mut week_two = filter("Squat");
+        week_two.date_gte = Some(date(2));
+        let stats = repo.get_session_stats(&pool, week_two).await.unwrap();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].date, date(8));
+    }
+
+    #[tokio::test]
+    async fn test_session_stats_for_holds() {
+        let pool = setup_db().await;
+        let repo = StatsRepo::new();
+        seed_logs(
+            &pool,
+            &[
+                (1, "L-Sit", 20, 0.0, WeightUnit::Kg, 0.0),
+                (1, "L-Sit", 30, 5.0, WeightUnit::Kg, 0.0),
+            ],
+        )
+        .await;
+        let library_id = sqlx::query(
+            r#"INSERT INTO exercise_library (
+                name, dynamic_or_static, upper_or_lower, compound_or_isolation
+            ) VALUES ('L-Sit', 'Static', 'Upper', 'Compound')"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap()
+        .last_insert_rowid();
+        sqlx::query("UPDATE workout_exercises SET exercise_library_id = ?")
+            .bind(library_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // the seconds held are not reps, so no volume or estimated 1rm
+        let stats = repo
+            .get_session_stats(&pool, filter("L-Sit"))
+            .await
+            .unwrap();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].top_set_kg, 5.0);
+        assert_eq!(stats[0].best_reps, 30);
+        assert_eq!(stats[0].volume_kg, None);
+        assert_eq!(stats[0].epley_1rm_kg, None);
+        assert_eq!(stats[0].brzycki_1rm_kg, None);
+    }
+
+    #[tokio::test]
+    async fn test_bodyweight_and_rep_records() {
+        let pool = setup_db().await;
+        let repo = StatsRepo::new();
+        seed_logs(
+            &pool,
+            &[
+                (1, "Pull Up", 8, 0.0, WeightUnit::Kg, 1.0),
+                (1, "Pull Up", 3, 10.0, WeightUnit::Kg, 1.0),
+                (8, "Pull Up", 10, 0.0, WeightUnit::Kg, 1.0),
+            ],
+        )
+        .await;
+
+        let mut with_bodyweight = filter("Pull Up");
+        with_bodyweight.bodyweight_kg = Some(75.0);
+        let stats = repo
+            .get_session_stats(&pool, with_bodyweight.clone())
+            .await
+            .unwrap();
+        assert_eq!(stats[0].top_set_kg, 85.0);
+        assert_eq!(stats[0].volume_kg, Some(75.0 * 8.0 + 85.0 * 3.0));
+
+        let records = repo
+            .get_rep_records(&pool, with_bodyweight)
+            .await
+            .expect("Failed to load rep records");
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].load_kg, 85.0);
+        assert_eq!(records[0].best_reps, 3);
+        assert_eq!(records[1].load_kg, 75.0);
+        assert_eq!(records[1].best_reps, 10);
+        assert_eq!(records[1].date, date(8));
+
+        let exercises = repo.get_logged_exercises(&pool).await.unwrap();
+        assert_eq!(exercises.len(), 1);
+        assert_eq!(exercises[0].exercise_name, "Pull Up");
+        assert_eq!(exercises[0].session_count, 2);
+        assert_eq!(exercises[0].last_date, date(8));
+    }
+}
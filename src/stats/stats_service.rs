@@ -0,0 +1,38 @@
+use crate::error::AppResult;
+use crate::stats::stats_dto::{RepRecordRes, SessionStatsRes, StatsExerciseRes, StatsFilterReq};
+use crate::stats::stats_repo::StatsRepo;
+use sqlx::{Pool, Sqlite};
+
+#[derive(Clone)]
+pub struct StatsService {
+    pool: Pool<Sqlite>,
+    repo: StatsRepo,
+}
+
+impl StatsService {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self {
+            pool,
+            repo: StatsRepo::new(),
+        }
+    }
+
+    pub async fn get_logged_exercises(&self) -> AppResult<Vec<StatsExerciseRes>> {
+        self.repo
+            .get_logged_exercises(&mut *self.pool.acquire().await?)
+            .await
+    }
+
+    pub async fn get_exercise_stats(
+        &self,
+        filter: StatsFilterReq,
+    ) -> AppResult<(Vec<SessionStatsRes>, Vec<RepRecordRes>)> {
+        let mut conn = self.pool.acquire().await?;
+        let sessions = self
+            .repo
+            .get_session_stats(&mut *conn, filter.clone())
+            .await?;
+        let rep_records = self.repo.get_rep_records(&mut *conn, filter).await?;
+        Ok((sessions, rep_records))
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TempoPhase {
+    Seconds(u8),
+    Explosive, // written as X
+}
+
+impl TempoPhase {
+    // an explosive phase still takes roughly a second
+    pub fn seconds(&self) -> u32 {
+        match self {
+            TempoPhase::Seconds(s) => *s as u32,
+            TempoPhase::Explosive => 1,
+        }
+    }
+}
+
+impl Display for TempoPhase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TempoPhase::Seconds(s) => write!(f, "{}", s),
+            TempoPhase::Explosive => write!(f, "X"),
+        }
+    }
+}
+
+// four digit tempo notation, e.g. "31X0": 3s down, 1s pause, explosive up, no pause at the top
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Tempo {
+    pub eccentric: TempoPhase,
+    pub bottom_pause: TempoPhase,
+    pub concentric: TempoPhase,
+    pub top_pause: TempoPhase,
+}
+
+impl Tempo {
+    pub fn phases(&self) -> [TempoPhase; 4] {
+        [
+            self.eccentric,
+            self.bottom_pause,
+            self.concentric,
+            self.top_pause,
+        ]
+    }
+
+    pub fn tut_per_rep(&self) -> u32 {
+        self.phases().iter().map(|p| p.seconds()).sum()
+    }
+
+    pub fn tut_per_set(&self, reps: u8) -> u32 {
+        self.tut_per_rep() * reps as u32
+    }
+
+    pub fn describe_tut(&self, reps: u8) -> String {
+        describe_tut(self.tut_per_rep(), self.tut_per_set(reps))
+    }
+}
+
+// "TUT 5s/rep, 40s/set"
+pub fn describe_tut(per_rep: u32, per_set: u32) -> String {
+    format!("TUT {}s/rep, {}s/set", per_rep, per_set)
+}
+
+impl FromStr for Tempo {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let phases = s
+            .trim()
+            .chars()
+            .map(|c| match c {
+                'x' | 'X' => Ok(TempoPhase::Explosive),
+                '0'..='9' => Ok(TempoPhase::Seconds(c as u8 - b'0')),
+                _ => Err(format!(
+                    "Invalid tempo '{}': use digits 0-9 or X for explosive",
+                    s
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match phases[..] {
+            [eccentric, bottom_pause, concentric, top_pause] => Ok(Tempo {
+                eccentric,
+                bottom_pause,
+                concentric,
+                top_pause,
+            }),
+            _ => Err(format!(
+                "Invalid tempo '{}': expected 4 phases, e.g. 3010 or 20X1",
+                s
+            )),
+        }
+    }
+}
+
+impl Display for Tempo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{}{}{}",
+            self.eccentric, self.bottom_pause, self.concentric, self.top_pause
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_round_trip() {
+        let tempo: Tempo = "31x0".parse().unwrap();
+        assert_eq!(tempo.eccentric, TempoPhase::Seconds(3));
+        assert_eq!(tempo.bottom_pause, TempoPhase::Seconds(1));
+        assert_eq!(tempo.concentric, TempoPhase::Explosive);
+        assert_eq!(tempo.top_pause, TempoPhase::Seconds(0));
+        assert_eq!(tempo.to_string(), "31X0");
+
+        for stored in ["0000", "2010", "40X1", "XXXX"] {
+            assert_eq!(stored.parse::<Tempo>().unwrap().to_string(), stored);
+        }
+    }
+
+    #[test]
+    fn test_rejects_invalid() {
+        assert!("".parse::<Tempo>().is_err());
+        assert!("301".parse::<Tempo>().is_err());
+        assert!("30100".parse::<Tempo>().is_err());
+        assert!("3a10".parse::<Tempo>().is_err());
+        assert!("3-1-0".parse::<Tempo>().is_err());
+    }
+
+    #[test]
+    fn test_time_under_tension() {
+        let tempo: Tempo = "31X0".parse().unwrap();
+        assert_eq!(tempo.tut_per_rep(), 5);
+        assert_eq!(tempo.tut_per_set(8), 40);
+        assert_eq!(tempo.describe_tut(8), "TUT 5s/rep, 40s/set");
+        assert_eq!("0000".parse::<Tempo>().unwrap().tut_per_rep(), 0);
+    }
+}
@@ -1,45 +1,347 @@
-use rodio::source::SineWave;
-use rodio::{OutputStream, OutputStreamBuilder, Sink, Source};
-#[cfg(test)]
+use crate::timer::cue::{Cue, CueSound, CueSounds, load_sample, sample_source};
+use rodio::{OutputStream, OutputStreamBuilder, Source};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender, channel};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// sounds are handed to the mixer this far ahead, delayed to land on their exact timestamp
+const LOOKAHEAD: Duration = Duration::from_millis(50);
+const SCHEDULER_TICK: Duration = Duration::from_millis(10);
+
+// a cycle of cues repeated every `period` from `start` until stopped
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    pub start: Instant,
+    pub period: Duration,
+    pub events: Vec<(Duration, Cue)>, // offsets into the cycle, at most `period`
+    pub volume: f32,
+}
 
 pub trait AudioBackend {
-    fn play_sound(&mut self, volume: f32);
+    fn schedule(&mut self, at: Instant, cue: Cue, volume: f32);
+    fn cancel_scheduled(&mut self);
+    fn start_pattern(&mut self, pattern: Pattern);
+    // keeps the current cycle's start, so the change lands on the next beat
+    fn retime_pattern(&mut self, period: Duration, volume: f32);
+    fn stop_pattern(&mut self);
+}
+
+enum AudioMessage {
+    Voice(u32, AudioCommand),
+    SetSounds(CueSounds),
+    Preview(Cue, f32),
+}
+
+enum AudioCommand {
+    Schedule(Instant, Cue, f32),
+    CancelScheduled,
+    StartPattern(Pattern),
+    RetimePattern(Duration, f32),
+    StopPattern,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AudioStatus {
+    Starting,
+    Speakers,
+    Silent(String), // no output device, timers are visual only
+}
+
+impl Display for AudioStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AudioStatus::Starting => write!(f, "Audio starting"),
+            AudioStatus::Speakers => write!(f, "Audio on"),
+            AudioStatus::Silent(_) => write!(f, "Visual only"),
+        }
+    }
 }
 
+// written by the audio thread, read by the footer
+struct AudioState {
+    status: AudioStatus,
+    last_cue_at: Option<Instant>,
+}
+
+// one audio thread and output stream for the whole app; each timer gets its own voice
+#[derive(Clone)]
 pub struct AudioEngine {
-    _stream_handler: OutputStream,
-    sink: Sink,
+    sender: Sender<AudioMessage>,
+    state: Arc<Mutex<AudioState>>,
+    next_voice: Arc<AtomicU32>,
 }
 
 impl AudioEngine {
     pub fn new() -> Self {
-        let stream_handler =
-            OutputStreamBuilder::open_default_stream().expect("Failed to open stream");
-        let sink = Sink::connect_new(&stream_handler.mixer());
+        let (sender, receiver) = channel();
+        let state = Arc::new(Mutex::new(AudioState {
+            status: AudioStatus::Starting,
+            last_cue_at: None,
+        }));
+        let thread_state = state.clone();
+        thread::Builder::new()
+            .name("audio".to_string())
+            .spawn(move || run_audio_thread(receiver, thread_state))
+            .expect("Failed to spawn audio thread");
         Self {
-            _stream_handler: stream_handler,
-            sink,
+            sender,
+            state,
+            next_voice: Arc::new(AtomicU32::new(0)),
         }
     }
+
+    pub fn voice(&self) -> AudioVoice {
+        AudioVoice {
+            id: self.next_voice.fetch_add(1, Ordering::Relaxed),
+            sender: self.sender.clone(),
+        }
+    }
+
+    pub fn set_sounds(&self, sounds: CueSounds) {
+        let _ = self.sender.send(AudioMessage::SetSounds(sounds));
+    }
+
+    // plays a cue right away, e.g. to try out a sound in settings
+    pub fn preview(&self, cue: Cue, volume: f32) {
+        let _ = self.sender.send(AudioMessage::Preview(cue, volume));
+    }
+
+    pub fn status(&self) -> AudioStatus {
+        self.state.lock().unwrap().status.clone()
+    }
+
+    // when the most recent cue was due, so the UI can flash along with (or instead of) the sound
+    pub fn last_cue_at(&self) -> Option<Instant> {
+        self.state.lock().unwrap().last_cue_at
+    }
 }
 
-impl AudioBackend for AudioEngine {
-    fn play_sound(&mut self, volume: f32) {
-        self.sink.append(
-            SineWave::new(880.0)
-                .take_duration(Duration::from_millis(100))
-                .amplify(volume),
-        );
+// a timer's own queue on the shared engine, so stopping one timer leaves the others playing
+pub struct AudioVoice {
+    id: u32,
+    sender: Sender<AudioMessage>,
+}
+
+impl AudioVoice {
+    fn send(&self, command: AudioCommand) {
+        // only fails once the app is shutting down
+        let _ = self.sender.send(AudioMessage::Voice(self.id, command));
+    }
+}
+
+impl AudioBackend for AudioVoice {
+    fn schedule(&mut self, at: Instant, cue: Cue, volume: f32) {
+        self.send(AudioCommand::Schedule(at, cue, volume));
+    }
+
+    fn cancel_scheduled(&mut self) {
+        self.send(AudioCommand::CancelScheduled);
+    }
+
+    fn start_pattern(&mut self, pattern: Pattern) {
+        self.send(AudioCommand::StartPattern(pattern));
+    }
+
+    fn retime_pattern(&mut self, period: Duration, volume: f32) {
+        self.send(AudioCommand::RetimePattern(period, volume));
+    }
+
+    fn stop_pattern(&mut self) {
+        self.send(AudioCommand::StopPattern);
+    }
+}
+
+// keeps scheduling without a device, so the visual cue indicator still works headless
+fn run_audio_thread(receiver: Receiver<AudioMessage>, state: Arc<Mutex<AudioState>>) {
+    let stream = match OutputStreamBuilder::open_default_stream() {
+        Ok(stream) => {
+            state.lock().unwrap().status = AudioStatus::Speakers;
+            Some(stream)
+        }
+        Err(e) => {
+            eprintln!("No audio output, timers will be visual only: {}", e);
+            state.lock().unwrap().status = AudioStatus::Silent(e.to_string());
+            None
+        }
+    };
+    let mut player = CuePlayer {
+        stream,
+        sounds: CueSounds::default(),
+        samples: HashMap::new(),
+    };
+    let mut voices: HashMap<u32, Scheduler> = HashMap::new();
+
+    loop {
+        let now = Instant::now();
+        for scheduler in voices.values_mut() {
+            for (at, cue, volume) in scheduler.take_due(now) {
+                player.play(cue, volume, at.saturating_duration_since(now));
+                let mut state = state.lock().unwrap();
+                state.last_cue_at = state.last_cue_at.max(Some(at));
+            }
+        }
+
+        let message = if voices.values().all(|s| s.is_idle()) {
+            receiver.recv().map_err(|_| RecvTimeoutError::Disconnected)
+        } else {
+            receiver.recv_timeout(SCHEDULER_TICK)
+        };
+        let (voice, command) = match message {
+            Ok(AudioMessage::Voice(voice, command)) => (voice, command),
+            Ok(AudioMessage::SetSounds(sounds)) => {
+                player.sounds = sounds;
+                player.samples.clear(); // files may have changed on disk
+                continue;
+            }
+            Ok(AudioMessage::Preview(cue, volume)) => {
+                player.play(cue, volume, Duration::ZERO);
+                state.lock().unwrap().last_cue_at = Some(Instant::now());
+                continue;
+            }
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        let scheduler = voices.entry(voice).or_default();
+        match command {
+            AudioCommand::Schedule(at, cue, volume) => scheduler.schedule(at, cue, volume),
+            AudioCommand::CancelScheduled => scheduler.cancel_scheduled(),
+            AudioCommand::StartPattern(pattern) => scheduler.start_pattern(pattern),
+            AudioCommand::RetimePattern(period, volume) => scheduler.retime(period, volume),
+            AudioCommand::StopPattern => scheduler.stop_pattern(),
+        }
+    }
+}
+
+// turns cues into rodio sources using the configured sounds
+struct CuePlayer {
+    stream: Option<OutputStream>,
+    sounds: CueSounds,
+    samples: HashMap<PathBuf, Result<Arc<[u8]>, String>>, // loaded once per file
+}
+
+impl CuePlayer {
+    fn play(&mut self, cue: Cue, volume: f32, delay: Duration) {
+        let Some(stream) = &self.stream else {
+            return;
+        };
+        let source = match self.sounds.sound_for(cue) {
+            CueSound::Tone(tone) => tone.source(volume),
+            CueSound::File(path) => {
+                let sample = self
+                    .samples
+                    .entry(path.clone())
+                    .or_insert_with(|| load_sample(&path))
+                    .clone()
+                    .and_then(|bytes| sample_source(bytes, volume));
+                match sample {
+                    Ok(source) => source,
+                    Err(e) => {
+                        // a missing or broken file shouldn't silence the cue
+                        eprintln!("Falling back to the default tone for {:?}: {}", cue, e);
+                        cue.default_tone().source(volume)
+                    }
+                }
+            }
+        };
+        stream.mixer().add(source.delay(delay));
+    }
+}
+
+// decides which cues are due; kept free of rodio so the timing can be tested
+#[derive(Default)]
+struct Scheduler {
+    one_shots: Vec<(Instant, Cue, f32)>,
+    pattern: Option<Pattern>,
+    cursor: Option<Instant>, // pattern cues before this were already handed out
+}
+
+impl Scheduler {
+    fn is_idle(&self) -> bool {
+        self.one_shots.is_empty() && self.pattern.is_none()
+    }
+
+    fn schedule(&mut self, at: Instant, cue: Cue, volume: f32) {
+        self.one_shots.push((at, cue, volume));
+    }
+
+    fn cancel_scheduled(&mut self) {
+        self.one_shots.clear();
+    }
+
+    fn start_pattern(&mut self, pattern: Pattern) {
+        self.cursor = Some(pattern.start);
+        self.pattern = if pattern.period.is_zero() {
+            None
+        } else {
+            Some(pattern)
+        };
+    }
+
+    fn retime(&mut self, period: Duration, volume: f32) {
+        if period.is_zero() {
+            return;
+        }
+        if let (Some(pattern), Some(cursor)) = (&mut self.pattern, self.cursor) {
+            let cycles = cursor.saturating_duration_since(pattern.start).as_nanos()
+                / pattern.period.as_nanos();
+            pattern.start += pattern.period * cycles as u32;
+            pattern.period = period;
+            pattern.volume = volume;
+        }
+    }
+
+    fn stop_pattern(&mut self) {
+        self.pattern = None;
+        self.cursor = None;
+    }
+
+    // cues due before now + LOOKAHEAD, in time order; ones already long past are dropped
+    fn take_due(&mut self, now: Instant) -> Vec<(Instant, Cue, f32)> {
+        let until = now + LOOKAHEAD;
+        let too_late = now.checked_sub(LOOKAHEAD).unwrap_or(now);
+        let mut due = Vec::new();
+
+        let (ready, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut self.one_shots)
+            .into_iter()
+            .partition(|(at, _, _)| *at < until);
+        self.one_shots = pending;
+        due.extend(ready);
+
+        if let (Some(pattern), Some(cursor)) = (&self.pattern, self.cursor) {
+            let from = cursor.max(too_late);
+            let first_cycle = (from.saturating_duration_since(pattern.start).as_nanos()
+                / pattern.period.as_nanos())
+            .saturating_sub(1) as u32;
+            let mut cycle = first_cycle;
+            while pattern.start + pattern.period * cycle < until {
+                let cycle_start = pattern.start + pattern.period * cycle;
+                for (offset, cue) in &pattern.events {
+                    let at = cycle_start + *offset;
+                    if at >= from && at < until {
+                        due.push((at, *cue, pattern.volume));
+                    }
+                }
+                cycle += 1;
+            }
+            self.cursor = Some(until);
+        }
+
+        due.retain(|(at, _, _)| *at >= too_late);
+        due.sort_by_key(|(at, _, _)| *at);
+        due
     }
 }
 
 #[cfg(test)]
 #[derive(Clone, Default)]
 pub struct AudioCallRecorder {
-    pub play_count: Arc<Mutex<u32>>,
-    pub last_volume: Arc<Mutex<Option<f32>>>,
+    pub scheduled: Arc<Mutex<Vec<(Instant, Cue, f32)>>>, // pending one-shots
+    pub pattern: Arc<Mutex<Option<Pattern>>>,            // the pattern currently playing
 }
 
 #[cfg(test)]
@@ -56,8 +358,130 @@ impl FakeAudioEngine {
 
 #[cfg(test)]
 impl AudioBackend for FakeAudioEngine {
-    fn play_sound(&mut self, volume: f32) {
-        *self.recorder.play_count.lock().unwrap() += 1;
-        *self.recorder.last_volume.lock().unwrap() = Some(volume);
+    fn schedule(&mut self, at: Instant, cue: Cue, volume: f32) {
+        self.recorder
+            .scheduled
+            .lock()
+            .unwrap()
+            .push((at, cue, volume));
+    }
+
+    fn cancel_scheduled(&mut self) {
+        self.recorder.scheduled.lock().unwrap().clear();
+    }
+
+    fn start_pattern(&mut self, pattern: Pattern) {
+        *self.recorder.pattern.lock().unwrap() = Some(pattern);
+    }
+
+    fn retime_pattern(&mut self, period: Duration, volume: f32) {
+        if let Some(pattern) = self.recorder.pattern.lock().unwrap().as_mut() {
+            pattern.period = period;
+            pattern.volume = volume;
+        }
+    }
+
+    fn stop_pattern(&mut self) {
+        *self.recorder.pattern.lock().unwrap() = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cues(due: &[(Instant, Cue, f32)], start: Instant) -> Vec<(u128, Cue)> {
+        due.iter()
+            .map(|(at, cue, _)| (at.duration_since(start).as_millis(), *cue))
+            .collect()
+    }
+
+    #[test]
+    fn test_pattern_cues_land_on_exact_offsets() {
+        let start = Instant::now();
+        let mut scheduler = Scheduler::default();
+        scheduler.start_pattern(Pattern {
+            start,
+            period: Duration::from_secs(3),
+            events: vec![
+                (Duration::ZERO, Cue::Eccentric),
+                (Duration::from_secs(2), Cue::Concentric),
+                (Duration::from_secs(3), Cue::RepComplete),
+            ],
+            volume: 0.1,
+        });
+
+        assert_eq!(
+            cues(&scheduler.take_due(start), start),
+            vec![(0, Cue::Eccentric)]
+        );
+        assert!(
+            scheduler
+                .take_due(start + Duration::from_millis(1000))
+                .is_empty()
+        );
+
+        // late wake-ups still hand out each cue once, at its own timestamp
+        let due = scheduler.take_due(start + Duration::from_millis(1970));
+        assert_eq!(cues(&due, start), vec![(2000, Cue::Concentric)]);
+        assert!(
+            scheduler
+                .take_due(start + Duration::from_millis(1990))
+                .is_empty()
+        );
+
+        let due = scheduler.take_due(start + Duration::from_millis(2980));
+        assert_eq!(
+            cues(&due, start),
+            vec![(3000, Cue::RepComplete), (3000, Cue::Eccentric)]
+        );
+    }
+
+    #[test]
+    fn test_retime_and_stop() {
+        let start = Instant::now();
+        let mut scheduler = Scheduler::default();
+        scheduler.start_pattern(Pattern {
+            start,
+            period: Duration::from_secs(1),
+            events: vec![(Duration::ZERO, Cue::MetronomeBeat)],
+            volume: 0.1,
+        });
+        scheduler.take_due(start + Duration::from_millis(1500));
+
+        // the next beat moves from 2s to 1.8s, two new periods after the 1s beat
+        scheduler.retime(Duration::from_millis(400), 0.2);
+        assert!(
+            scheduler
+                .take_due(start + Duration::from_millis(1600))
+                .is_empty()
+        );
+        let due = scheduler.take_due(start + Duration::from_millis(1760));
+        assert_eq!(cues(&due, start), vec![(1800, Cue::MetronomeBeat)]);
+        assert_eq!(due[0].2, 0.2);
+
+        scheduler.stop_pattern();
+        assert!(scheduler.is_idle());
+        assert!(
+            scheduler
+                .take_due(start + Duration::from_secs(5))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_one_shots_and_cancel() {
+        let start = Instant::now();
+        let mut scheduler = Scheduler::default();
+        scheduler.schedule(start + Duration::from_secs(60), Cue::RestFinished, 10.0);
+        assert!(scheduler.take_due(start).is_empty());
+        assert_eq!(
+            cues(&scheduler.take_due(start + Duration::from_secs(60)), start),
+            vec![(60000, Cue::RestFinished)]
+        );
+
+        scheduler.schedule(start + Duration::from_secs(90), Cue::RestFinished, 10.0);
+        scheduler.cancel_scheduled();
+        assert!(scheduler.is_idle());
     }
 }
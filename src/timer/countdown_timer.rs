@@ -1,106 +1,137 @@
 use crate::timer::Timer;
 use crate::timer::audio_engine::{AudioBackend, AudioEngine};
+use crate::timer::clock::{Clock, SystemClock};
+use crate::timer::cue::Cue;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 pub struct CountDownTimer {
     pub input_minutes: u32,
     pub input_seconds: u32,
-    pub current_seconds: u32,
-    pub volume: f32,
+    pub current_seconds: u32, // remaining time rounded up, for display
+    pub volume: f32,          // percent
     pub is_running: bool,
-    pub last_tick: Option<Instant>,
     pub audio_engine: Box<dyn AudioBackend>,
+    pub finish_cue: Cue,
+    pub countdown_beeps: bool, // 3-2-1 before the finish cue
+    remaining: Duration,       // exact remaining time while paused
+    deadline: Option<Instant>, // set while running
+    finished_at: Option<Instant>,
+    clock: Arc<dyn Clock>,
 }
 
 impl CountDownTimer {
-    pub fn new() -> Self {
-        Self {
-            input_minutes: 0,
-            input_seconds: 0,
-            current_seconds: 0,
-            volume: 10.0,
-            is_running: false,
-            last_tick: None,
-            audio_engine: Box::new(AudioEngine::new()),
-        }
+    pub fn new(audio: &AudioEngine, finish_cue: Cue) -> Self {
+        let mut timer = Self::with_engine(Box::new(audio.voice()), Arc::new(SystemClock));
+        timer.finish_cue = finish_cue;
+        timer
     }
 
-    #[cfg(test)]
-    pub fn with_engine(audio_engine: Box<dyn AudioBackend>) -> Self {
+    pub fn with_engine(audio_engine: Box<dyn AudioBackend>, clock: Arc<dyn Clock>) -> Self {
         Self {
             input_minutes: 0,
             input_seconds: 0,
             current_seconds: 0,
             volume: 10.0,
             is_running: false,
-            last_tick: None,
             audio_engine,
+            finish_cue: Cue::RestFinished,
+            countdown_beeps: true,
+            remaining: Duration::ZERO,
+            deadline: None,
+            finished_at: None,
+            clock,
         }
     }
 
-    #[cfg(test)]
-    pub fn advance_seconds(&mut self, seconds: u32) {
+    // loads a new duration, stopping the countdown
+    pub fn load_seconds(&mut self, seconds: u32) {
+        self.input_minutes = seconds / 60;
+        self.input_seconds = seconds % 60;
+        self.current_seconds = 0;
         if self.is_running {
-            if self.current_seconds > seconds {
-                self.current_seconds -= seconds;
-                // prevent immediate double-tick if real tick is called
-                self.last_tick = Some(Instant::now());
-            } else {
-                self.current_seconds = 0;
-                self.is_running = false;
-                self.last_tick = None;
-                self.audio_engine.play_sound(self.volume);
+            self.audio_engine.cancel_scheduled();
+        }
+        self.is_running = false;
+        self.remaining = Duration::ZERO;
+        self.deadline = None;
+        self.finished_at = None;
+    }
+
+    // loads a new duration and (re)starts the countdown from it
+    pub fn start_with_seconds(&mut self, seconds: u32) {
+        self.load_seconds(seconds);
+        self.toggle();
+    }
+
+    // starts counting from a given instant, so chained timers don't lose the frame latency
+    pub fn start_from(&mut self, start: Instant) {
+        if self.remaining.is_zero() {
+            self.remaining =
+                Duration::from_secs((self.input_minutes * 60 + self.input_seconds) as u64);
+        }
+        if self.remaining.is_zero() {
+            self.current_seconds = 0;
+            return;
+        }
+        let deadline = start + self.remaining;
+        self.is_running = true;
+        self.deadline = Some(deadline);
+        self.finished_at = None;
+        // the audio thread plays the alarm on time even if the UI is not repainting
+        let volume = self.volume / 100.0;
+        if self.countdown_beeps {
+            for seconds_left in (1..=3).rev() {
+                let at = deadline - Duration::from_secs(seconds_left);
+                if at > start {
+                    self.audio_engine.schedule(at, Cue::Countdown, volume);
+                }
             }
         }
+        self.audio_engine
+            .schedule(deadline, self.finish_cue, volume);
+        self.update_remaining(self.clock.now());
     }
 
-    #[cfg(test)]
-    pub fn set_duration(&mut self, minutes: u32, seconds: u32) {
-        self.input_minutes = minutes;
-        self.input_seconds = seconds;
-        self.current_seconds = 0;
-        self.is_running = false;
-        self.last_tick = None;
+    // when the last countdown actually ran out, which may be slightly before it was noticed
+    pub fn finished_at(&self) -> Option<Instant> {
+        self.finished_at
     }
 
     pub fn minutes_and_seconds(&self) -> (u32, u32) {
         (self.current_seconds / 60, self.current_seconds % 60)
     }
+
+    fn update_remaining(&mut self, now: Instant) {
+        if let Some(deadline) = self.deadline {
+            self.remaining = deadline.saturating_duration_since(now);
+        }
+        self.current_seconds = self.remaining.as_nanos().div_ceil(1_000_000_000) as u32;
+    }
 }
 
 impl Timer for CountDownTimer {
     fn toggle(&mut self) {
         if self.is_running {
+            self.update_remaining(self.clock.now());
             self.is_running = false;
-            self.last_tick = None;
+            self.deadline = None;
+            self.audio_engine.cancel_scheduled();
         } else {
-            if self.current_seconds == 0 {
-                self.current_seconds = self.input_minutes * 60 + self.input_seconds;
-            }
-
-            if self.current_seconds > 0 {
-                self.is_running = true;
-                self.last_tick = Some(Instant::now());
-            }
+            let now = self.clock.now();
+            self.start_from(now);
         }
     }
 
     fn tick(&mut self) {
-        if self.is_running {
-            if let Some(last) = self.last_tick {
-                if last.elapsed() >= Duration::from_secs(1) {
-                    if self.current_seconds > 0 {
-                        self.current_seconds -= 1;
-                        self.last_tick = Some(Instant::now());
-                    }
-
-                    if self.current_seconds == 0 {
-                        self.is_running = false;
-                        self.last_tick = None;
-                        self.audio_engine.play_sound(self.volume);
-                    }
-                }
-            }
+        if !self.is_running {
+            return;
+        }
+        let now = self.clock.now();
+        self.update_remaining(now);
+        if self.remaining.is_zero() {
+            self.is_running = false;
+            self.finished_at = self.deadline.take();
         }
     }
 }
@@ -109,11 +140,18 @@ impl Timer for CountDownTimer {
 mod tests {
     use super::*;
     use crate::timer::audio_engine::{AudioCallRecorder, FakeAudioEngine};
+    use crate::timer::clock::{Clock, FakeClock};
+
+    fn fake_timer(recorder: AudioCallRecorder) -> (CountDownTimer, Arc<FakeClock>) {
+        let clock = Arc::new(FakeClock::new());
+        let timer =
+            CountDownTimer::with_engine(Box::new(FakeAudioEngine::new(recorder)), clock.clone());
+        (timer, clock)
+    }
 
     #[test]
     fn test_new_timer_not_running() {
-        let recorder = AudioCallRecorder::default();
-        let timer = CountDownTimer::with_engine(Box::new(FakeAudioEngine::new(recorder)));
+        let (timer, _) = fake_timer(AudioCallRecorder::default());
 
         assert!(!timer.is_running);
         assert_eq!(timer.current_seconds, 0);
@@ -121,9 +159,8 @@ mod tests {
 
     #[test]
     fn test_toggle_starts_timer_with_duration() {
-        let recorder = AudioCallRecorder::default();
-        let mut timer = CountDownTimer::with_engine(Box::new(FakeAudioEngine::new(recorder)));
-        timer.set_duration(0, 5);
+        let (mut timer, _) = fake_timer(AudioCallRecorder::default());
+        timer.load_seconds(5);
 
         timer.toggle();
 
@@ -133,21 +170,19 @@ mod tests {
 
     #[test]
     fn test_toggle_stops_timer() {
-        let recorder = AudioCallRecorder::default();
-        let mut timer = CountDownTimer::with_engine(Box::new(FakeAudioEngine::new(recorder)));
-        timer.set_duration(0, 5);
+        let (mut timer, _) = fake_timer(AudioCallRecorder::default());
+        timer.load_seconds(5);
         timer.toggle(); // Start
         timer.toggle(); // Stop
 
         assert!(!timer.is_running);
-        assert!(timer.last_tick.is_none());
+        assert!(timer.deadline.is_none());
     }
 
     #[test]
     fn test_toggle_with_zero_duration_does_not_start() {
-        let recorder = AudioCallRecorder::default();
-        let mut timer = CountDownTimer::with_engine(Box::new(FakeAudioEngine::new(recorder)));
-        timer.set_duration(0, 0);
+        let (mut timer, _) = fake_timer(AudioCallRecorder::default());
+        timer.load_seconds(0);
 
         timer.toggle();
 
@@ -157,11 +192,10 @@ mod tests {
 
     #[test]
     fn test_tick_decrements_seconds() {
-        let recorder = AudioCallRecorder::default();
-        let mut timer = CountDownTimer::with_engine(Box::new(FakeAudioEngine::new(recorder)));
-        timer.set_duration(0, 3);
+        let (mut timer, clock) = fake_timer(AudioCallRecorder::default());
+        timer.load_seconds(3);
         timer.toggle();
-        timer.last_tick = Some(Instant::now() - Duration::from_secs(1));
+        clock.advance(Duration::from_secs(1));
 
         timer.tick();
 
@@ -170,60 +204,68 @@ mod tests {
     }
 
     #[test]
-    fn test_tick_plays_sound_when_timer_completes() {
+    fn test_start_schedules_alarm_at_deadline() {
         let recorder = AudioCallRecorder::default();
-        let mut timer =
-            CountDownTimer::with_engine(Box::new(FakeAudioEngine::new(recorder.clone())));
-        timer.set_duration(0, 1);
+        let (mut timer, clock) = fake_timer(recorder.clone());
+        let started = clock.now();
+        timer.load_seconds(1);
         timer.toggle();
-        timer.last_tick = Some(Instant::now() - Duration::from_secs(2));
 
+        assert_eq!(
+            *recorder.scheduled.lock().unwrap(),
+            vec![(started + Duration::from_secs(1), Cue::RestFinished, 0.1)]
+        );
+
+        clock.advance(Duration::from_secs(2));
         timer.tick();
 
         assert_eq!(timer.current_seconds, 0);
         assert!(!timer.is_running);
-        assert_eq!(*recorder.play_count.lock().unwrap(), 1);
-        assert_eq!(*recorder.last_volume.lock().unwrap(), Some(10.0));
+        assert_eq!(timer.finished_at(), Some(started + Duration::from_secs(1)));
     }
 
     #[test]
-    fn test_tick_does_not_play_sound_before_completion() {
+    fn test_pause_cancels_alarm() {
         let recorder = AudioCallRecorder::default();
-        let mut timer =
-            CountDownTimer::with_engine(Box::new(FakeAudioEngine::new(recorder.clone())));
-        timer.set_duration(0, 5);
+        let (mut timer, clock) = fake_timer(recorder.clone());
+        timer.load_seconds(5);
         timer.toggle();
-        timer.last_tick = Some(Instant::now() - Duration::from_secs(1));
-
+        clock.advance(Duration::from_secs(1));
         timer.tick();
 
+        timer.toggle();
+
         assert_eq!(timer.current_seconds, 4);
-        assert!(timer.is_running);
-        assert_eq!(*recorder.play_count.lock().unwrap(), 0);
+        assert!(recorder.scheduled.lock().unwrap().is_empty());
+
+        // resuming reschedules for the remaining 4s
+        timer.toggle();
+        assert_eq!(
+            recorder.scheduled.lock().unwrap().last(),
+            Some(&(clock.now() + Duration::from_secs(4), Cue::RestFinished, 0.1))
+        );
     }
 
     #[test]
     fn test_tick_respects_one_second_interval() {
         let recorder = AudioCallRecorder::default();
-        let mut timer =
-            CountDownTimer::with_engine(Box::new(FakeAudioEngine::new(recorder.clone())));
-        timer.set_duration(0, 5);
+        let (mut timer, clock) = fake_timer(recorder.clone());
+        timer.load_seconds(5);
         timer.toggle();
-        timer.last_tick = Some(Instant::now()); // Just now, not enough time elapsed
+        clock.advance(Duration::from_millis(999)); // not a full second yet
 
         timer.tick();
 
         assert_eq!(timer.current_seconds, 5); // Should not decrement yet
-        assert_eq!(*recorder.play_count.lock().unwrap(), 0);
+        assert!(timer.is_running);
     }
 
     #[test]
-    fn test_set_duration_resets_timer() {
-        let recorder = AudioCallRecorder::default();
-        let mut timer = CountDownTimer::with_engine(Box::new(FakeAudioEngine::new(recorder)));
-        timer.set_duration(0, 5);
+    fn test_load_seconds_resets_timer() {
+        let (mut timer, _) = fake_timer(AudioCallRecorder::default());
+        timer.load_seconds(5);
         timer.toggle();
-        timer.set_duration(1, 30);
+        timer.load_seconds(90);
 
         assert_eq!(timer.input_minutes, 1);
         assert_eq!(timer.input_seconds, 30);
@@ -231,22 +273,122 @@ mod tests {
         assert!(!timer.is_running);
     }
 
+    #[test]
+    fn test_start_with_seconds_restarts_running_timer() {
+        let (mut timer, clock) = fake_timer(AudioCallRecorder::default());
+        timer.load_seconds(5);
+        timer.toggle();
+        clock.advance(Duration::from_secs(3));
+        timer.tick();
+
+        timer.start_with_seconds(90);
+
+        assert!(timer.is_running);
+        assert_eq!(timer.current_seconds, 90);
+        assert_eq!(timer.input_minutes, 1);
+        assert_eq!(timer.input_seconds, 30);
+    }
+
+    #[test]
+    fn test_start_with_zero_seconds_stops_timer() {
+        let (mut timer, _) = fake_timer(AudioCallRecorder::default());
+        timer.load_seconds(5);
+        timer.toggle();
+
+        timer.start_with_seconds(0);
+
+        assert!(!timer.is_running);
+        assert!(timer.deadline.is_none());
+    }
+
     #[test]
     fn test_multiple_ticks_until_completion() {
         let recorder = AudioCallRecorder::default();
-        let mut timer =
-            CountDownTimer::with_engine(Box::new(FakeAudioEngine::new(recorder.clone())));
-        timer.set_duration(0, 3);
+        let (mut timer, clock) = fake_timer(recorder.clone());
+        timer.load_seconds(3);
         timer.toggle();
 
         // Tick 3 times to complete
         for _ in 0..3 {
-            timer.last_tick = Some(Instant::now() - Duration::from_secs(2));
+            clock.advance(Duration::from_secs(1));
             timer.tick();
         }
 
         assert_eq!(timer.current_seconds, 0);
         assert!(!timer.is_running);
-        assert_eq!(*recorder.play_count.lock().unwrap(), 1);
+        assert_eq!(recorder.scheduled.lock().unwrap().len(), 3); // 2, 1, finish
+    }
+
+    #[test]
+    fn test_late_frames_do_not_drift() {
+        let recorder = AudioCallRecorder::default();
+        let (mut timer, clock) = fake_timer(recorder.clone());
+        timer.load_seconds(10);
+        timer.toggle();
+
+        // frames arriving 1.3s apart used to lose 0.3s on every tick
+        for _ in 0..7 {
+            clock.advance_secs_f64(1.3);
+            timer.tick();
+        }
+        assert!(timer.is_running);
+        assert_eq!(timer.current_seconds, 1); // 0.9s left
+
+        clock.advance_secs_f64(0.9);
+        timer.tick();
+        assert!(!timer.is_running);
+    }
+
+    #[test]
+    fn test_pause_keeps_remaining_time() {
+        let (mut timer, clock) = fake_timer(AudioCallRecorder::default());
+        timer.load_seconds(5);
+        timer.toggle();
+        clock.advance_secs_f64(2.5);
+        timer.toggle(); // pause with 2.5s left
+
+        clock.advance(Duration::from_secs(60));
+        timer.tick();
+        assert_eq!(timer.current_seconds, 3);
+
+        timer.toggle(); // resume
+        clock.advance_secs_f64(2.4);
+        timer.tick();
+        assert!(timer.is_running);
+        clock.advance_secs_f64(0.1);
+        timer.tick();
+        assert!(!timer.is_running);
+    }
+
+    #[test]
+    fn test_countdown_beeps_before_finish() {
+        let recorder = AudioCallRecorder::default();
+        let (mut timer, clock) = fake_timer(recorder.clone());
+        let started = clock.now();
+        timer.finish_cue = Cue::EmomWorkEnd;
+        timer.load_seconds(10);
+        timer.toggle();
+
+        let scheduled: Vec<(u64, Cue)> = recorder
+            .scheduled
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(at, cue, _)| (at.duration_since(started).as_secs(), *cue))
+            .collect();
+        assert_eq!(
+            scheduled,
+            vec![
+                (7, Cue::Countdown),
+                (8, Cue::Countdown),
+                (9, Cue::Countdown),
+                (10, Cue::EmomWorkEnd)
+            ]
+        );
+
+        timer.countdown_beeps = false;
+        timer.load_seconds(10);
+        timer.toggle();
+        assert_eq!(recorder.scheduled.lock().unwrap().len(), 1);
     }
 }
@@ -0,0 +1,262 @@
+use rodio::source::SineWave;
+use rodio::{Decoder, Source};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+// every event that makes a sound; each one can be given its own tone or sample
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Cue {
+    MetronomeBeat,
+    RestFinished,
+    EmomWorkEnd,
+    EmomRestEnd,
+    Countdown, // 3-2-1 before a timer finishes
+    Eccentric,
+    BottomPause,
+    Concentric,
+    TopPause,
+    RepComplete,
+}
+
+impl Cue {
+    pub const ALL: [Cue; 10] = [
+        Cue::MetronomeBeat,
+        Cue::RestFinished,
+        Cue::EmomWorkEnd,
+        Cue::EmomRestEnd,
+        Cue::Countdown,
+        Cue::Eccentric,
+        Cue::BottomPause,
+        Cue::Concentric,
+        Cue::TopPause,
+        Cue::RepComplete,
+    ];
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Cue::MetronomeBeat => "Metronome beat",
+            Cue::RestFinished => "Rest finished",
+            Cue::EmomWorkEnd => "EMOM work end",
+            Cue::EmomRestEnd => "EMOM rest end",
+            Cue::Countdown => "3-2-1 countdown",
+            Cue::Eccentric => "Tempo: lowering",
+            Cue::BottomPause => "Tempo: bottom pause",
+            Cue::Concentric => "Tempo: lifting",
+            Cue::TopPause => "Tempo: top pause",
+            Cue::RepComplete => "Tempo: rep done",
+        }
+    }
+
+    pub fn default_tone(&self) -> Tone {
+        match self {
+            Cue::MetronomeBeat => Tone::High,
+            Cue::RestFinished => Tone::Long,
+            Cue::EmomWorkEnd => Tone::Chime,
+            Cue::EmomRestEnd => Tone::Long,
+            Cue::Countdown => Tone::Click,
+            Cue::Eccentric => Tone::Low,
+            Cue::BottomPause => Tone::Tick,
+            Cue::Concentric => Tone::Higher,
+            Cue::TopPause => Tone::Tick,
+            Cue::RepComplete => Tone::Chime,
+        }
+    }
+}
+
+// shown next to the running metronome, so kept short
+impl Display for Cue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Cue::MetronomeBeat => "Beat",
+            Cue::RestFinished | Cue::EmomRestEnd => "Rest over",
+            Cue::EmomWorkEnd => "Work over",
+            Cue::Countdown => "Countdown",
+            Cue::Eccentric => "Down",
+            Cue::BottomPause => "Pause",
+            Cue::Concentric => "Up",
+            Cue::TopPause => "Hold",
+            Cue::RepComplete => "Rep",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+// synthesized sounds, so the app works without any sample files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Tone {
+    Click,
+    Tick,
+    Low,
+    Mid,
+    High,
+    Higher,
+    Chime,
+    Long,
+}
+
+impl Tone {
+    pub const ALL: [Tone; 8] = [
+        Tone::Click,
+        Tone::Tick,
+        Tone::Low,
+        Tone::Mid,
+        Tone::High,
+        Tone::Higher,
+        Tone::Chime,
+        Tone::Long,
+    ];
+
+    // (frequency in Hz, duration in ms)
+    fn frequency_and_millis(&self) -> (f32, u64) {
+        match self {
+            Tone::Click => (1760.0, 30),
+            Tone::Tick => (660.0, 60),
+            Tone::Low => (440.0, 150),
+            Tone::Mid => (660.0, 120),
+            Tone::High => (880.0, 100),
+            Tone::Higher => (990.0, 150),
+            Tone::Chime => (1320.0, 250),
+            Tone::Long => (880.0, 600),
+        }
+    }
+
+    pub fn source(&self, volume: f32) -> Box<dyn Source + Send> {
+        let (frequency, millis) = self.frequency_and_millis();
+        Box::new(
+            SineWave::new(frequency)
+                .take_duration(Duration::from_millis(millis))
+                .amplify(volume),
+        )
+    }
+}
+
+impl Display for Tone {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (frequency, millis) = self.frequency_and_millis();
+        write!(f, "{:?} ({} Hz, {} ms)", self, frequency, millis)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CueSound {
+    Tone(Tone),
+    File(PathBuf), // WAV or OGG
+}
+
+// the sound chosen for each cue; cues without a choice use their default tone
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct CueSounds(HashMap<Cue, CueSound>);
+
+impl CueSounds {
+    pub fn sound_for(&self, cue: Cue) -> CueSound {
+        self.0
+            .get(&cue)
+            .cloned()
+            .unwrap_or(CueSound::Tone(cue.default_tone()))
+    }
+
+    pub fn set(&mut self, cue: Cue, sound: CueSound) {
+        if sound == CueSound::Tone(cue.default_tone()) {
+            self.0.remove(&cue);
+        } else {
+            self.0.insert(cue, sound);
+        }
+    }
+}
+
+// reads a sample into memory and checks rodio can decode it
+pub fn load_sample(path: &Path) -> Result<Arc<[u8]>, String> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase());
+    if !matches!(extension.as_deref(), Some("wav") | Some("ogg")) {
+        return Err("Only WAV and OGG files are supported".to_string());
+    }
+    let bytes: Arc<[u8]> = std::fs::read(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+        .into();
+    Decoder::new(Cursor::new(bytes.clone()))
+        .map_err(|e| format!("Failed to decode {}: {}", path.display(), e))?;
+    Ok(bytes)
+}
+
+pub fn sample_source(bytes: Arc<[u8]>, volume: f32) -> Result<Box<dyn Source + Send>, String> {
+    let decoder = Decoder::new(Cursor::new(bytes)).map_err(|e| e.to_string())?;
+    Ok(Box::new(decoder.amplify(volume)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cue_sounds_fall_back_to_default_tone() {
+        let mut sounds = CueSounds::default();
+        assert_eq!(
+            sounds.sound_for(Cue::RestFinished),
+            CueSound::Tone(Tone::Long)
+        );
+
+        sounds.set(Cue::RestFinished, CueSound::File("/tmp/gong.ogg".into()));
+        assert_eq!(
+            sounds.sound_for(Cue::RestFinished),
+            CueSound::File("/tmp/gong.ogg".into())
+        );
+
+        // picking the default again drops the override
+        sounds.set(Cue::RestFinished, CueSound::Tone(Tone::Long));
+        assert_eq!(sounds, CueSounds::default());
+    }
+
+    #[test]
+    fn test_cue_sounds_round_trip_through_json() {
+        let mut sounds = CueSounds::default();
+        sounds.set(Cue::MetronomeBeat, CueSound::Tone(Tone::Click));
+        sounds.set(Cue::EmomWorkEnd, CueSound::File("/tmp/whistle.wav".into()));
+
+        let json = serde_json::to_string(&sounds).unwrap();
+        assert_eq!(serde_json::from_str::<CueSounds>(&json).unwrap(), sounds);
+    }
+
+    #[test]
+    fn test_load_sample_rejects_other_formats() {
+        assert!(load_sample(Path::new("/tmp/song.mp3")).is_err());
+        assert!(load_sample(Path::new("/nonexistent/beep.wav")).is_err());
+    }
+
+    #[test]
+    fn test_load_sample_decodes_wav() {
+        // 8 kHz mono 16-bit PCM, 100 samples of silence
+        let samples = 100u32;
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + samples * 2).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+        wav.extend_from_slice(&8000u32.to_le_bytes());
+        wav.extend_from_slice(&16000u32.to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&(samples * 2).to_le_bytes());
+        wav.resize(wav.len() + samples as usize * 2, 0);
+
+        let path = std::env::temp_dir().join(format!("cue_test_{}.WAV", std::process::id()));
+        std::fs::write(&path, &wav).unwrap();
+        let bytes = load_sample(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let bytes = bytes.expect("wav should decode");
+        assert_eq!(bytes.len(), wav.len());
+        assert!(sample_source(bytes, 1.0).is_ok());
+    }
+}
@@ -1,7 +1,14 @@
 use crate::timer::Timer;
 #[cfg(test)]
 use crate::timer::audio_engine::AudioBackend;
+use crate::timer::audio_engine::AudioEngine;
+#[cfg(test)]
+use crate::timer::clock::Clock;
 use crate::timer::countdown_timer::CountDownTimer;
+use crate::timer::cue::Cue;
+#[cfg(test)]
+use std::sync::Arc;
+use std::time::Instant;
 
 pub struct EMOMTimer {
     pub current_round: u32,
@@ -10,17 +17,19 @@ pub struct EMOMTimer {
     pub work_timer: CountDownTimer,
     pub rest_timer: CountDownTimer,
     pub is_work: bool,
+    finished_rounds: Vec<u32>, // rounds finished since the last take_finished_rounds
 }
 
 impl EMOMTimer {
-    pub fn new() -> Self {
+    pub fn new(audio: &AudioEngine) -> Self {
         Self {
             current_round: 0,
             rounds: 0,
             is_running: false,
-            work_timer: CountDownTimer::new(),
-            rest_timer: CountDownTimer::new(),
+            work_timer: CountDownTimer::new(audio, Cue::EmomWorkEnd),
+            rest_timer: CountDownTimer::new(audio, Cue::EmomRestEnd),
             is_work: true,
+            finished_rounds: Vec::new(),
         }
     }
 
@@ -28,22 +37,48 @@ impl EMOMTimer {
     pub fn with_engines(
         work_engine: Box<dyn AudioBackend>,
         rest_engine: Box<dyn AudioBackend>,
+        clock: Arc<dyn Clock>,
     ) -> Self {
+        let mut work_timer = CountDownTimer::with_engine(work_engine, clock.clone());
+        work_timer.finish_cue = Cue::EmomWorkEnd;
+        let mut rest_timer = CountDownTimer::with_engine(rest_engine, clock);
+        rest_timer.finish_cue = Cue::EmomRestEnd;
         Self {
             current_round: 0,
             rounds: 0,
             is_running: false,
-            work_timer: CountDownTimer::with_engine(work_engine),
-            rest_timer: CountDownTimer::with_engine(rest_engine),
+            work_timer,
+            rest_timer,
             is_work: true,
+            finished_rounds: Vec::new(),
         }
     }
 
-    fn handle_rest_finished(&mut self) {
+    // sets up a fresh run, stopping whatever was in progress
+    pub fn configure(&mut self, rounds: u32, work_seconds: u32, rest_seconds: u32) {
+        self.is_running = false;
+        self.current_round = 0;
+        self.is_work = true;
+        self.rounds = rounds;
+        self.work_timer.load_seconds(work_seconds);
+        self.rest_timer.load_seconds(rest_seconds);
+        self.finished_rounds.clear();
+    }
+
+    pub fn take_finished_rounds(&mut self) -> Vec<u32> {
+        std::mem::take(&mut self.finished_rounds)
+    }
+
+    // each phase starts at the previous phase's deadline rather than when the frame noticed it
+    fn handle_rest_finished(&mut self, finished_at: Option<Instant>) {
+        self.finished_rounds.push(self.current_round);
         if self.current_round < self.rounds {
             self.current_round += 1;
             self.is_work = true;
-            self.work_timer.toggle();
+            match finished_at {
+                Some(start) => self.work_timer.start_from(start),
+                None => self.work_timer.toggle(),
+            }
         } else {
             self.is_running = false;
             self.current_round = 0;
@@ -87,17 +122,21 @@ impl Timer for EMOMTimer {
         if self.is_work {
             self.work_timer.tick();
             if !self.work_timer.is_running && self.work_timer.current_seconds == 0 {
+                let work_finished_at = self.work_timer.finished_at();
                 self.is_work = false;
-                self.rest_timer.toggle();
+                match work_finished_at {
+                    Some(start) => self.rest_timer.start_from(start),
+                    None => self.rest_timer.toggle(),
+                }
 
                 if !self.rest_timer.is_running {
-                    self.handle_rest_finished();
+                    self.handle_rest_finished(work_finished_at);
                 }
             }
         } else {
             self.rest_timer.tick();
             if !self.rest_timer.is_running && self.rest_timer.current_seconds == 0 {
-                self.handle_rest_finished();
+                self.handle_rest_finished(self.rest_timer.finished_at());
             }
         }
     }
@@ -107,19 +146,33 @@ impl Timer for EMOMTimer {
 mod tests {
     use super::*;
     use crate::timer::audio_engine::{AudioCallRecorder, FakeAudioEngine};
+    use crate::timer::clock::FakeClock;
+    use std::time::Duration;
+
+    fn finish_cues(recorder: &AudioCallRecorder) -> usize {
+        recorder
+            .scheduled
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, cue, _)| matches!(cue, Cue::EmomWorkEnd | Cue::EmomRestEnd))
+            .count()
+    }
 
     #[test]
     fn test_emom_transitions() {
         let work_recorder = AudioCallRecorder::default();
         let rest_recorder = AudioCallRecorder::default();
+        let clock = Arc::new(FakeClock::new());
         let mut timer = EMOMTimer::with_engines(
             Box::new(FakeAudioEngine::new(work_recorder.clone())),
             Box::new(FakeAudioEngine::new(rest_recorder.clone())),
+            clock.clone(),
         );
 
         timer.rounds = 2;
-        timer.work_timer.set_duration(0, 5);
-        timer.rest_timer.set_duration(0, 3);
+        timer.work_timer.load_seconds(5);
+        timer.rest_timer.load_seconds(3);
 
         // Start Round 1 Work
         timer.toggle();
@@ -129,7 +182,7 @@ mod tests {
         assert!(timer.work_timer.is_running);
 
         // Finish Work
-        timer.work_timer.advance_seconds(5);
+        clock.advance(Duration::from_secs(5));
         timer.tick(); // Trigger transition
 
         // Check Transition to Rest
@@ -137,10 +190,10 @@ mod tests {
         assert!(!timer.is_work);
         assert!(timer.rest_timer.is_running);
         assert_eq!(timer.rest_timer.current_seconds, 3);
-        assert_eq!(*work_recorder.play_count.lock().unwrap(), 1);
+        assert_eq!(finish_cues(&work_recorder), 1);
 
         // Finish Rest
-        timer.rest_timer.advance_seconds(3);
+        clock.advance(Duration::from_secs(3));
         timer.tick(); // Trigger transition
 
         // Check Transition to Round 2 Work
@@ -148,23 +201,23 @@ mod tests {
         assert_eq!(timer.current_round, 2);
         assert!(timer.is_work);
         assert!(timer.work_timer.is_running);
-        assert_eq!(*rest_recorder.play_count.lock().unwrap(), 1);
+        assert_eq!(finish_cues(&rest_recorder), 1);
 
         // Finish Round 2 Work
-        timer.work_timer.advance_seconds(5);
+        clock.advance(Duration::from_secs(5));
         timer.tick();
 
         // Round 2 Rest
         assert!(!timer.is_work);
         assert!(timer.rest_timer.is_running);
-        assert_eq!(*work_recorder.play_count.lock().unwrap(), 2);
+        assert_eq!(finish_cues(&work_recorder), 2);
 
         // Finish Round 2 Rest (End of EMOM)
-        timer.rest_timer.advance_seconds(3);
+        clock.advance(Duration::from_secs(3));
         timer.tick();
 
         assert!(!timer.is_running);
-        assert_eq!(*rest_recorder.play_count.lock().unwrap(), 2);
+        assert_eq!(finish_cues(&rest_recorder), 2);
         assert_eq!(timer.current_round, 0);
     }
 
@@ -172,19 +225,21 @@ mod tests {
     fn test_zero_rest_duration() {
         let work_recorder = AudioCallRecorder::default();
         let rest_recorder = AudioCallRecorder::default();
+        let clock = Arc::new(FakeClock::new());
         let mut timer = EMOMTimer::with_engines(
             Box::new(FakeAudioEngine::new(work_recorder.clone())),
             Box::new(FakeAudioEngine::new(rest_recorder.clone())),
+            clock.clone(),
         );
 
         timer.rounds = 2;
-        timer.work_timer.set_duration(0, 5);
-        timer.rest_timer.set_duration(0, 0);
+        timer.work_timer.load_seconds(5);
+        timer.rest_timer.load_seconds(0);
 
         timer.toggle();
 
         // Finish Work
-        timer.work_timer.advance_seconds(5);
+        clock.advance(Duration::from_secs(5));
         timer.tick();
 
         // Should have skipped rest and gone to round 2 work immediately
@@ -193,4 +248,70 @@ mod tests {
         assert!(timer.is_work);
         assert!(timer.work_timer.is_running);
     }
+
+    #[test]
+    fn test_configure_and_finished_rounds() {
+        let work_recorder = AudioCallRecorder::default();
+        let rest_recorder = AudioCallRecorder::default();
+        let clock = Arc::new(FakeClock::new());
+        let mut timer = EMOMTimer::with_engines(
+            Box::new(FakeAudioEngine::new(work_recorder)),
+            Box::new(FakeAudioEngine::new(rest_recorder)),
+            clock.clone(),
+        );
+
+        timer.configure(2, 40, 20);
+        assert_eq!(timer.rounds, 2);
+        assert_eq!(timer.work_timer.input_seconds, 40);
+        assert_eq!(timer.rest_timer.input_seconds, 20);
+        assert!(!timer.is_running);
+
+        timer.toggle();
+        clock.advance(Duration::from_secs(40));
+        timer.tick();
+        assert!(timer.take_finished_rounds().is_empty()); // work done, round still in rest
+
+        clock.advance(Duration::from_secs(20));
+        timer.tick();
+        assert_eq!(timer.take_finished_rounds(), vec![1]);
+        assert!(timer.take_finished_rounds().is_empty());
+
+        clock.advance(Duration::from_secs(40));
+        timer.tick();
+        clock.advance(Duration::from_secs(20));
+        timer.tick();
+        assert_eq!(timer.take_finished_rounds(), vec![2]);
+        assert!(!timer.is_running);
+    }
+
+    #[test]
+    fn test_rounds_stay_on_the_minute_with_late_frames() {
+        let work_recorder = AudioCallRecorder::default();
+        let rest_recorder = AudioCallRecorder::default();
+        let clock = Arc::new(FakeClock::new());
+        let mut timer = EMOMTimer::with_engines(
+            Box::new(FakeAudioEngine::new(work_recorder)),
+            Box::new(FakeAudioEngine::new(rest_recorder)),
+            clock.clone(),
+        );
+        timer.configure(3, 40, 20);
+        timer.toggle();
+
+        // each transition is noticed 0.5s late
+        clock.advance_secs_f64(40.5);
+        timer.tick();
+        assert!(!timer.is_work);
+        clock.advance_secs_f64(20.0);
+        timer.tick();
+        assert_eq!(timer.current_round, 2);
+
+        // round 2 started at exactly 60s, so its work ends at 100s
+        clock.advance_secs_f64(39.4);
+        timer.tick();
+        assert!(timer.is_work);
+        assert_eq!(timer.work_timer.current_seconds, 1);
+        clock.advance_secs_f64(0.1);
+        timer.tick();
+        assert!(!timer.is_work);
+    }
 }
@@ -1,34 +1,121 @@
+use crate::tempo::Tempo;
 use crate::timer::Timer;
-use crate::timer::audio_engine::{AudioBackend, AudioEngine};
+use crate::timer::audio_engine::{AudioBackend, AudioEngine, Pattern};
+use crate::timer::clock::{Clock, SystemClock};
+use crate::timer::cue::Cue;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+// the audio thread plays the beats; this side only starts, stops and retimes them,
+// and works out what to display from the start time
 pub struct Metronome {
     pub bpm: f64, // should be fixed to 60
     pub volume: f32,
     pub is_running: bool,
-    pub last_tick: Option<Instant>,
     pub audio_engine: Box<dyn AudioBackend>,
+    // tempo mode: cue each phase of the rep instead of beeping at bpm
+    pub tempo: Option<Tempo>,
+    pub phase_index: usize,
+    pub reps: u32,
+    started_at: Option<Instant>,
+    clock: Arc<dyn Clock>,
 }
 
+const PHASE_CUES: [Cue; 4] = [
+    Cue::Eccentric,
+    Cue::BottomPause,
+    Cue::Concentric,
+    Cue::TopPause,
+];
+
 impl Metronome {
-    pub fn new() -> Self {
-        Self {
-            bpm: 60.0,
-            volume: 10.0,
-            is_running: false,
-            last_tick: None,
-            audio_engine: Box::new(AudioEngine::new()),
-        }
+    pub fn new(audio: &AudioEngine) -> Self {
+        Self::with_engine(Box::new(audio.voice()), Arc::new(SystemClock))
     }
 
-    #[cfg(test)]
-    pub fn with_engine(audio_engine: Box<dyn AudioBackend>) -> Self {
+    pub fn with_engine(audio_engine: Box<dyn AudioBackend>, clock: Arc<dyn Clock>) -> Self {
         Self {
             bpm: 60.0,
             volume: 10.0,
             is_running: false,
-            last_tick: None,
             audio_engine,
+            tempo: None,
+            phase_index: 0,
+            reps: 0,
+            started_at: None,
+            clock,
+        }
+    }
+
+    // a tempo without any timed phase (e.g. 0000) falls back to plain beats
+    pub fn set_tempo(&mut self, tempo: Option<Tempo>) {
+        if self.is_running {
+            self.audio_engine.stop_pattern();
+        }
+        self.tempo = tempo.filter(|t| t.tut_per_rep() > 0);
+        self.is_running = false;
+        self.started_at = None;
+        self.phase_index = 0;
+        self.reps = 0;
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        if volume != self.volume {
+            self.volume = volume;
+            self.retime();
+        }
+    }
+
+    pub fn current_phase(&self) -> Option<Cue> {
+        self.tempo.map(|_| PHASE_CUES[self.phase_index])
+    }
+
+    fn retime(&mut self) {
+        if self.is_running {
+            self.audio_engine
+                .retime_pattern(self.period(), self.volume / 100.0);
+        }
+    }
+
+    fn period(&self) -> Duration {
+        match self.tempo {
+            Some(tempo) => Duration::from_secs(tempo.tut_per_rep() as u64),
+            None => Duration::from_secs_f64(60.0 / self.bpm),
+        }
+    }
+
+    // (offset into the rep, phase index) for each phase that takes time
+    fn phase_offsets(&self) -> Vec<(Duration, usize)> {
+        let mut offsets = Vec::new();
+        let mut offset = Duration::ZERO;
+        if let Some(tempo) = self.tempo {
+            for (index, phase) in tempo.phases().iter().enumerate() {
+                if phase.seconds() > 0 {
+                    offsets.push((offset, index));
+                    offset += Duration::from_secs(phase.seconds() as u64);
+                }
+            }
+        }
+        offsets
+    }
+
+    fn pattern(&self, start: Instant) -> Pattern {
+        let events = if self.tempo.is_some() {
+            let mut events: Vec<(Duration, Cue)> = self
+                .phase_offsets()
+                .into_iter()
+                .map(|(offset, index)| (offset, PHASE_CUES[index]))
+                .collect();
+            events.push((self.period(), Cue::RepComplete));
+            events
+        } else {
+            vec![(Duration::ZERO, Cue::MetronomeBeat)]
+        };
+        Pattern {
+            start,
+            period: self.period(),
+            events,
+            volume: self.volume / 100.0,
         }
     }
 }
@@ -37,62 +124,134 @@ impl Timer for Metronome {
     fn toggle(&mut self) {
         self.is_running = !self.is_running;
         if self.is_running {
-            self.last_tick = Some(Instant::now());
-            self.audio_engine.play_sound(self.volume / 100.0);
+            let now = self.clock.now();
+            self.started_at = Some(now);
+            self.reps = 0;
+            self.audio_engine.start_pattern(self.pattern(now));
+            self.tick();
+        } else {
+            self.started_at = None;
+            self.audio_engine.stop_pattern();
         }
     }
+
+    // display only, the audio thread keeps time on its own
     fn tick(&mut self) {
-        if self.is_running {
-            let interval = Duration::from_secs_f64(60.0 / self.bpm);
-            if let Some(last) = self.last_tick {
-                if last.elapsed() >= interval {
-                    self.audio_engine.play_sound(self.volume);
-                    self.last_tick = Some(Instant::now());
-                }
-            }
+        let Some(started_at) = self.started_at else {
+            return;
+        };
+        if self.tempo.is_none() {
+            return;
+        }
+        let elapsed = self.clock.now().saturating_duration_since(started_at);
+        let period = self.period().as_nanos();
+        self.reps = (elapsed.as_nanos() / period) as u32;
+        let into_rep = Duration::from_nanos((elapsed.as_nanos() % period) as u64);
+        if let Some((_, index)) = self
+            .phase_offsets()
+            .into_iter()
+            .rev()
+            .find(|(offset, _)| *offset <= into_rep)
+        {
+            self.phase_index = index;
         }
     }
 }
 
-// src/timer/metronome.rs (test module)
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::timer::audio_engine::{AudioCallRecorder, FakeAudioEngine};
+    use crate::timer::clock::FakeClock;
+
+    fn fake_metronome(recorder: AudioCallRecorder) -> (Metronome, Arc<FakeClock>) {
+        let clock = Arc::new(FakeClock::new());
+        let fake_engine = Box::new(FakeAudioEngine::new(recorder));
+        (Metronome::with_engine(fake_engine, clock.clone()), clock)
+    }
 
     #[test]
-    fn test_toggle_plays_sound() {
+    fn test_toggle_starts_and_stops_beats() {
         let recorder = AudioCallRecorder::default();
-        let fake_engine = Box::new(FakeAudioEngine::new(recorder.clone()));
-        let mut metronome = Metronome::with_engine(fake_engine);
+        let (mut metronome, clock) = fake_metronome(recorder.clone());
 
-        assert_eq!(*recorder.play_count.lock().unwrap(), 0);
+        metronome.toggle();
+
+        assert_eq!(
+            *recorder.pattern.lock().unwrap(),
+            Some(Pattern {
+                start: clock.now(),
+                period: Duration::from_secs(1),
+                events: vec![(Duration::ZERO, Cue::MetronomeBeat)],
+                volume: 0.1, // 10.0 / 100.0
+            })
+        );
 
         metronome.toggle();
+        assert_eq!(*recorder.pattern.lock().unwrap(), None);
+    }
 
-        assert_eq!(*recorder.play_count.lock().unwrap(), 1);
-        assert_eq!(*recorder.last_volume.lock().unwrap(), Some(0.1)); // 10.0 / 100.0
+    #[test]
+    fn test_volume_change_retimes_running_pattern() {
+        let recorder = AudioCallRecorder::default();
+        let (mut metronome, _) = fake_metronome(recorder.clone());
+
+        // nothing to retime while stopped
+        metronome.set_volume(30.0);
+        assert_eq!(*recorder.pattern.lock().unwrap(), None);
+
+        metronome.toggle();
+        metronome.set_volume(20.0);
+
+        let pattern = recorder.pattern.lock().unwrap().clone().unwrap();
+        assert_eq!(pattern.period, Duration::from_secs(1));
+        assert_eq!(pattern.volume, 0.2);
     }
 
     #[test]
-    fn test_tick_plays_sound_on_interval() {
+    fn test_tempo_mode_cues_each_phase_and_counts_reps() {
         let recorder = AudioCallRecorder::default();
-        let fake_engine = Box::new(FakeAudioEngine::new(recorder.clone()));
-        let mut metronome = Metronome::with_engine(fake_engine);
+        let (mut metronome, clock) = fake_metronome(recorder.clone());
+        metronome.set_tempo(Some("3120".parse().unwrap()));
 
-        metronome.is_running = true;
-        metronome.last_tick = Some(Instant::now());
+        metronome.toggle();
+        let pattern = recorder.pattern.lock().unwrap().clone().unwrap();
+        assert_eq!(pattern.period, Duration::from_secs(6));
+        // top pause is 0s so the next rep starts straight after the concentric
+        assert_eq!(
+            pattern.events,
+            vec![
+                (Duration::ZERO, Cue::Eccentric),
+                (Duration::from_secs(3), Cue::BottomPause),
+                (Duration::from_secs(4), Cue::Concentric),
+                (Duration::from_secs(6), Cue::RepComplete),
+            ]
+        );
+        assert_eq!(metronome.current_phase(), Some(Cue::Eccentric));
 
-        // Tick too soon
+        clock.advance(Duration::from_secs(3));
         metronome.tick();
-        assert_eq!(*recorder.play_count.lock().unwrap(), 0);
+        assert_eq!(metronome.current_phase(), Some(Cue::BottomPause));
 
-        // Simulate time passed
-        metronome.last_tick = Some(Instant::now() - Duration::from_secs(2));
+        clock.advance(Duration::from_secs(1));
         metronome.tick();
+        assert_eq!(metronome.current_phase(), Some(Cue::Concentric));
 
-        assert_eq!(*recorder.play_count.lock().unwrap(), 1);
-        assert_eq!(*recorder.last_volume.lock().unwrap(), Some(10.0));
+        clock.advance(Duration::from_secs(2));
+        metronome.tick();
+        assert_eq!(metronome.current_phase(), Some(Cue::Eccentric));
+        assert_eq!(metronome.reps, 1);
+    }
+
+    #[test]
+    fn test_zero_tempo_falls_back_to_beats() {
+        let recorder = AudioCallRecorder::default();
+        let (mut metronome, _) = fake_metronome(recorder.clone());
+        metronome.set_tempo(Some("0000".parse().unwrap()));
+
+        assert_eq!(metronome.tempo, None);
+        metronome.toggle();
+        let pattern = recorder.pattern.lock().unwrap().clone().unwrap();
+        assert_eq!(pattern.events, vec![(Duration::ZERO, Cue::MetronomeBeat)]);
     }
 }
@@ -1,5 +1,7 @@
-mod audio_engine;
+pub mod audio_engine;
+pub mod clock;
 pub mod countdown_timer;
+pub mod cue;
 pub mod emom_timer;
 pub mod metronome;
 
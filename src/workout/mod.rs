@@ -1,3 +1,4 @@
+pub mod session_summary;
 pub mod start_workout_page;
 pub mod workout_dto;
 pub mod workout_entity;
@@ -0,0 +1,66 @@
+use crate::enums::WeightUnit;
+use crate::personal_record::personal_record_dto::PersonalRecordRes;
+use crate::workout::workout_dto::WorkoutRes;
+use chrono::TimeDelta;
+
+// one exercise of a finished session, volume is load x reps over the completed sets
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExerciseSummary {
+    pub exercise_name: String,
+    pub planned_sets: usize,
+    pub completed_sets: usize,
+    pub volume: f64, // in the session's weight unit
+}
+
+// shown once a session is saved, until another one is started
+#[derive(Debug, Clone)]
+pub struct SessionSummary {
+    pub log_group_id: u32,
+    pub workout_name: String,
+    pub duration: Option<TimeDelta>, // None for drafts saved before sessions were timed
+    pub notes: Option<String>,
+    pub weight_unit: WeightUnit,
+    pub exercises: Vec<ExerciseSummary>,
+    pub records: Vec<PersonalRecordRes>,
+    pub next_workout: Option<WorkoutRes>,
+}
+
+impl SessionSummary {
+    pub fn completed_sets(&self) -> usize {
+        self.exercises.iter().map(|e| e.completed_sets).sum()
+    }
+
+    pub fn planned_sets(&self) -> usize {
+        self.exercises.iter().map(|e| e.planned_sets).sum()
+    }
+
+    pub fn total_volume(&self) -> f64 {
+        self.exercises.iter().map(|e| e.volume).sum()
+    }
+}
+
+// "1h 05m", "42m 10s"
+pub fn format_duration(duration: TimeDelta) -> String {
+    let seconds = duration.num_seconds().max(0);
+    let (hours, minutes) = (seconds / 3600, seconds % 3600 / 60);
+    if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else {
+        format!("{}m {:02}s", minutes, seconds % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(TimeDelta::seconds(42 * 60 + 10)), "42m 10s");
+        assert_eq!(
+            format_duration(TimeDelta::seconds(3600 + 5 * 60 + 59)),
+            "1h 05m"
+        );
+        assert_eq!(format_duration(TimeDelta::seconds(-5)), "0m 00s");
+    }
+}
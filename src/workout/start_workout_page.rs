@@ -1,19 +1,59 @@
-use crate::client::app::PageAction;
-use crate::client::app_utils::CommonUiState;
+use crate::client::app::{EmomSetup, PageAction};
+use crate::client::app_utils::{CommonUiState, effort_combo};
+use crate::enums::{Band, WeightUnit};
+use crate::error::AppError;
+use crate::load::Load;
+use crate::personal_record::personal_record_service::PersonalRecordService;
+use crate::settings::settings_dto::SettingsRes;
+use crate::workout::session_summary::{ExerciseSummary, SessionSummary, format_duration};
 use crate::workout::workout_dto::{RestMinuteAndSeconds, WorkoutExerciseRes, WorkoutRes};
 use crate::workout::workout_service::WorkoutService;
-use crate::workout_log::workout_log_dto::{WorkoutLogGroupReq, WorkoutLogReq};
+use crate::workout::workouts_page::render_band_picker;
+use crate::workout_log::workout_log_dto::{
+    WorkoutLogDetailRes, WorkoutLogGroupReq, WorkoutLogReq, WorkoutSessionDraftReq,
+    WorkoutSessionDraftRes,
+};
 use crate::workout_log::workout_log_service::WorkoutLogService;
+use chrono::{DateTime, Utc};
 use eframe::egui;
+use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Sqlite};
+use std::collections::HashMap;
 use std::sync::mpsc::{Receiver, Sender, channel};
+use std::time::{Duration, Instant};
+
+// minimum time between two draft writes while a session is being edited
+const DRAFT_AUTOSAVE_INTERVAL: Duration = Duration::from_secs(1);
+// how long closing the app waits for a draft save that is already in flight
+const DRAFT_EXIT_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct StartWorkoutPage {
     current_workout_id: Option<u32>,
     // Local state for the active session
     active_session: Option<ActiveSession>,
+    // Pre-fill sets from the last logged session instead of the template targets
+    prefill_from_last_time: bool,
+    // Display unit, new sessions are logged in it
+    weight_unit: WeightUnit,
+    bodyweight_kg: Option<f64>,
+    // Crash-safe draft of the active session
+    draft_id: Option<u32>,
+    draft_dirty: bool,
+    draft_saving: bool,
+    last_draft_save: Option<Instant>,
+    // bumped whenever the session on screen goes away, draft saves report back with theirs
+    draft_session: u64,
+    // closed sessions whose draft save was still in flight
+    closed_drafts: HashMap<u64, ClosedDraft>,
+    // the draft becomes the log, it must not be written again meanwhile
+    log_saving: bool,
+    unfinished_drafts: Vec<WorkoutSessionDraftRes>,
+    show_draft_prompt: bool,
+    // Summary of the last saved session, shown until another one starts
+    summary: Option<SessionSummary>,
     workout_service: WorkoutService,
     workout_log_service: WorkoutLogService,
+    record_service: PersonalRecordService,
     // Async Communication
     receiver: Receiver<StartWorkoutsPageMsg>,
     sender: Sender<StartWorkoutsPageMsg>,
@@ -21,37 +61,150 @@ pub struct StartWorkoutPage {
     common_ui_state: CommonUiState,
 }
 
-// Temporary structs to hold form state
-#[derive(Debug, Clone)]
+// what happens to a closed session's draft once the save in flight returns its id
+enum ClosedDraft {
+    Keep(WorkoutSessionDraftReq), // changes made after that save
+    Discard,
+}
+
+// Temporary structs to hold form state, serialized into the session draft
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct ActiveSession {
     name: String,
+    #[serde(default)]
+    auto_start_rest_timer: bool,
+    #[serde(default)]
+    weight_unit: WeightUnit, // unit every weight in the session is in
+    #[serde(default)]
+    started_at: Option<DateTime<Utc>>,
     exercises: Vec<ActiveExercise>,
     description: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct ActiveExercise {
     workout_exercise_id: u32,
     exercise_name: String,
     reps_or_seconds_target: u8,
-    working_weight: u16,
+    working_weight: f64,
+    #[serde(default)]
+    assistance_band: Option<Band>,
+    #[serde(default)]
+    bodyweight_fraction: f64,
     rest_period_seconds: u16,
     tempo: String,
     emom: bool,
     sets: Vec<ActiveSet>,
+    #[serde(skip)] // fetched again when a draft is resumed
+    previous_sets: Vec<WorkoutLogDetailRes>,
+    #[serde(skip)] // the workout exercise it was started from, also fetched again on resume
+    plan: Option<WorkoutExerciseRes>,
 }
 
 impl ActiveExercise {
-    fn new(res: WorkoutExerciseRes, active_sets: Vec<ActiveSet>) -> Self {
+    fn new(res: WorkoutExerciseRes, weight_unit: WeightUnit, active_sets: Vec<ActiveSet>) -> Self {
         Self {
             workout_exercise_id: res.id,
-            exercise_name: res.name,
+            working_weight: res.working_weight_in(weight_unit),
+            assistance_band: res.assistance_band,
+            bodyweight_fraction: res.bodyweight_fraction,
+            exercise_name: res.name.clone(),
             reps_or_seconds_target: res.reps_or_seconds_target,
-            working_weight: res.working_weight,
             rest_period_seconds: res.rest_period_seconds,
-            tempo: res.tempo,
+            tempo: res.tempo.clone(),
             emom: res.emom,
             sets: active_sets,
+            previous_sets: Vec::new(),
+            plan: Some(res),
+        }
+    }
+
+    fn target_load(&self, weight_unit: WeightUnit) -> Load {
+        Load {
+            weight: self.working_weight,
+            weight_unit,
+            assistance_band: self.assistance_band,
+            bodyweight_fraction: self.bodyweight_fraction,
+        }
+    }
+
+    fn previous_summary(
+        &self,
+        weight_unit: WeightUnit,
+        bodyweight_kg: Option<f64>,
+    ) -> Option<String> {
+        let first = self.previous_sets.first()?;
+        let sets = self
+            .previous_sets
+            .iter()
+            .map(|s| {
+                format!(
+                    "{} @ {}",
+                    s.rep_number_or_seconds,
+                    s.load().describe(weight_unit, bodyweight_kg)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join(", ");
+        Some(format!(
+            "Last time ({}): {} sets - {}",
+            first.workout_date,
+            self.previous_sets.len(),
+            sets
+        ))
+    }
+
+    fn summary(&self, weight_unit: WeightUnit, bodyweight_kg: Option<f64>) -> ExerciseSummary {
+        let completed: Vec<&ActiveSet> = self.sets.iter().filter(|s| s.completed).collect();
+        let is_static = self.plan.as_ref().is_some_and(|plan| plan.is_static);
+        ExerciseSummary {
+            exercise_name: self.exercise_name.clone(),
+            planned_sets: self.sets.len(),
+            completed_sets: completed.len(),
+            volume: completed
+                .iter()
+                .map(|set| {
+                    let load = Load {
+                        weight: set.weight,
+                        weight_unit,
+                        assistance_band: set.assistance_band,
+                        bodyweight_fraction: self.bodyweight_fraction,
+                    };
+                    load.volume_in(weight_unit, bodyweight_kg, set.reps_or_seconds, is_static)
+                })
+                .sum(),
+        }
+    }
+
+    // completed sets are never overwritten, only the ones still to be done,
+    // and sets the user already changed keep their values too
+    fn prefill_sets(&mut self, from_last_time: bool, weight_unit: WeightUnit) {
+        let target = (self.working_weight, self.assistance_band, 0);
+        for set in self.sets.iter_mut().filter(|s| !s.completed) {
+            let previous = self
+                .previous_sets
+                .iter()
+                .find(|p| p.set_number == set.set_number)
+                .or(self.previous_sets.last())
+                .map(|p| {
+                    (
+                        p.weight_in(weight_unit),
+                        p.assistance_band,
+                        p.rep_number_or_seconds,
+                    )
+                });
+
+            let current = (set.weight, set.assistance_band, set.reps_or_seconds);
+            if current != target && Some(current) != previous {
+                continue;
+            }
+            let (weight, assistance_band, reps_or_seconds) = match previous {
+                Some(previous) if from_last_time => previous,
+                _ => target,
+            };
+            set.weight = weight;
+            set.assistance_band = assistance_band;
+            set.reps_or_seconds = reps_or_seconds;
         }
     }
 }
@@ -66,31 +219,51 @@ impl RestMinuteAndSeconds for ActiveExercise {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct ActiveSet {
     set_number: u8,
-    weight: u16,
+    weight: f64, // negative for assistance
+    #[serde(default)]
+    assistance_band: Option<Band>,
     reps_or_seconds: u8,
+    #[serde(default)]
+    rpe: Option<f64>,
+    #[serde(default)]
+    rir: Option<f64>,
     description: String,
     completed: bool,
+    #[serde(default)]
+    completed_at: Option<DateTime<Utc>>,
 }
 
 impl ActiveSet {
-    fn new(set_number: u8, weight: u16) -> Self {
+    fn new(set_number: u8, weight: f64, assistance_band: Option<Band>) -> Self {
         Self {
             set_number,
             weight,
+            assistance_band,
             reps_or_seconds: 0,
+            rpe: None,
+            rir: None,
             description: "".to_string(),
             completed: false,
+            completed_at: None,
         }
     }
 
+    // unchecking and checking again restarts the clock on the set
+    fn mark_completed(&mut self, completed: bool) {
+        self.completed = completed;
+        self.completed_at = completed.then(Utc::now);
+    }
+
     fn to_workout_log_req(
         &self,
         workout_id: u32,
         workout_exercise_id: u32,
         exercise_name: String,
+        weight_unit: WeightUnit,
+        bodyweight_fraction: f64,
     ) -> WorkoutLogReq {
         WorkoutLogReq {
             workout_id,
@@ -99,12 +272,18 @@ impl ActiveSet {
             exercise_name,
             set_number: self.set_number,
             weight: self.weight,
+            weight_unit,
+            assistance_band: self.assistance_band,
+            bodyweight_fraction,
             rep_number_or_seconds: self.reps_or_seconds,
+            rpe: self.rpe,
+            rir: self.rir,
             description: if self.description.is_empty() {
                 None
             } else {
                 Some(self.description.clone())
             },
+            completed_at: self.completed_at,
         }
     }
 }
@@ -115,8 +294,22 @@ impl StartWorkoutPage {
         Self {
             current_workout_id: None,
             active_session: None,
+            prefill_from_last_time: false,
+            weight_unit: WeightUnit::default(),
+            bodyweight_kg: None,
+            draft_id: None,
+            draft_dirty: false,
+            draft_saving: false,
+            last_draft_save: None,
+            draft_session: 0,
+            closed_drafts: HashMap::new(),
+            log_saving: false,
+            unfinished_drafts: Vec::new(),
+            show_draft_prompt: false,
+            summary: None,
             workout_service: WorkoutService::new(pool.clone()),
             workout_log_service: WorkoutLogService::new(pool.clone()),
+            record_service: PersonalRecordService::new(pool.clone()),
             receiver,
             sender,
             common_ui_state: CommonUiState::default(),
@@ -126,44 +319,129 @@ impl StartWorkoutPage {
 
 pub enum StartWorkoutsPageMsg {
     WorkoutLoaded(WorkoutRes, Vec<WorkoutExerciseRes>),
-    Saved,
+    // the workout exercises a resumed draft was started from
+    PlansLoaded(Vec<WorkoutExerciseRes>),
+    // bool: pre-fill the sets still to be done, false when resuming a draft
+    PreviousLogsLoaded(HashMap<u32, Vec<WorkoutLogDetailRes>>, bool),
+    DraftsLoaded(Vec<WorkoutSessionDraftRes>),
+    // the draft session the save was made for, and the draft id
+    DraftSaved(u64, u32),
+    DraftSaveFailed(u64, AppError),
+    DraftDiscarded(u32),
+    Saved(SessionSummary),
+    SaveFailed(AppError),
     Deleted,
-    Error(String),
+    Error(AppError),
 }
 
 impl StartWorkoutPage {
-    fn handle_async_messages(&mut self) {
+    fn handle_async_messages(&mut self, ctx: &egui::Context) {
         while let Ok(msg) = self.receiver.try_recv() {
             self.common_ui_state.set_as_not_loading();
             match msg {
                 StartWorkoutsPageMsg::WorkoutLoaded(workout, exercises) => {
                     // Initialize active session state from the loaded workout
+                    let weight_unit = self.weight_unit;
                     let active_exercises = exercises
                         .iter()
                         .map(|e| {
                             let sets = (1..=e.sets_target)
-                                .map(|i| ActiveSet::new(i, e.working_weight))
+                                .map(|i| {
+                                    ActiveSet::new(
+                                        i,
+                                        e.working_weight_in(weight_unit),
+                                        e.assistance_band,
+                                    )
+                                })
                                 .collect();
-                            ActiveExercise::new(e.clone(), sets)
+                            ActiveExercise::new(e.clone(), weight_unit, sets)
                         })
                         .collect();
 
                     self.active_session = Some(ActiveSession {
                         name: workout.name.clone(),
+                        auto_start_rest_timer: workout.auto_start_rest_timer,
+                        weight_unit,
+                        started_at: Some(Utc::now()),
                         exercises: active_exercises,
                         description: format!("Workout name: {}", workout.name).into(),
                     });
                 }
-                StartWorkoutsPageMsg::Saved => {
+                StartWorkoutsPageMsg::PlansLoaded(plans) => {
+                    if let Some(session) = &mut self.active_session {
+                        for exercise in &mut session.exercises {
+                            exercise.plan = plans
+                                .iter()
+                                .find(|plan| plan.id == exercise.workout_exercise_id)
+                                .cloned();
+                        }
+                    }
+                }
+                StartWorkoutsPageMsg::PreviousLogsLoaded(mut previous_logs, prefill) => {
+                    if let Some(session) = &mut self.active_session {
+                        for exercise in &mut session.exercises {
+                            if let Some(logs) = previous_logs.remove(&exercise.workout_exercise_id)
+                            {
+                                exercise.previous_sets = logs;
+                                if prefill {
+                                    exercise.prefill_sets(
+                                        self.prefill_from_last_time,
+                                        session.weight_unit,
+                                    );
+                                }
+                            }
+                        }
+                    }
+                }
+                StartWorkoutsPageMsg::DraftsLoaded(drafts) => {
+                    // the draft of the session on screen is not "unfinished"
+                    self.unfinished_drafts = drafts
+                        .into_iter()
+                        .filter(|d| Some(d.id) != self.draft_id)
+                        .collect();
+                }
+                StartWorkoutsPageMsg::DraftSaved(draft_session, id) => {
+                    if draft_session == self.draft_session {
+                        self.draft_saving = false;
+                        self.draft_id = Some(id);
+                        continue;
+                    }
+                    // the session was closed while the save was in flight
+                    match self.closed_drafts.remove(&draft_session) {
+                        Some(ClosedDraft::Keep(req)) => {
+                            self.save_draft(ctx, draft_session, Some(id), req);
+                        }
+                        Some(ClosedDraft::Discard) => self.discard_draft(ctx, id),
+                        None => self.fetch_drafts(ctx),
+                    }
+                }
+                StartWorkoutsPageMsg::DraftSaveFailed(draft_session, e) => {
+                    if draft_session == self.draft_session {
+                        self.draft_saving = false;
+                    } else {
+                        self.closed_drafts.remove(&draft_session);
+                    }
+                    self.common_ui_state
+                        .show_error(&format!("Failed to save draft: {}", e));
+                }
+                StartWorkoutsPageMsg::DraftDiscarded(id) => {
+                    self.unfinished_drafts.retain(|d| d.id != id);
+                }
+                StartWorkoutsPageMsg::Saved(summary) => {
                     self.common_ui_state
                         .show_success("Workout logged successfully!");
-                    // Reset state or navigate away?
-                    // For now, we keep the ID but maybe clear the form?
-                    // self.active_session = None;
-                    // self.current_workout_id = None;
+                    // the draft was turned into the log, start fresh
+                    self.log_saving = false;
+                    self.clear_session();
+                    self.summary = Some(summary);
+                }
+                StartWorkoutsPageMsg::SaveFailed(e) => {
+                    // the draft is still there, keep autosaving into it
+                    self.log_saving = false;
+                    self.common_ui_state.show_app_error(&e);
                 }
                 StartWorkoutsPageMsg::Error(e) => {
-                    self.common_ui_state.show_error(&e);
+                    self.common_ui_state.show_app_error(&e);
                 }
                 _ => {}
             }
@@ -172,6 +450,7 @@ impl StartWorkoutPage {
 
     fn render_workout(&mut self, ui: &mut egui::Ui) -> PageAction {
         let mut page_action = PageAction::None;
+        let mut session_changed = false;
 
         if let Some(session) = &mut self.active_session {
             egui::ScrollArea::vertical().show(ui, |ui| {
@@ -190,14 +469,34 @@ impl StartWorkoutPage {
                     } else {
                         Some(description)
                     };
+                    session_changed = true;
                 }
                 ui.button("Go to workout").clicked().then(|| {
                     page_action = PageAction::GoToWorkoutDetails(self.current_workout_id.unwrap());
                 });
+                if ui
+                    .checkbox(
+                        &mut self.prefill_from_last_time,
+                        "Pre-fill sets from last time",
+                    )
+                    .changed()
+                {
+                    for exercise in &mut session.exercises {
+                        exercise.prefill_sets(self.prefill_from_last_time, session.weight_unit);
+                    }
+                    session_changed = true;
+                }
+                session_changed |= ui
+                    .checkbox(
+                        &mut session.auto_start_rest_timer,
+                        "Auto-start rest timer when a set is done",
+                    )
+                    .changed();
                 ui.add_space(10.0);
                 ui.separator();
                 ui.add_space(10.0);
 
+                let weight_suffix = format!(" {}", session.weight_unit);
                 for (ex_idx, exercise) in session.exercises.iter_mut().enumerate() {
                     let id = ui.make_persistent_id(format!("ex_{}", ex_idx));
                     egui::CollapsingHeader::new(&exercise.exercise_name)
@@ -216,8 +515,10 @@ impl StartWorkoutPage {
                                 ui.label("|");
                                 ui.label(
                                     egui::RichText::new(format!(
-                                        "Weight: {}kg",
-                                        exercise.working_weight
+                                        "Load: {}",
+                                        exercise
+                                            .target_load(session.weight_unit)
+                                            .describe(session.weight_unit, self.bodyweight_kg)
                                     ))
                                     .strong(),
                                 );
@@ -228,6 +529,19 @@ impl StartWorkoutPage {
                                     ui.label("|");
                                     ui.label(format!("Tempo: {}", exercise.tempo));
                                 }
+                                if let Some(plan) = &exercise.plan
+                                    && let Some(tempo) = plan.parsed_tempo()
+                                    && tempo.tut_per_rep() > 0
+                                {
+                                    ui.label(plan.tut_summary());
+                                    if ui
+                                        .button("Tempo Metronome")
+                                        .on_hover_text("Cue each phase of the rep")
+                                        .clicked()
+                                    {
+                                        page_action = PageAction::StartTempoMetronome(tempo);
+                                    }
+                                }
                                 if exercise.emom {
                                     ui.label("|");
                                     ui.label(
@@ -235,8 +549,25 @@ impl StartWorkoutPage {
                                             .color(egui::Color32::WHITE)
                                             .background_color(egui::Color32::RED),
                                     );
+                                    if ui
+                                        .button("Start EMOM")
+                                        .on_hover_text("Each finished round checks off a set")
+                                        .clicked()
+                                    {
+                                        page_action = PageAction::StartEmomTimer(EmomSetup {
+                                            workout_exercise_id: exercise.workout_exercise_id,
+                                            rounds: exercise.sets.len() as u32,
+                                            work_seconds: exercise.reps_or_seconds_target as u32,
+                                            rest_seconds: exercise.rest_period_seconds as u32,
+                                        });
+                                    }
                                 }
                             });
+                            if let Some(summary) =
+                                exercise.previous_summary(session.weight_unit, self.bodyweight_kg)
+                            {
+                                ui.label(egui::RichText::new(summary).weak().italics());
+                            }
                             ui.separator();
 
                             egui::Grid::new(format!("grid_{}", ex_idx))
@@ -244,22 +575,46 @@ impl StartWorkoutPage {
                                 .min_col_width(50.0)
                                 .spacing([10.0, 4.0])
                                 .show(ui, |ui| {
+                                    // bands only matter for bodyweight and assisted work
+                                    let show_band = exercise.bodyweight_fraction > 0.0
+                                        || exercise.assistance_band.is_some();
+
                                     ui.label("Set");
-                                    ui.label("Weight");
+                                    ui.label("Added Weight");
+                                    if show_band {
+                                        ui.label("Band");
+                                    }
                                     ui.label("Reps/Seconds");
-                                    ui.label("Description");
+                                    ui.label("RPE");
+                                    ui.label("RIR");
+                                    ui.label("Notes");
                                     ui.label("Done");
                                     ui.end_row();
 
                                     for set in &mut exercise.sets {
                                         ui.label(format!("{}", set.set_number));
 
-                                        let mut w_ui = ui
-                                            .add(egui::DragValue::new(&mut set.weight).speed(0.1));
+                                        let mut w_ui = ui.add(
+                                            egui::DragValue::new(&mut set.weight)
+                                                .speed(0.25)
+                                                .range(-500.0..=1000.0)
+                                                .max_decimals(2)
+                                                .suffix(weight_suffix.as_str()),
+                                        );
                                         if set.completed {
                                             w_ui = w_ui.on_hover_text("Set completed");
                                         }
 
+                                        if show_band {
+                                            let band = set.assistance_band;
+                                            render_band_picker(
+                                                ui,
+                                                &format!("band_{}_{}", ex_idx, set.set_number),
+                                                &mut set.assistance_band,
+                                            );
+                                            session_changed |= band != set.assistance_band;
+                                        }
+
                                         let mut r_ui = ui.add(
                                             egui::DragValue::new(&mut set.reps_or_seconds)
                                                 .speed(0.1),
@@ -268,13 +623,39 @@ impl StartWorkoutPage {
                                             r_ui = r_ui.on_hover_text("Set completed");
                                         }
 
-                                        let mut rpe_ui =
+                                        session_changed |= effort_combo(
+                                            ui,
+                                            &format!("rpe_{}_{}", ex_idx, set.set_number),
+                                            &mut set.rpe,
+                                        );
+                                        session_changed |= effort_combo(
+                                            ui,
+                                            &format!("rir_{}_{}", ex_idx, set.set_number),
+                                            &mut set.rir,
+                                        );
+
+                                        let mut notes_ui =
                                             ui.text_edit_singleline(&mut set.description);
                                         if set.completed {
-                                            rpe_ui = rpe_ui.on_hover_text("Set completed");
+                                            notes_ui = notes_ui.on_hover_text("Set completed");
                                         }
 
-                                        ui.checkbox(&mut set.completed, "");
+                                        let done_ui = ui.checkbox(&mut set.completed, "");
+                                        if done_ui.changed() {
+                                            set.mark_completed(set.completed);
+                                        }
+                                        if done_ui.changed()
+                                            && set.completed
+                                            && session.auto_start_rest_timer
+                                        {
+                                            page_action = PageAction::StartRestTimer(
+                                                exercise.rest_period_seconds,
+                                            );
+                                        }
+                                        session_changed |= w_ui.changed()
+                                            || r_ui.changed()
+                                            || notes_ui.changed()
+                                            || done_ui.changed();
                                         ui.end_row();
                                     }
                                 });
@@ -285,6 +666,9 @@ impl StartWorkoutPage {
         } else {
             ui.label("Loading workout...");
         }
+        if session_changed {
+            self.draft_dirty = true;
+        }
         page_action
     }
 
@@ -294,23 +678,21 @@ impl StartWorkoutPage {
 
         ui.separator();
         ui.horizontal(|ui| {
-            if ui.button("Cancel").clicked() {
-                self.active_session = None;
-                self.current_workout_id = None;
+            // the draft stays around so the session can be resumed later
+            if ui
+                .button("Close")
+                .on_hover_text("Keep the session as a draft")
+                .clicked()
+            {
+                self.close_session(ctx);
             }
 
-            let valid_to_save = self
-                .active_session
-                .as_ref()
-                .map(|s| {
-                    s.exercises
-                        .iter()
-                        .any(|e| e.sets.iter().any(|set| set.completed))
-                })
-                .unwrap_or(false);
+            if ui.button("Discard").clicked() {
+                self.discard_session(ctx);
+            }
 
             if ui
-                .add_enabled(valid_to_save, egui::Button::new("Finish & Save Log"))
+                .add_enabled(self.can_finish(), egui::Button::new("Finish & Save Log"))
                 .clicked()
             {
                 self.save_log(ctx);
@@ -318,15 +700,27 @@ impl StartWorkoutPage {
         });
     }
 
+    // wait for a pending draft save so it can't outlive the log, and save the log only once
+    fn can_finish(&self) -> bool {
+        let any_completed = self.active_session.as_ref().is_some_and(|s| {
+            s.exercises
+                .iter()
+                .any(|e| e.sets.iter().any(|set| set.completed))
+        });
+        any_completed && !self.draft_saving && !self.log_saving && !self.common_ui_state.loading
+    }
+
     fn save_log(&mut self, ctx: &egui::Context) {
         if let Some(session) = &self.active_session {
             self.common_ui_state.set_as_loading();
+            self.log_saving = true;
 
             let current_workout_id = self
                 .current_workout_id
                 .expect("Current workout ID should be set");
 
             // Map ActiveSession to WorkoutLogReq
+            let weight_unit = session.weight_unit;
             let entries: Vec<WorkoutLogReq> = session
                 .exercises
                 .iter()
@@ -336,24 +730,59 @@ impl StartWorkoutPage {
                             current_workout_id,
                             ex.workout_exercise_id,
                             ex.exercise_name.clone(),
+                            weight_unit,
+                            ex.bodyweight_fraction,
                         )
                     })
                 })
                 .collect();
 
-            let log_req = WorkoutLogGroupReq::new(session.description.clone());
+            let finished_at = Utc::now();
+            let log_req = WorkoutLogGroupReq {
+                started_at: session.started_at,
+                finished_at: Some(finished_at),
+                ..WorkoutLogGroupReq::new(session.description.clone())
+            };
+            let draft_id = self.draft_id;
+            let mut summary = SessionSummary {
+                log_group_id: 0,
+                workout_name: session.name.clone(),
+                duration: session
+                    .started_at
+                    .map(|started_at| finished_at - started_at),
+                notes: session.description.clone(),
+                weight_unit,
+                exercises: session
+                    .exercises
+                    .iter()
+                    .map(|e| e.summary(weight_unit, self.bodyweight_kg))
+                    .collect(),
+                records: Vec::new(),
+                next_workout: None,
+            };
 
             let sender = self.sender.clone();
             let service = self.workout_log_service.clone();
+            let record_service = self.record_service.clone();
+            let workout_service = self.workout_service.clone();
             let ctx = ctx.clone();
 
             tokio::spawn(async move {
-                match service.create_log_group(log_req, entries).await {
-                    Ok(_) => {
-                        let _ = sender.send(StartWorkoutsPageMsg::Saved);
+                // the session is saved once the log group is, a failed lookup after that
+                // only leaves its part of the summary empty
+                match service.create_log_group(log_req, entries, draft_id).await {
+                    Ok(log_group_id) => {
+                        summary.log_group_id = log_group_id;
+                        summary.records = record_service
+                            .get_records_by_log_group_id(log_group_id)
+                            .await
+                            .unwrap_or_default();
+                        summary.next_workout =
+                            workout_service.get_next_workout().await.ok().flatten();
+                        let _ = sender.send(StartWorkoutsPageMsg::Saved(summary));
                     }
                     Err(e) => {
-                        let _ = sender.send(StartWorkoutsPageMsg::Error(e));
+                        let _ = sender.send(StartWorkoutsPageMsg::SaveFailed(e));
                     }
                 }
                 ctx.request_repaint();
@@ -362,21 +791,42 @@ impl StartWorkoutPage {
     }
 
     pub fn load_workout(&mut self, ctx: &egui::Context, workout_id: u32) {
+        // an unsaved session in progress is left behind as a draft
+        if self.active_session.is_some() {
+            self.close_session(ctx);
+        }
         self.current_workout_id = Some(workout_id);
         self.active_session = None;
+        self.summary = None;
         self.common_ui_state.set_as_loading();
 
         // Trigger fetch
         let sender = self.sender.clone();
         let service = self.workout_service.clone();
+        let log_service = self.workout_log_service.clone();
         let ctx = ctx.clone();
 
         tokio::spawn(async move {
             match service.get_one(workout_id).await {
                 Ok(workout) => match service.get_all_exercises_by_workout_id(workout_id).await {
                     Ok(exercises) => {
+                        let exercise_ids = exercises.iter().map(|e| e.id).collect();
                         let _ =
                             sender.send(StartWorkoutsPageMsg::WorkoutLoaded(workout, exercises));
+                        match log_service
+                            .get_last_logs_by_workout_exercise_ids(exercise_ids)
+                            .await
+                        {
+                            Ok(previous_logs) => {
+                                let _ = sender.send(StartWorkoutsPageMsg::PreviousLogsLoaded(
+                                    previous_logs,
+                                    true,
+                                ));
+                            }
+                            Err(e) => {
+                                let _ = sender.send(StartWorkoutsPageMsg::Error(e));
+                            }
+                        }
                     }
                     Err(e) => {
                         let _ = sender.send(StartWorkoutsPageMsg::Error(e));
@@ -390,14 +840,478 @@ impl StartWorkoutPage {
         });
     }
 
+    // marks a set done from outside the page, e.g. a finished EMOM round
+    pub fn complete_set(&mut self, workout_exercise_id: u32, set_number: u8) {
+        let Some(session) = &mut self.active_session else {
+            return;
+        };
+        let set = session
+            .exercises
+            .iter_mut()
+            .filter(|e| e.workout_exercise_id == workout_exercise_id)
+            .flat_map(|e| e.sets.iter_mut())
+            .find(|s| s.set_number == set_number && !s.completed);
+        if let Some(set) = set {
+            set.mark_completed(true);
+            self.draft_dirty = true;
+        }
+    }
+
+    pub fn apply_settings(&mut self, settings: &SettingsRes) {
+        self.weight_unit = settings.weight_unit;
+        self.bodyweight_kg = settings.bodyweight_kg;
+    }
+
+    fn clear_session(&mut self) {
+        self.active_session = None;
+        self.current_workout_id = None;
+        self.draft_id = None;
+        self.draft_dirty = false;
+        // a save still in flight belongs to the old session now
+        self.draft_saving = false;
+        self.draft_session += 1;
+    }
+
+    // keeps the draft and lists it with the other unfinished workouts,
+    // changes made since the last autosave are saved first
+    fn close_session(&mut self, ctx: &egui::Context) {
+        if self.draft_dirty
+            && let Some(req) = self.draft_req()
+        {
+            if self.draft_saving {
+                self.closed_drafts
+                    .insert(self.draft_session, ClosedDraft::Keep(req));
+            } else {
+                self.save_draft(ctx, self.draft_session, self.draft_id, req);
+            }
+        }
+        self.clear_session();
+        self.fetch_drafts(ctx);
+    }
+
+    fn discard_session(&mut self, ctx: &egui::Context) {
+        if self.draft_saving {
+            // the draft may not have an id yet, delete it once the save returns one
+            self.closed_drafts
+                .insert(self.draft_session, ClosedDraft::Discard);
+        } else if let Some(draft_id) = self.draft_id {
+            self.discard_draft(ctx, draft_id);
+        }
+        self.clear_session();
+    }
+
+    fn autosave_draft(&mut self, ctx: &egui::Context) {
+        if !self.draft_dirty || self.draft_saving || self.log_saving {
+            return;
+        }
+
+        if let Some(last_save) = self.last_draft_save {
+            let elapsed = last_save.elapsed();
+            if elapsed < DRAFT_AUTOSAVE_INTERVAL {
+                ctx.request_repaint_after(DRAFT_AUTOSAVE_INTERVAL - elapsed);
+                return;
+            }
+        }
+
+        if let Some(req) = self.draft_req() {
+            self.save_draft(ctx, self.draft_session, self.draft_id, req);
+        }
+    }
+
+    // the session on screen as a draft, clears the dirty flag
+    fn draft_req(&mut self) -> Option<WorkoutSessionDraftReq> {
+        let (Some(workout_id), Some(session)) = (self.current_workout_id, &self.active_session)
+        else {
+            return None;
+        };
+        self.draft_dirty = false;
+
+        match serde_json::to_string(session) {
+            Ok(session) => Some(WorkoutSessionDraftReq {
+                workout_id,
+                session,
+            }),
+            Err(e) => {
+                self.common_ui_state
+                    .show_error(&format!("Failed to serialize draft: {}", e));
+                None
+            }
+        }
+    }
+
+    fn save_draft(
+        &mut self,
+        ctx: &egui::Context,
+        draft_session: u64,
+        draft_id: Option<u32>,
+        req: WorkoutSessionDraftReq,
+    ) {
+        if draft_session == self.draft_session {
+            self.draft_saving = true;
+            self.last_draft_save = Some(Instant::now());
+        }
+
+        let sender = self.sender.clone();
+        let service = self.workout_log_service.clone();
+        let ctx = ctx.clone();
+
+        tokio::spawn(async move {
+            match service.save_draft(draft_id, req).await {
+                Ok(id) => {
+                    let _ = sender.send(StartWorkoutsPageMsg::DraftSaved(draft_session, id));
+                }
+                Err(e) => {
+                    let _ = sender.send(StartWorkoutsPageMsg::DraftSaveFailed(draft_session, e));
+                }
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    // the app is closing, so nothing is left to autosave later: waits for the draft saves in
+    // flight, then writes the changes made since the last one. blocks the calling thread, the
+    // saves run on the runtime's workers
+    pub fn save_draft_on_exit(&mut self) {
+        while self.draft_saving || !self.closed_drafts.is_empty() {
+            let Ok(msg) = self.receiver.recv_timeout(DRAFT_EXIT_TIMEOUT) else {
+                println!("Timed out waiting for the session draft to save");
+                return;
+            };
+            match msg {
+                StartWorkoutsPageMsg::DraftSaved(draft_session, id) => {
+                    if draft_session == self.draft_session {
+                        self.draft_saving = false;
+                        self.draft_id = Some(id);
+                        continue;
+                    }
+                    let service = self.workout_log_service.clone();
+                    let result =
+                        match self.closed_drafts.remove(&draft_session) {
+                            Some(ClosedDraft::Keep(req)) => wait_for(async move {
+                                service.save_draft(Some(id), req).await.map(|_| ())
+                            }),
+                            Some(ClosedDraft::Discard) => {
+                                wait_for(async move { service.discard_draft(id).await })
+                            }
+                            None => None,
+                        };
+                    if let Some(Err(e)) = result {
+                        println!("Failed to update a closed session's draft: {}", e);
+                    }
+                }
+                StartWorkoutsPageMsg::DraftSaveFailed(draft_session, _) => {
+                    if draft_session == self.draft_session {
+                        self.draft_saving = false;
+                    } else {
+                        self.closed_drafts.remove(&draft_session);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // a session being logged is written as the log, not as a draft
+        if !self.draft_dirty || self.log_saving {
+            return;
+        }
+        if let Some(req) = self.draft_req() {
+            let service = self.workout_log_service.clone();
+            let draft_id = self.draft_id;
+            match wait_for(async move { service.save_draft(draft_id, req).await }) {
+                Some(Ok(id)) => self.draft_id = Some(id),
+                Some(Err(e)) => println!("Failed to save the session draft: {}", e),
+                None => {}
+            }
+        }
+    }
+
+    fn fetch_drafts(&mut self, ctx: &egui::Context) {
+        let sender = self.sender.clone();
+        let service = self.workout_log_service.clone();
+        let ctx = ctx.clone();
+
+        tokio::spawn(async move {
+            match service.get_unfinished_drafts().await {
+                Ok(drafts) => {
+                    let _ = sender.send(StartWorkoutsPageMsg::DraftsLoaded(drafts));
+                }
+                Err(e) => {
+                    let _ = sender.send(StartWorkoutsPageMsg::Error(e));
+                }
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    fn discard_draft(&mut self, ctx: &egui::Context, draft_id: u32) {
+        let sender = self.sender.clone();
+        let service = self.workout_log_service.clone();
+        let ctx = ctx.clone();
+
+        tokio::spawn(async move {
+            match service.discard_draft(draft_id).await {
+                Ok(_) => {
+                    let _ = sender.send(StartWorkoutsPageMsg::DraftDiscarded(draft_id));
+                }
+                Err(e) => {
+                    let _ = sender.send(StartWorkoutsPageMsg::Error(e));
+                }
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    fn resume_draft(&mut self, ctx: &egui::Context, draft: WorkoutSessionDraftRes) {
+        let session: ActiveSession = match serde_json::from_str(&draft.session) {
+            Ok(session) => session,
+            Err(e) => {
+                self.common_ui_state
+                    .show_error(&format!("Failed to restore draft: {}", e));
+                return;
+            }
+        };
+
+        if self.active_session.is_some() {
+            self.close_session(ctx);
+        }
+        let exercise_ids = session
+            .exercises
+            .iter()
+            .map(|e| e.workout_exercise_id)
+            .collect();
+        self.unfinished_drafts.retain(|d| d.id != draft.id);
+        self.current_workout_id = Some(draft.workout_id);
+        self.active_session = Some(session);
+        self.summary = None;
+        self.draft_id = Some(draft.id);
+        self.draft_dirty = false;
+
+        // restore the plans and the "Last time" hints without touching the restored sets
+        let sender = self.sender.clone();
+        let service = self.workout_log_service.clone();
+        let workout_service = self.workout_service.clone();
+        let workout_id = draft.workout_id;
+        let ctx = ctx.clone();
+
+        tokio::spawn(async move {
+            match workout_service
+                .get_all_exercises_by_workout_id(workout_id)
+                .await
+            {
+                Ok(plans) => {
+                    let _ = sender.send(StartWorkoutsPageMsg::PlansLoaded(plans));
+                }
+                Err(e) => {
+                    let _ = sender.send(StartWorkoutsPageMsg::Error(e));
+                }
+            }
+            match service
+                .get_last_logs_by_workout_exercise_ids(exercise_ids)
+                .await
+            {
+                Ok(previous_logs) => {
+                    let _ = sender.send(StartWorkoutsPageMsg::PreviousLogsLoaded(
+                        previous_logs,
+                        false,
+                    ));
+                }
+                Err(e) => {
+                    let _ = sender.send(StartWorkoutsPageMsg::Error(e));
+                }
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    // returns true if a draft was resumed
+    fn render_unfinished_drafts(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) -> bool {
+        let mut to_resume = None;
+        let mut to_discard = None;
+
+        egui::Grid::new("unfinished_drafts_grid")
+            .striped(true)
+            .spacing([10.0, 4.0])
+            .show(ui, |ui| {
+                for draft in &self.unfinished_drafts {
+                    ui.label(&draft.workout_name);
+                    ui.label(
+                        draft
+                            .updated_at
+                            .with_timezone(&chrono::Local)
+                            .format("%Y-%m-%d %H:%M")
+                            .to_string(),
+                    );
+                    if ui.button("Resume").clicked() {
+                        to_resume = Some(draft.clone());
+                    }
+                    if ui.button("Discard").clicked() {
+                        to_discard = Some(draft.id);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        if let Some(draft_id) = to_discard {
+            self.discard_draft(ctx, draft_id);
+        }
+        if let Some(draft) = to_resume {
+            self.resume_draft(ctx, draft);
+            return true;
+        }
+        false
+    }
+
+    // called on startup, offers to resume sessions left unfinished by a crash or close
+    pub fn check_for_drafts(&mut self, ctx: &egui::Context) {
+        self.show_draft_prompt = true;
+        self.fetch_drafts(ctx);
+    }
+
+    pub fn render_draft_prompt(&mut self, ctx: &egui::Context) -> PageAction {
+        self.handle_async_messages(ctx);
+
+        if !self.show_draft_prompt || self.unfinished_drafts.is_empty() {
+            return PageAction::None;
+        }
+
+        let mut page_action = PageAction::None;
+        egui::Window::new("Unfinished Workout")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("These workouts were not finished. Resume one?");
+                ui.add_space(10.0);
+                if self.render_unfinished_drafts(ctx, ui) {
+                    self.show_draft_prompt = false;
+                    page_action = PageAction::GoToActiveWorkout;
+                }
+                ui.add_space(10.0);
+                if ui.button("Later").clicked() {
+                    self.show_draft_prompt = false;
+                }
+            });
+        page_action
+    }
+
+    fn render_summary(&mut self, ui: &mut egui::Ui) -> PageAction {
+        let mut page_action = PageAction::None;
+        let mut dismiss = false;
+        let Some(summary) = &self.summary else {
+            return page_action;
+        };
+        let unit = summary.weight_unit;
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.label(egui::RichText::new(format!("Finished: {}", summary.workout_name)).strong());
+            ui.label(format!(
+                "Duration: {}",
+                summary
+                    .duration
+                    .map(format_duration)
+                    .unwrap_or("-".to_string())
+            ));
+            ui.label(format!(
+                "Sets: {} of {} planned",
+                summary.completed_sets(),
+                summary.planned_sets()
+            ));
+            ui.label(format!(
+                "Total volume: {}",
+                unit.format(summary.total_volume())
+            ));
+            ui.add_space(10.0);
+
+            egui::Grid::new("session_summary_grid")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("Exercise");
+                    ui.label("Sets Done/Planned");
+                    ui.label("Volume");
+                    ui.end_row();
+
+                    for exercise in &summary.exercises {
+                        ui.label(&exercise.exercise_name);
+                        let sets = format!("{}/{}", exercise.completed_sets, exercise.planned_sets);
+                        if exercise.completed_sets < exercise.planned_sets {
+                            ui.label(egui::RichText::new(sets).color(egui::Color32::ORANGE));
+                        } else {
+                            ui.label(sets);
+                        }
+                        ui.label(unit.format(exercise.volume));
+                        ui.end_row();
+                    }
+                });
+            ui.add_space(10.0);
+
+            ui.heading("New PRs");
+            if summary.records.is_empty() {
+                ui.label("No new records this time.");
+            } else {
+                egui::Grid::new("session_summary_records_grid")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for record in &summary.records {
+                            ui.label(&record.exercise_name);
+                            ui.label(record.record_type.to_string());
+                            ui.label(
+                                egui::RichText::new(record.describe(unit))
+                                    .color(egui::Color32::GOLD)
+                                    .strong(),
+                            );
+                            ui.label(record.describe_previous(unit));
+                            ui.end_row();
+                        }
+                    });
+            }
+            ui.add_space(10.0);
+
+            if let Some(notes) = &summary.notes {
+                ui.label(format!("Notes: {}", notes));
+                ui.add_space(10.0);
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("Open Log").clicked() {
+                    page_action = PageAction::GoToWorkoutLog(summary.log_group_id);
+                }
+                if let Some(next_workout) = &summary.next_workout
+                    && ui
+                        .button(format!("Start Next Workout: {}", next_workout.name))
+                        .clicked()
+                {
+                    page_action = PageAction::GoToStartWorkout(next_workout.id);
+                }
+                if ui.button("Done").clicked() {
+                    dismiss = true;
+                }
+            });
+        });
+
+        if dismiss {
+            self.summary = None;
+        }
+        page_action
+    }
+
     pub fn render_page(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) -> PageAction {
-        self.handle_async_messages();
+        self.handle_async_messages(ctx);
         self.common_ui_state.show_toasts(ui);
 
         ui.heading("Start Workout");
 
+        if self.summary.is_some() {
+            return self.render_summary(ui);
+        }
+
         if self.current_workout_id.is_none() {
             ui.label("No workout selected. Go to Workouts page to start one.");
+            if !self.unfinished_drafts.is_empty() {
+                ui.add_space(10.0);
+                ui.label(egui::RichText::new("Unfinished workouts").strong());
+                self.render_unfinished_drafts(ctx, ui);
+            }
             return PageAction::None;
         }
 
@@ -411,6 +1325,302 @@ impl StartWorkoutPage {
         // Footer Actions
         self.render_log_form(ctx, ui);
 
+        self.autosave_draft(ctx);
+
         page_action
     }
 }
+
+// runs a task on the runtime and blocks until it is done, for when there is no next frame.
+// None if the runtime dropped it
+fn wait_for<T: Send + 'static>(task: impl Future<Output = T> + Send + 'static) -> Option<T> {
+    let (done_sender, done_receiver) = channel();
+    tokio::spawn(async move {
+        let _ = done_sender.send(task.await);
+    });
+    done_receiver.recv().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{IN_MEMORY_DB_URL, init_db};
+    use crate::workout::workout_dto::{
+        WorkoutExerciseReq, WorkoutReq, default_exercise_req, default_workout_req,
+    };
+    use chrono::NaiveDate;
+
+    fn exercise(sets: Vec<ActiveSet>, previous_sets: Vec<WorkoutLogDetailRes>) -> ActiveExercise {
+        ActiveExercise {
+            workout_exercise_id: 1,
+            exercise_name: "Squat".to_string(),
+            reps_or_seconds_target: 5,
+            working_weight: 100.0,
+            assistance_band: None,
+            bodyweight_fraction: 0.0,
+            rest_period_seconds: 120,
+            tempo: String::new(),
+            emom: false,
+            sets,
+            previous_sets,
+            plan: None,
+        }
+    }
+
+    fn previous(set_number: u8, reps: u8, weight: f64) -> WorkoutLogDetailRes {
+        WorkoutLogDetailRes {
+            id: set_number as u32,
+            workout_log_group_id: 1,
+            workout_date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            workout_id: 1,
+            workout_name: "Leg Day".to_string(),
+            workout_exercise_id: 1,
+            workout_exercise_name: "Squat".to_string(),
+            exercise_library_id: None,
+            set_number,
+            rep_number_or_seconds: reps,
+            weight,
+            weight_unit: WeightUnit::Kg,
+            assistance_band: None,
+            bodyweight_fraction: 0.0,
+            rpe: None,
+            rir: None,
+            description: None,
+            completed_at: None,
+            rest_period_seconds: 120,
+            is_static: false,
+        }
+    }
+
+    fn values(exercise: &ActiveExercise) -> Vec<(f64, u8)> {
+        exercise
+            .sets
+            .iter()
+            .map(|s| (s.weight, s.reps_or_seconds))
+            .collect()
+    }
+
+    #[test]
+    fn test_prefill_keeps_sets_the_user_changed() {
+        let mut exercise = exercise(
+            (1..=3).map(|n| ActiveSet::new(n, 100.0, None)).collect(),
+            vec![previous(1, 5, 102.5), previous(2, 4, 102.5)],
+        );
+        exercise.prefill_sets(true, WeightUnit::Kg);
+        assert_eq!(values(&exercise), vec![(102.5, 5), (102.5, 4), (102.5, 4)]);
+
+        // typed into set 2, checked off set 3
+        exercise.sets[1].weight = 105.0;
+        exercise.sets[2].mark_completed(true);
+
+        exercise.prefill_sets(false, WeightUnit::Kg);
+        assert_eq!(values(&exercise), vec![(100.0, 0), (105.0, 4), (102.5, 4)]);
+
+        exercise.prefill_sets(true, WeightUnit::Kg);
+        assert_eq!(values(&exercise), vec![(102.5, 5), (105.0, 4), (102.5, 4)]);
+    }
+
+    #[tokio::test]
+    async fn test_summary_leaves_holds_out_of_volume() {
+        let ctx = egui::Context::default();
+        let pool = init_db(IN_MEMORY_DB_URL).await.unwrap();
+        let l_sit_id = sqlx::query(
+            r#"INSERT INTO exercise_library (
+                name, dynamic_or_static, upper_or_lower, compound_or_isolation
+            ) VALUES ('L-Sit', 'Static', 'Upper', 'Compound')"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap()
+        .last_insert_rowid() as u32;
+        WorkoutService::new(pool.clone())
+            .create(
+                WorkoutReq {
+                    name: "Leg Day".to_string(),
+                    ..default_workout_req()
+                },
+                vec![
+                    WorkoutExerciseReq {
+                        name: "Squat".to_string(),
+                        code: "A1".to_string(),
+                        ..default_exercise_req(0, WeightUnit::Kg, 60)
+                    },
+                    WorkoutExerciseReq {
+                        exercise_library_id: Some(l_sit_id),
+                        name: "L-Sit".to_string(),
+                        code: "B1".to_string(),
+                        ..default_exercise_req(0, WeightUnit::Kg, 60)
+                    },
+                ],
+            )
+            .await
+            .unwrap();
+        let workout_id: u32 = sqlx::query_scalar("SELECT id FROM workouts")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        let mut page = StartWorkoutPage::default(pool.clone());
+        page.load_workout(&ctx, workout_id);
+        settle(&mut page, &ctx).await;
+        let session = page.active_session.as_mut().unwrap();
+        for exercise in session.exercises.iter_mut() {
+            let set = &mut exercise.sets[0];
+            set.weight = 10.0;
+            set.reps_or_seconds = 30;
+            set.mark_completed(true);
+        }
+
+        // 10 kg for 30 reps counts, a 30 second hold with 10 kg does not
+        let volumes: Vec<f64> = session
+            .exercises
+            .iter()
+            .map(|e| e.summary(WeightUnit::Kg, None).volume)
+            .collect();
+        assert_eq!(volumes, vec![300.0, 0.0]);
+    }
+
+    // a page with "Leg Day" loaded and its first set checked off, not autosaved yet
+    async fn page_with_session(ctx: &egui::Context) -> (StartWorkoutPage, Pool<Sqlite>) {
+        let pool = init_db(IN_MEMORY_DB_URL).await.unwrap();
+        WorkoutService::new(pool.clone())
+            .create(
+                WorkoutReq {
+                    name: "Leg Day".to_string(),
+                    ..default_workout_req()
+                },
+                vec![WorkoutExerciseReq {
+                    name: "Squat".to_string(),
+                    code: "A1".to_string(),
+                    ..default_exercise_req(0, WeightUnit::Kg, 60)
+                }],
+            )
+            .await
+            .unwrap();
+        let workout_id: u32 = sqlx::query_scalar("SELECT id FROM workouts")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        let mut page = StartWorkoutPage::default(pool.clone());
+        page.load_workout(ctx, workout_id);
+        settle(&mut page, ctx).await;
+        let exercise_id = page.active_session.as_ref().unwrap().exercises[0].workout_exercise_id;
+        page.complete_set(exercise_id, 1);
+        assert!(page.draft_dirty);
+        (page, pool)
+    }
+
+    async fn settle(page: &mut StartWorkoutPage, ctx: &egui::Context) {
+        for _ in 0..20 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            page.handle_async_messages(ctx);
+        }
+    }
+
+    async fn drafts(pool: &Pool<Sqlite>) -> Vec<WorkoutSessionDraftRes> {
+        WorkoutLogService::new(pool.clone())
+            .get_unfinished_drafts()
+            .await
+            .unwrap()
+    }
+
+    fn completed_sets(draft: &WorkoutSessionDraftRes) -> usize {
+        let session: ActiveSession = serde_json::from_str(&draft.session).unwrap();
+        session.exercises[0]
+            .sets
+            .iter()
+            .filter(|s| s.completed)
+            .count()
+    }
+
+    #[tokio::test]
+    async fn test_close_saves_unsaved_changes() {
+        let ctx = egui::Context::default();
+        let (mut page, pool) = page_with_session(&ctx).await;
+
+        page.close_session(&ctx);
+        settle(&mut page, &ctx).await;
+
+        let drafts = drafts(&pool).await;
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(completed_sets(&drafts[0]), 1);
+        assert_eq!(page.unfinished_drafts.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_close_during_first_save_keeps_later_changes() {
+        let ctx = egui::Context::default();
+        let (mut page, pool) = page_with_session(&ctx).await;
+
+        page.autosave_draft(&ctx);
+        assert!(page.draft_saving);
+        let exercise_id = page.active_session.as_ref().unwrap().exercises[0].workout_exercise_id;
+        page.complete_set(exercise_id, 2);
+        page.close_session(&ctx);
+        settle(&mut page, &ctx).await;
+
+        let drafts = drafts(&pool).await;
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(completed_sets(&drafts[0]), 2);
+    }
+
+    // exiting blocks on saves run by the runtime's workers
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_exit_saves_changes_since_the_last_autosave() {
+        let ctx = egui::Context::default();
+        let (mut page, pool) = page_with_session(&ctx).await;
+
+        page.autosave_draft(&ctx);
+        assert!(page.draft_saving);
+        let exercise_id = page.active_session.as_ref().unwrap().exercises[0].workout_exercise_id;
+        page.complete_set(exercise_id, 2);
+        tokio::task::block_in_place(|| page.save_draft_on_exit());
+
+        let drafts = drafts(&pool).await;
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(completed_sets(&drafts[0]), 2);
+        assert!(!page.draft_dirty);
+    }
+
+    #[tokio::test]
+    async fn test_discard_during_first_save_leaves_no_draft() {
+        let ctx = egui::Context::default();
+        let (mut page, pool) = page_with_session(&ctx).await;
+
+        page.autosave_draft(&ctx);
+        assert!(page.draft_saving);
+        page.discard_session(&ctx);
+        settle(&mut page, &ctx).await;
+
+        assert!(drafts(&pool).await.is_empty());
+        assert!(page.closed_drafts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_save_log_stops_autosave_and_logs_once() {
+        let ctx = egui::Context::default();
+        let (mut page, pool) = page_with_session(&ctx).await;
+
+        page.autosave_draft(&ctx);
+        settle(&mut page, &ctx).await;
+        assert!(page.draft_id.is_some());
+
+        let exercise_id = page.active_session.as_ref().unwrap().exercises[0].workout_exercise_id;
+        page.complete_set(exercise_id, 2);
+        assert!(page.can_finish());
+        page.save_log(&ctx);
+        assert!(!page.can_finish());
+        page.autosave_draft(&ctx);
+        assert!(!page.draft_saving);
+        settle(&mut page, &ctx).await;
+
+        assert!(page.summary.is_some());
+        assert!(drafts(&pool).await.is_empty());
+        let log_groups: u32 = sqlx::query_scalar("SELECT COUNT(*) FROM workout_log_groups")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(log_groups, 1);
+    }
+}
@@ -1,14 +1,22 @@
-use crate::enums::{Band, Equipment};
+use crate::enums::{Band, Equipment, WeightUnit};
+use crate::error::{AppError, AppResult};
+use crate::load::Load;
+use crate::tempo::{Tempo, describe_tut};
 use crate::workout::workout_entity::{WorkoutEntity, WorkoutExerciseEntity};
+use crate::workout_log::workout_log_dto::ExerciseHistoryTarget;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct WorkoutExerciseReq {
     pub workout_id: u32,
+    pub exercise_library_id: Option<u32>,
     pub name: String,
     pub code: String,
     pub sets_target: u8,
     pub reps_or_seconds_target: u8,
-    pub working_weight: u16,
+    pub working_weight: f64,
+    pub weight_unit: WeightUnit,
+    pub assistance_band: Option<Band>,
+    pub bodyweight_fraction: f64,
     pub rest_period_seconds: u16,
     pub tempo: String,
     pub emom: bool,
@@ -17,21 +25,26 @@ pub struct WorkoutExerciseReq {
     pub description: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct WorkoutExerciseRes {
     pub id: u32,
     pub workout_id: u32,
+    pub exercise_library_id: Option<u32>,
     pub name: String,
     pub code: String,
     pub sets_target: u8,
     pub reps_or_seconds_target: u8,
-    pub working_weight: u16,
+    pub working_weight: f64,
+    pub weight_unit: WeightUnit,
+    pub assistance_band: Option<Band>,
+    pub bodyweight_fraction: f64,
     pub rest_period_seconds: u16,
     pub tempo: String,
     pub emom: bool,
     pub equipments: Vec<Equipment>,
     pub bands: Vec<Band>,
     pub description: Option<String>,
+    pub is_static: bool, // the linked library exercise is a hold
 }
 
 pub trait RestMinuteAndSeconds {
@@ -48,13 +61,81 @@ impl RestMinuteAndSeconds for WorkoutExerciseRes {
     }
 }
 
+impl WorkoutExerciseReq {
+    // converts the working weight so the form edits it in the display unit
+    pub fn set_weight_unit(&mut self, unit: WeightUnit) {
+        self.working_weight = self.weight_unit.convert(self.working_weight, unit);
+        self.weight_unit = unit;
+    }
+
+    pub fn load(&self) -> Load {
+        Load {
+            weight: self.working_weight,
+            weight_unit: self.weight_unit,
+            assistance_band: self.assistance_band,
+            bodyweight_fraction: self.bodyweight_fraction,
+        }
+    }
+
+    pub fn validate(&self) -> AppResult<()> {
+        self.tempo
+            .parse::<Tempo>()
+            .map(|_| ())
+            .map_err(AppError::Validation)
+    }
+}
+
 impl WorkoutExerciseRes {
-    pub fn target(&self) -> String {
+    pub fn working_weight_in(&self, unit: WeightUnit) -> f64 {
+        self.weight_unit.convert(self.working_weight, unit)
+    }
+
+    pub fn load(&self) -> Load {
+        Load {
+            weight: self.working_weight,
+            weight_unit: self.weight_unit,
+            assistance_band: self.assistance_band,
+            bodyweight_fraction: self.bodyweight_fraction,
+        }
+    }
+
+    // rows saved before tempo was validated may not parse
+    pub fn parsed_tempo(&self) -> Option<Tempo> {
+        self.tempo.parse().ok()
+    }
+
+    pub fn tut_per_rep(&self) -> Option<u32> {
+        self.parsed_tempo().map(|t| t.tut_per_rep())
+    }
+
+    pub fn tut_per_set(&self) -> Option<u32> {
+        self.parsed_tempo()
+            .map(|t| t.tut_per_set(self.reps_or_seconds_target))
+    }
+
+    // empty when the tempo is unset or invalid
+    pub fn tut_summary(&self) -> String {
+        match (self.tut_per_rep(), self.tut_per_set()) {
+            (Some(per_rep), Some(per_set)) if per_rep > 0 => describe_tut(per_rep, per_set),
+            _ => String::new(),
+        }
+    }
+
+    pub fn target(&self, unit: WeightUnit, bodyweight_kg: Option<f64>) -> String {
         format!(
             "{}x{} @ {}",
-            self.sets_target, self.reps_or_seconds_target, self.working_weight
+            self.sets_target,
+            self.reps_or_seconds_target,
+            self.load().describe(unit, bodyweight_kg)
         )
     }
+
+    pub fn history_target(&self) -> ExerciseHistoryTarget {
+        ExerciseHistoryTarget {
+            exercise_library_id: self.exercise_library_id,
+            exercise_name: self.name.clone(),
+        }
+    }
 }
 
 impl WorkoutExerciseRes {
@@ -62,17 +143,22 @@ impl WorkoutExerciseRes {
         Self {
             id: entity.id,
             workout_id: entity.workout_id,
+            exercise_library_id: entity.exercise_library_id,
             name: entity.name,
             code: entity.code,
             sets_target: entity.sets_target,
             reps_or_seconds_target: entity.reps_or_seconds_target,
             working_weight: entity.working_weight,
+            weight_unit: entity.weight_unit,
+            assistance_band: entity.assistance_band,
+            bodyweight_fraction: entity.bodyweight_fraction,
             rest_period_seconds: entity.rest_period_seconds,
             tempo: entity.tempo,
             emom: entity.emom,
             equipments: entity.equipments.0,
             bands: entity.bands.0,
             description: entity.description,
+            is_static: entity.is_static,
         }
     }
 }
@@ -82,6 +168,7 @@ pub struct WorkoutReq {
     pub name: String,
     pub description: Option<String>,
     pub active: bool,
+    pub auto_start_rest_timer: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -90,6 +177,7 @@ pub struct WorkoutRes {
     pub name: String,
     pub description: Option<String>,
     pub active: bool,
+    pub auto_start_rest_timer: bool,
 }
 
 impl WorkoutRes {
@@ -99,44 +187,44 @@ impl WorkoutRes {
             name: workout_entity.name,
             description: workout_entity.description,
             active: workout_entity.active,
+            auto_start_rest_timer: workout_entity.auto_start_rest_timer,
         }
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct WorkoutsFilterReq {
     pub name: Option<String>,
     pub description: Option<String>,
     pub active: Option<bool>,
 }
 
-impl Default for WorkoutsFilterReq {
-    fn default() -> Self {
-        Self {
-            name: None,
-            description: None,
-            active: None,
-        }
-    }
-}
-
 pub fn default_workout_req() -> WorkoutReq {
     WorkoutReq {
         name: String::new(),
         description: None,
         active: true,
+        auto_start_rest_timer: true,
     }
 }
 
-pub fn default_exercise_req(workout_id: u32) -> WorkoutExerciseReq {
+pub fn default_exercise_req(
+    workout_id: u32,
+    weight_unit: WeightUnit,
+    rest_period_seconds: u16,
+) -> WorkoutExerciseReq {
     WorkoutExerciseReq {
         workout_id,
+        exercise_library_id: None,
         name: String::new(),
         code: String::new(),
         sets_target: 3,
         reps_or_seconds_target: 10,
-        working_weight: 0,
-        rest_period_seconds: 60,
+        working_weight: 0.0,
+        weight_unit,
+        assistance_band: None,
+        bodyweight_fraction: 0.0,
+        rest_period_seconds,
         tempo: "0000".to_string(),
         emom: false,
         equipments: vec![],
@@ -150,20 +238,25 @@ pub fn workout_to_req(res: &WorkoutRes) -> WorkoutReq {
         name: res.name.clone(),
         description: res.description.clone(),
         active: res.active,
+        auto_start_rest_timer: res.auto_start_rest_timer,
     }
 }
 
 pub fn exercise_res_to_req(res: &WorkoutExerciseRes) -> WorkoutExerciseReq {
     WorkoutExerciseReq {
         workout_id: res.workout_id,
+        exercise_library_id: res.exercise_library_id,
         name: res.name.clone(),
         code: res.code.clone(),
         sets_target: res.sets_target,
         reps_or_seconds_target: res.reps_or_seconds_target,
         working_weight: res.working_weight,
+        weight_unit: res.weight_unit,
+        assistance_band: res.assistance_band,
+        bodyweight_fraction: res.bodyweight_fraction,
         rest_period_seconds: res.rest_period_seconds,
         tempo: res.tempo.clone(),
-        emom: false,
+        emom: res.emom,
         equipments: res.equipments.clone(),
         bands: res.bands.clone(),
         description: res.description.clone(),
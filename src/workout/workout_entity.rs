@@ -1,27 +1,33 @@
 use crate::db::pagination_support::HasId;
-use crate::enums::{Band, Equipment};
+use crate::enums::{Band, Equipment, WeightUnit};
 use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use sqlx::FromRow;
 use sqlx::types::Json;
 
 // mapped to a db row
-#[derive(Debug, Clone, PartialEq, Eq, Hash, FromRow, Deserialize)]
+#[derive(Debug, Clone, PartialEq, FromRow, Deserialize)]
 pub struct WorkoutExerciseEntity {
     pub id: u32,
     pub created_at: DateTime<Utc>, // should be some kinda DateTime
     pub workout_id: u32,           // fk to Workout
+    pub exercise_library_id: Option<u32>, // fk to ExerciseLibraryEntity, None for free text
     pub name: String,
     pub code: String, // A1, A2, B1, B2 ... input by user
     pub sets_target: u8,
     pub reps_or_seconds_target: u8,
-    pub working_weight: u16,
+    pub working_weight: f64,     // negative for assistance
+    pub weight_unit: WeightUnit, // unit working_weight was entered in
+    pub assistance_band: Option<Band>,
+    pub bodyweight_fraction: f64, // share of bodyweight moved, 0.0 for external load only
     pub rest_period_seconds: u16,
     pub tempo: String,
     pub emom: bool,
     pub equipments: Json<Vec<Equipment>>,
     pub bands: Json<Vec<Band>>,
     pub description: Option<String>,
+    #[serde(default)]
+    pub is_static: bool, // joined from the linked library exercise, holds log seconds
 }
 // WorkoutEntity -> WorkoutExerciseEntity, 1:many
 // ExerciseLibraryEntry -> WorkoutExerciseEntity, 1:many
@@ -34,6 +40,7 @@ pub struct WorkoutEntity {
     pub name: String,
     pub description: Option<String>,
     pub active: bool,
+    pub auto_start_rest_timer: bool, // start the rest timer when a set is checked
 }
 
 impl HasId for WorkoutEntity {
@@ -2,42 +2,52 @@ use crate::db::pagination_support::{
     PaginationParams, PaginationRes, get_cursors, keyset_paginate,
 };
 use crate::db::{SqliteExecutor, SqliteTx};
+use crate::error::{AppError, AppResult};
 use crate::workout::workout_dto::{
     WorkoutExerciseReq, WorkoutExerciseRes, WorkoutReq, WorkoutRes, WorkoutsFilterReq,
 };
 use crate::workout::workout_entity::{WorkoutEntity, WorkoutExerciseEntity};
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
 use sqlx::types::Json;
 use sqlx::{QueryBuilder, Sqlite};
 
+const WORKOUT_EXERCISE_SELECT: &str = r#"
+    SELECT we.*, COALESCE(el.dynamic_or_static = 'Static', 0) AS is_static
+    FROM workout_exercises we
+    LEFT JOIN exercise_library el ON we.exercise_library_id = el.id
+"#;
+
 #[derive(Copy, Clone)]
 pub struct WorkoutRepo {}
 
+impl Default for WorkoutRepo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl WorkoutRepo {
     pub fn new() -> Self {
         Self {}
     }
 
-    pub async fn create_workout(
-        &self,
-        tx: &mut SqliteTx<'_>,
-        req: WorkoutReq,
-    ) -> Result<u32, String> {
+    pub async fn create_workout(&self, tx: &mut SqliteTx<'_>, req: WorkoutReq) -> AppResult<u32> {
         let created_at = Utc::now();
 
         let result = sqlx::query(
             r#"
-        INSERT INTO workouts (created_at, name, description, active)
-        VALUES (?, ?, ?, ?)
+        INSERT INTO workouts (created_at, name, description, active, auto_start_rest_timer)
+        VALUES (?, ?, ?, ?, ?)
         "#,
         )
         .bind(created_at)
         .bind(&req.name)
         .bind(&req.description)
-        .bind(&req.active)
+        .bind(req.active)
+        .bind(req.auto_start_rest_timer)
         .execute(&mut **tx)
         .await
-        .map_err(|e| format!("Failed to create core: {}", e))?;
+        .map_err(AppError::db("Failed to create workout"))?;
 
         let id = result.last_insert_rowid() as u32;
         Ok(id)
@@ -48,64 +58,117 @@ impl WorkoutRepo {
         tx: &mut SqliteTx<'_>,
         id: u32,
         req: WorkoutReq,
-    ) -> Result<(), String> {
+    ) -> AppResult<()> {
         let result = sqlx::query(
             r#"
         UPDATE workouts
-        SET name = ?, description = ?, active = ?
+        SET name = ?, description = ?, active = ?, auto_start_rest_timer = ?
         WHERE id = ?
         "#,
         )
         .bind(&req.name)
         .bind(&req.description)
-        .bind(&req.active)
+        .bind(req.active)
+        .bind(req.auto_start_rest_timer)
         .bind(id)
         .execute(&mut **tx)
         .await
-        .map_err(|e| format!("Failed to update core: {}", e))?;
+        .map_err(AppError::db("Failed to update workout"))?;
 
         if result.rows_affected() == 0 {
-            return Err("Workout not found".to_string());
+            return Err(AppError::NotFound("Workout"));
         }
 
         Ok(())
     }
 
-    pub async fn delete_workout(&self, tx: &mut SqliteTx<'_>, id: u32) -> Result<(), String> {
+    pub async fn delete_workout(&self, tx: &mut SqliteTx<'_>, id: u32) -> AppResult<()> {
+        // workout_logs reference workouts with ON DELETE RESTRICT
+        let dependents = self
+            .find_logged_sessions(tx, "SELECT DISTINCT g.date FROM workout_logs l JOIN workout_log_groups g ON g.id = l.workout_log_group_id WHERE l.workout_id = ? ORDER BY g.date", id)
+            .await?;
+        if !dependents.is_empty() {
+            return Err(AppError::ForeignKeyRestricted {
+                what: "Workout",
+                dependents,
+            });
+        }
+
         let result = sqlx::query("DELETE FROM workouts WHERE id = ?")
             .bind(id)
             .execute(&mut **tx)
             .await
-            .map_err(|e| format!("Failed to delete core (may be in use): {}", e))?;
+            .map_err(AppError::db_delete("Workout"))?;
 
         if result.rows_affected() == 0 {
-            return Err("Workout not found".to_string());
+            return Err(AppError::NotFound("Workout"));
         }
 
         Ok(())
     }
 
+    // dates of the logged sessions that still point at a row
+    async fn find_logged_sessions(
+        &self,
+        tx: &mut SqliteTx<'_>,
+        query: &'static str,
+        id: u32,
+    ) -> AppResult<Vec<String>> {
+        let dates: Vec<NaiveDate> = sqlx::query_scalar(query)
+            .bind(id)
+            .fetch_all(&mut **tx)
+            .await
+            .map_err(AppError::db("Failed to look up logged sessions"))?;
+        Ok(dates.iter().map(|date| date.to_string()).collect())
+    }
+
     pub async fn get_one_workout<'e>(
         &self,
         executor: impl SqliteExecutor<'e>,
         id: u32,
-    ) -> Result<WorkoutRes, String> {
+    ) -> AppResult<WorkoutRes> {
         let row: WorkoutEntity = sqlx::query_as("SELECT * FROM workouts WHERE id = ?")
             .bind(id)
             .fetch_optional(executor)
             .await
-            .map_err(|e| format!("Database error: {}", e))?
-            .ok_or_else(|| "Workout not found".to_string())?;
+            .map_err(AppError::db("Database error"))?
+            .ok_or(AppError::NotFound("Workout"))?;
 
         Ok(WorkoutRes::from_entity(row))
     }
 
+    // active workouts in id order, the one after the most recently logged comes next
+    pub async fn get_next_workout<'e>(
+        &self,
+        executor: impl SqliteExecutor<'e>,
+    ) -> AppResult<Option<WorkoutRes>> {
+        let row: Option<WorkoutEntity> = sqlx::query_as(
+            r#"
+                SELECT * FROM workouts
+                WHERE active = 1
+                ORDER BY id <= COALESCE((
+                    SELECT l.workout_id
+                    FROM workout_logs l
+                    JOIN workout_log_groups g ON l.workout_log_group_id = g.id
+                    ORDER BY g.date DESC, g.id DESC, l.id DESC
+                    LIMIT 1
+                ), 0), id
+                LIMIT 1
+                "#,
+        )
+        .fetch_optional(executor)
+        .await
+        .map_err(AppError::db("Database error"))?;
+
+        Ok(row.map(WorkoutRes::from_entity))
+    }
+
     pub async fn paginate_workouts<'e>(
         &self,
         executor: impl SqliteExecutor<'e>,
         pagination_filters: Option<WorkoutsFilterReq>,
         pagination_params: PaginationParams,
-    ) -> Result<PaginationRes<WorkoutRes>, String> {
+    ) -> AppResult<PaginationRes<WorkoutRes>> {
         let mut qb = QueryBuilder::new("SELECT * FROM workouts WHERE 1=1");
         self.pagination_filters(pagination_filters, &mut qb);
         keyset_paginate(&pagination_params, None, &mut qb);
@@ -114,14 +177,14 @@ impl WorkoutRepo {
             .build_query_as()
             .fetch_all(executor)
             .await
-            .map_err(|e| format!("Failed to paginate workouts: {}", e))?;
+            .map_err(AppError::db("Failed to paginate workouts"))?;
 
         let cursors = get_cursors(&pagination_params, &mut rows);
 
         let items = rows
             .iter()
             .map(|row| Ok(WorkoutRes::from_entity(row.clone())))
-            .collect::<Result<Vec<WorkoutRes>, String>>()?;
+            .collect::<Result<Vec<WorkoutRes>, AppError>>()?;
 
         Ok(PaginationRes::new(items, cursors))
     }
@@ -155,33 +218,39 @@ impl WorkoutRepo {
         &self,
         tx: &mut SqliteTx<'_>,
         req: WorkoutExerciseReq,
-    ) -> Result<u32, String> {
+    ) -> AppResult<u32> {
+        req.validate()?;
         let created_at = Utc::now();
 
         let result = sqlx::query(
             r#"
         INSERT INTO workout_exercises (
-            created_at, workout_id, name, code, sets_target, reps_or_seconds_target, working_weight,
-            rest_period_seconds, tempo, emom, equipments, bands, description
-        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            created_at, workout_id, exercise_library_id, name, code, sets_target,
+            reps_or_seconds_target, working_weight, weight_unit, assistance_band,
+            bodyweight_fraction, rest_period_seconds, tempo, emom, equipments, bands, description
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
         )
         .bind(created_at)
         .bind(req.workout_id)
+        .bind(req.exercise_library_id)
         .bind(&req.name)
         .bind(&req.code)
         .bind(req.sets_target)
         .bind(req.reps_or_seconds_target)
         .bind(req.working_weight)
+        .bind(req.weight_unit)
+        .bind(req.assistance_band)
+        .bind(req.bodyweight_fraction)
         .bind(req.rest_period_seconds)
         .bind(&req.tempo)
-        .bind(&req.emom)
+        .bind(req.emom)
         .bind(Json(req.equipments))
         .bind(Json(req.bands))
         .bind(&req.description)
         .execute(&mut **tx)
         .await
-        .map_err(|e| format!("Failed to create core exercise: {}", e))?;
+        .map_err(AppError::db("Failed to create workout exercise"))?;
 
         let id = result.last_insert_rowid() as u32;
         Ok(id)
@@ -192,23 +261,28 @@ impl WorkoutRepo {
         tx: &mut SqliteTx<'_>,
         id: u32,
         req: WorkoutExerciseReq,
-    ) -> Result<(), String> {
+    ) -> AppResult<()> {
+        req.validate()?;
         let result = sqlx::query(
             r#"
         UPDATE workout_exercises
-        SET workout_id = ?, code = ?, name = ?,
-            sets_target = ?, reps_or_seconds_target = ?, working_weight = ?,
-            rest_period_seconds = ?, tempo = ?, emom = ?, equipments = ?,
+        SET workout_id = ?, exercise_library_id = ?, code = ?, name = ?,
+            sets_target = ?, reps_or_seconds_target = ?, working_weight = ?, weight_unit = ?,
+            assistance_band = ?, bodyweight_fraction = ?, rest_period_seconds = ?, tempo = ?, emom = ?, equipments = ?,
             bands = ?, description = ?
         WHERE id = ?
         "#,
         )
         .bind(req.workout_id)
+        .bind(req.exercise_library_id)
         .bind(&req.code)
         .bind(&req.name)
         .bind(req.sets_target)
         .bind(req.reps_or_seconds_target)
         .bind(req.working_weight)
+        .bind(req.weight_unit)
+        .bind(req.assistance_band)
+        .bind(req.bodyweight_fraction)
         .bind(req.rest_period_seconds)
         .bind(&req.tempo)
         .bind(req.emom)
@@ -218,28 +292,34 @@ impl WorkoutRepo {
         .bind(id)
         .execute(&mut **tx)
         .await
-        .map_err(|e| format!("Failed to update core exercise: {}", e))?;
+        .map_err(AppError::db("Failed to update workout exercise"))?;
 
         if result.rows_affected() == 0 {
-            return Err("Workout exercise not found".to_string());
+            return Err(AppError::NotFound("Workout exercise"));
         }
 
         Ok(())
     }
 
-    pub async fn delete_workout_exercise(
-        &self,
-        tx: &mut SqliteTx<'_>,
-        id: u32,
-    ) -> Result<(), String> {
+    pub async fn delete_workout_exercise(&self, tx: &mut SqliteTx<'_>, id: u32) -> AppResult<()> {
+        let dependents = self
+            .find_logged_sessions(tx, "SELECT DISTINCT g.date FROM workout_logs l JOIN workout_log_groups g ON g.id = l.workout_log_group_id WHERE l.workout_exercise_id = ? ORDER BY g.date", id)
+            .await?;
+        if !dependents.is_empty() {
+            return Err(AppError::ForeignKeyRestricted {
+                what: "Workout exercise",
+                dependents,
+            });
+        }
+
         let result = sqlx::query("DELETE FROM workout_exercises WHERE id = ?")
             .bind(id)
             .execute(&mut **tx)
             .await
-            .map_err(|e| format!("Failed to delete core exercise (may be in use): {}", e))?;
+            .map_err(AppError::db_delete("Workout exercise"))?;
 
         if result.rows_affected() == 0 {
-            return Err("Workout exercise not found".to_string());
+            return Err(AppError::NotFound("Workout exercise"));
         }
 
         Ok(())
@@ -249,18 +329,20 @@ impl WorkoutRepo {
         &self,
         executor: impl SqliteExecutor<'e>,
         workout_id: u32,
-    ) -> Result<Vec<WorkoutExerciseRes>, String> {
-        let rows: Vec<WorkoutExerciseEntity> =
-            sqlx::query_as("SELECT * FROM workout_exercises WHERE workout_id = ? ORDER BY code")
-                .bind(workout_id)
-                .fetch_all(executor)
-                .await
-                .map_err(|e| format!("Database error: {}", e))?;
+    ) -> AppResult<Vec<WorkoutExerciseRes>> {
+        let rows: Vec<WorkoutExerciseEntity> = sqlx::query_as(&format!(
+            "{} WHERE we.workout_id = ? ORDER BY we.code",
+            WORKOUT_EXERCISE_SELECT
+        ))
+        .bind(workout_id)
+        .fetch_all(executor)
+        .await
+        .map_err(AppError::db("Database error"))?;
 
         let res = rows
             .iter()
             .map(|row| Ok(WorkoutExerciseRes::from_entity(row.clone())))
-            .collect::<Result<Vec<WorkoutExerciseRes>, String>>()?;
+            .collect::<Result<Vec<WorkoutExerciseRes>, AppError>>()?;
 
         Ok(res)
     }
@@ -269,31 +351,84 @@ impl WorkoutRepo {
         &self,
         executor: impl SqliteExecutor<'e>,
         id: u32,
-    ) -> Result<WorkoutExerciseRes, String> {
+    ) -> AppResult<WorkoutExerciseRes> {
         let entity: WorkoutExerciseEntity =
-            sqlx::query_as("SELECT * FROM workout_exercises WHERE id = ?")
+            sqlx::query_as(&format!("{} WHERE we.id = ?", WORKOUT_EXERCISE_SELECT))
                 .bind(id)
                 .fetch_optional(executor)
                 .await
-                .map_err(|e| format!("Database error: {}", e))?
-                .ok_or_else(|| "Workout exercise not found".to_string())?;
+                .map_err(AppError::db("Database error"))?
+                .ok_or(AppError::NotFound("Workout exercise"))?;
 
         Ok(WorkoutExerciseRes::from_entity(entity))
     }
+
+    // links free-text workout exercises that match a library exercise's full name
+    // already linked rows are left alone
+    pub async fn link_workout_exercises_by_name(
+        &self,
+        tx: &mut SqliteTx<'_>,
+        full_name: &str,
+        exercise_library_id: u32,
+    ) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+        UPDATE workout_exercises
+        SET exercise_library_id = ?
+        WHERE exercise_library_id IS NULL AND LOWER(TRIM(name)) = LOWER(TRIM(?))
+        "#,
+        )
+        .bind(exercise_library_id)
+        .bind(full_name)
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::db("Failed to link workout exercises"))?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn has_backfill_run<'e>(
+        &self,
+        executor: impl SqliteExecutor<'e>,
+        name: &str,
+    ) -> AppResult<bool> {
+        let ran: Option<String> =
+            sqlx::query_scalar("SELECT name FROM data_backfills WHERE name = ?")
+                .bind(name)
+                .fetch_optional(executor)
+                .await
+                .map_err(AppError::db("Database error"))?;
+
+        Ok(ran.is_some())
+    }
+
+    pub async fn mark_backfill_run(&self, tx: &mut SqliteTx<'_>, name: &str) -> AppResult<()> {
+        sqlx::query("INSERT OR IGNORE INTO data_backfills (name, ran_at) VALUES (?, ?)")
+            .bind(name)
+            .bind(Utc::now())
+            .execute(&mut **tx)
+            .await
+            .map_err(AppError::db("Failed to record backfill"))?;
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::db::pagination_support::{PaginationDirection, PaginationParams};
     use crate::db::{IN_MEMORY_DB_URL, init_db};
-    use crate::enums::{Band, Equipment};
+    use crate::enums::{
+        Band, CompoundOrIsolation, DynamicOrStatic, Equipment, UpperOrLower, WeightUnit,
+    };
+    use crate::error::AppError;
     use crate::workout::workout_dto::{WorkoutExerciseReq, WorkoutReq, WorkoutsFilterReq};
     use crate::workout::workout_repo::WorkoutRepo;
     use chrono::Utc;
     use sqlx::SqlitePool;
 
     async fn setup_db() -> SqlitePool {
-        init_db(IN_MEMORY_DB_URL).await
+        init_db(IN_MEMORY_DB_URL).await.unwrap()
     }
 
     fn mock_workout_req(name: &str) -> WorkoutReq {
@@ -301,17 +436,22 @@ mod tests {
             name: name.to_string(),
             description: Some(format!("{} notes", name)),
             active: true,
+            auto_start_rest_timer: true,
         }
     }
 
     fn mock_workout_exercise_req(workout_id: u32, code: &str, name: &str) -> WorkoutExerciseReq {
         WorkoutExerciseReq {
             workout_id,
+            exercise_library_id: None,
             code: code.to_string(),
             name: name.to_string(),
             sets_target: 4,
             reps_or_seconds_target: 8,
-            working_weight: 100,
+            working_weight: 100.0,
+            weight_unit: WeightUnit::Kg,
+            assistance_band: None,
+            bodyweight_fraction: 0.0,
             rest_period_seconds: 90,
             tempo: "2010".to_string(),
             emom: false,
@@ -333,7 +473,7 @@ mod tests {
         let workout_id = repository
             .create_workout(&mut tx, mock_workout_req("Upper Body A"))
             .await
-            .expect("Failed to create core");
+            .expect("Failed to create workout");
 
         // Get
         let workout = repository
@@ -341,14 +481,16 @@ mod tests {
             .await
             .expect("Failed to get core");
         assert_eq!(workout.name, "Upper Body A");
+        assert!(workout.auto_start_rest_timer);
 
         // Update
         let mut updated_req = mock_workout_req("Lower Body A");
         updated_req.description = Some("Leg day!".to_string());
+        updated_req.auto_start_rest_timer = false;
         repository
             .update_workout(&mut tx, workout_id, updated_req)
             .await
-            .expect("Failed to update core");
+            .expect("Failed to update workout");
 
         let updated_workout = repository
             .get_one_workout(&mut *tx, workout_id)
@@ -356,12 +498,13 @@ mod tests {
             .expect("Failed to get updated core");
         assert_eq!(updated_workout.name, "Lower Body A");
         assert_eq!(updated_workout.description, Some("Leg day!".to_string()));
+        assert!(!updated_workout.auto_start_rest_timer);
 
         // Delete
         repository
             .delete_workout(&mut tx, workout_id)
             .await
-            .expect("Failed to delete core");
+            .expect("Failed to delete workout");
 
         assert!(
             repository
@@ -484,13 +627,13 @@ mod tests {
                 mock_workout_exercise_req(workout_id, "A1", "Pushups"),
             )
             .await
-            .expect("Failed to create core exercise");
+            .expect("Failed to create workout exercise");
 
         // Get
         let ex = repository
             .get_one_workout_exercise(&mut *tx, ex_id)
             .await
-            .expect("Failed to get core exercise");
+            .expect("Failed to get workout exercise");
         assert_eq!(ex.code, "A1");
         assert_eq!(ex.workout_id, workout_id);
         assert_eq!(ex.equipments, vec![Equipment::Barbell]);
@@ -500,7 +643,10 @@ mod tests {
         // Update
         let mut updated_req = mock_workout_exercise_req(workout_id, "A2", "Pullups");
         updated_req.sets_target = 5;
-        updated_req.working_weight = 120;
+        updated_req.working_weight = 121.25;
+        updated_req.weight_unit = WeightUnit::Lb;
+        updated_req.assistance_band = Some(Band::Red);
+        updated_req.bodyweight_fraction = 1.0;
         updated_req.equipments = vec![Equipment::Dumbbells];
         updated_req.bands = vec![];
         updated_req.description = None;
@@ -508,7 +654,7 @@ mod tests {
         repository
             .update_workout_exercise(&mut tx, ex_id, updated_req)
             .await
-            .expect("Failed to update core exercise");
+            .expect("Failed to update workout exercise");
 
         let updated_ex = repository
             .get_one_workout_exercise(&mut *tx, ex_id)
@@ -517,7 +663,10 @@ mod tests {
         assert_eq!(updated_ex.code, "A2");
         assert_eq!(updated_ex.name, "Pullups");
         assert_eq!(updated_ex.sets_target, 5);
-        assert_eq!(updated_ex.working_weight, 120);
+        assert_eq!(updated_ex.working_weight, 121.25);
+        assert_eq!(updated_ex.weight_unit, WeightUnit::Lb);
+        assert_eq!(updated_ex.assistance_band, Some(Band::Red));
+        assert_eq!(updated_ex.bodyweight_fraction, 1.0);
         assert_eq!(updated_ex.equipments, vec![Equipment::Dumbbells]);
         assert!(updated_ex.bands.is_empty());
         assert_eq!(updated_ex.description, None);
@@ -528,7 +677,7 @@ mod tests {
                 mock_workout_exercise_req(workout_id, "B1", "Triceps extension"),
             )
             .await
-            .expect("Failed to create core exercise");
+            .expect("Failed to create workout exercise");
 
         let found = repository
             .get_workout_exercises_by_workout_id(&mut *tx, workout_id)
@@ -540,7 +689,7 @@ mod tests {
         repository
             .delete_workout_exercise(&mut tx, ex_id)
             .await
-            .expect("Failed to delete core exercise");
+            .expect("Failed to delete workout exercise");
 
         assert!(
             repository
@@ -552,6 +701,86 @@ mod tests {
         tx.commit().await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_link_workout_exercises_by_name() {
+        let pool = setup_db().await;
+        let mut tx = pool.begin().await.unwrap();
+        let repository = WorkoutRepo::new();
+
+        let library_id = sqlx::query(
+            r#"INSERT INTO exercise_library (
+                name, dynamic_or_static, upper_or_lower, compound_or_isolation
+            ) VALUES (?, ?, ?, ?)"#,
+        )
+        .bind("Squat")
+        .bind(DynamicOrStatic::Dynamic)
+        .bind(UpperOrLower::Lower)
+        .bind(CompoundOrIsolation::Compound)
+        .execute(&mut *tx)
+        .await
+        .unwrap()
+        .last_insert_rowid() as u32;
+
+        let workout_id = repository
+            .create_workout(&mut tx, mock_workout_req("Leg Day"))
+            .await
+            .unwrap();
+
+        let free_text_id = repository
+            .create_workout_exercise(
+                &mut tx,
+                mock_workout_exercise_req(workout_id, "A1", " squat "),
+            )
+            .await
+            .unwrap();
+        let other_id = repository
+            .create_workout_exercise(
+                &mut tx,
+                mock_workout_exercise_req(workout_id, "B1", "Lunges"),
+            )
+            .await
+            .unwrap();
+
+        let linked = repository
+            .link_workout_exercises_by_name(&mut tx, "Squat", library_id)
+            .await
+            .expect("Failed to link workout exercises");
+        assert_eq!(linked, 1);
+
+        let ex = repository
+            .get_one_workout_exercise(&mut *tx, free_text_id)
+            .await
+            .unwrap();
+        assert_eq!(ex.exercise_library_id, Some(library_id));
+
+        let other = repository
+            .get_one_workout_exercise(&mut *tx, other_id)
+            .await
+            .unwrap();
+        assert_eq!(other.exercise_library_id, None);
+
+        // already linked rows are not touched again
+        let linked_again = repository
+            .link_workout_exercises_by_name(&mut tx, "Squat", library_id)
+            .await
+            .unwrap();
+        assert_eq!(linked_again, 0);
+
+        // deleting the library exercise keeps the workout exercise, just unlinked
+        sqlx::query("DELETE FROM exercise_library WHERE id = ?")
+            .bind(library_id)
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+        let unlinked = repository
+            .get_one_workout_exercise(&mut *tx, free_text_id)
+            .await
+            .unwrap();
+        assert_eq!(unlinked.exercise_library_id, None);
+
+        tx.commit().await.unwrap();
+    }
+
     // --- INTEGRITY TESTS ---
 
     #[tokio::test]
@@ -565,7 +794,7 @@ mod tests {
             .await
             .unwrap();
 
-        // Create a core exercise → now core is referenced
+        // Create a workout exercise → now core is referenced
         repository
             .create_workout_exercise(&mut tx, mock_workout_exercise_req(1, "A1", "Pushups"))
             .await
@@ -607,7 +836,7 @@ mod tests {
         .unwrap()
         .last_insert_rowid();
 
-        // Create a log entry referencing the core exercise
+        // Create a log entry referencing the workout exercise
         sqlx::query(
             r#"INSERT INTO workout_logs (
                 workout_id, workout_exercise_id, workout_log_group_id,
@@ -626,10 +855,116 @@ mod tests {
         .await
         .unwrap();
 
-        // Now try to delete the core exercise → should fail
+        // Now try to delete the workout exercise → should fail
         let result = repository.delete_workout_exercise(&mut tx, ex_id).await;
+        match result {
+            Err(AppError::ForeignKeyRestricted { what, dependents }) => {
+                assert_eq!(what, "Workout exercise");
+                assert_eq!(dependents.len(), 1);
+            }
+            other => panic!("expected a restricted delete, got {:?}", other),
+        }
+
+        tx.commit().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_next_workout_rotates_active_workouts() {
+        let pool = setup_db().await;
+        let mut tx = pool.begin().await.unwrap();
+        let repository = WorkoutRepo::new();
+
+        assert!(
+            repository
+                .get_next_workout(&mut *tx)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let mut ids = vec![];
+        for (name, active) in [("A", true), ("Retired", false), ("B", true)] {
+            let mut req = mock_workout_req(name);
+            req.active = active;
+            ids.push(repository.create_workout(&mut tx, req).await.unwrap());
+        }
+
+        // nothing logged yet, start from the first
+        let next = repository.get_next_workout(&mut *tx).await.unwrap();
+        assert_eq!(next.unwrap().id, ids[0]);
+
+        let log = |workout_id: u32, date: &'static str| {
+            sqlx::query(
+                r#"INSERT INTO workout_logs (
+                    workout_id, workout_exercise_id, workout_log_group_id,
+                    exercise_name, set_number, rep_number_or_seconds, weight
+                ) VALUES (?, 1, (
+                    SELECT id FROM workout_log_groups WHERE date = ?
+                ), 'Pushups', 1, 8, 0)"#,
+            )
+            .bind(workout_id)
+            .bind(date)
+        };
+        repository
+            .create_workout_exercise(&mut tx, mock_workout_exercise_req(ids[0], "A1", "Pushups"))
+            .await
+            .unwrap();
+        for date in ["2024-01-01", "2024-01-03"] {
+            sqlx::query("INSERT INTO workout_log_groups (created_at, date) VALUES (?, ?)")
+                .bind(Utc::now())
+                .bind(date)
+                .execute(&mut *tx)
+                .await
+                .unwrap();
+        }
+
+        // after A comes B, skipping the inactive one
+        log(ids[0], "2024-01-01").execute(&mut *tx).await.unwrap();
+        let next = repository.get_next_workout(&mut *tx).await.unwrap();
+        assert_eq!(next.unwrap().id, ids[2]);
+
+        // after the last one it wraps around
+        log(ids[2], "2024-01-03").execute(&mut *tx).await.unwrap();
+        let next = repository.get_next_workout(&mut *tx).await.unwrap();
+        assert_eq!(next.unwrap().id, ids[0]);
+
+        tx.commit().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rejects_invalid_tempo() {
+        let pool = setup_db().await;
+        let mut tx = pool.begin().await.unwrap();
+        let repository = WorkoutRepo::new();
+
+        let workout_id = repository
+            .create_workout(&mut tx, mock_workout_req("Tempo Day"))
+            .await
+            .unwrap();
+
+        let mut req = mock_workout_exercise_req(workout_id, "SQ", "Squat");
+        req.tempo = "31".to_string();
+        let result = repository.create_workout_exercise(&mut tx, req).await;
+        assert!(matches!(result, Err(AppError::Validation(ref e)) if e.contains("Invalid tempo")));
+
+        let mut req = mock_workout_exercise_req(workout_id, "SQ", "Squat");
+        req.tempo = "31x0".to_string();
+        let ex_id = repository
+            .create_workout_exercise(&mut tx, req.clone())
+            .await
+            .unwrap();
+
+        req.tempo = "3a10".to_string();
+        let result = repository
+            .update_workout_exercise(&mut tx, ex_id, req)
+            .await;
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("may be in use"));
+
+        let ex = repository
+            .get_one_workout_exercise(&mut *tx, ex_id)
+            .await
+            .unwrap();
+        assert_eq!(ex.tempo, "31x0");
 
         tx.commit().await.unwrap();
     }
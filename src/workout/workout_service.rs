@@ -1,21 +1,30 @@
 use crate::db::pagination_support::{PaginationParams, PaginationRes};
+use crate::error::AppResult;
+use crate::exercise::exercise_dto::ExerciseName;
+use crate::exercise::exercise_repo::ExerciseRepo;
 use crate::workout::workout_dto::{
     WorkoutExerciseReq, WorkoutExerciseRes, WorkoutReq, WorkoutRes, WorkoutsFilterReq,
 };
 use crate::workout::workout_repo::WorkoutRepo;
 use sqlx::{Pool, Sqlite};
+use std::collections::HashMap;
 
 #[derive(Clone)]
 pub struct WorkoutService {
     pool: Pool<Sqlite>,
     repo: WorkoutRepo,
+    exercise_repo: ExerciseRepo,
 }
 
+// recorded once the backfill has run, so exercises unlinked on purpose stay unlinked
+const LIBRARY_BACKFILL: &str = "exercise_library_ids";
+
 impl WorkoutService {
     pub fn new(pool: Pool<Sqlite>) -> Self {
         Self {
             pool,
             repo: WorkoutRepo::new(),
+            exercise_repo: ExerciseRepo::new(),
         }
     }
 
@@ -23,11 +32,8 @@ impl WorkoutService {
         &self,
         workout_req: WorkoutReq,
         exercises_req: Vec<WorkoutExerciseReq>,
-    ) -> Result<(), String> {
-        let mut conn = match self.pool.begin().await {
-            Ok(conn) => conn,
-            Err(e) => return Err(e.to_string()),
-        };
+    ) -> AppResult<()> {
+        let mut conn = self.pool.begin().await?;
 
         let workout_id = self.repo.create_workout(&mut conn, workout_req).await?;
         for mut exercise_req in exercises_req {
@@ -37,7 +43,7 @@ impl WorkoutService {
                 .create_workout_exercise(&mut conn, exercise_req)
                 .await?;
         }
-        conn.commit().await.unwrap();
+        conn.commit().await?;
         Ok(())
     }
 
@@ -46,11 +52,8 @@ impl WorkoutService {
         id: u32,
         workout_req: WorkoutReq,
         exercises_req: Vec<(u32, WorkoutExerciseReq)>,
-    ) -> Result<(), String> {
-        let mut conn = match self.pool.begin().await {
-            Ok(conn) => conn,
-            Err(e) => return Err(e.to_string()),
-        };
+    ) -> AppResult<()> {
+        let mut conn = self.pool.begin().await?;
         self.repo.update_workout(&mut conn, id, workout_req).await?;
         for (exercise_id, mut exercise_req) in exercises_req {
             exercise_req.workout_id = id;
@@ -59,78 +62,166 @@ impl WorkoutService {
                 .await?;
         }
 
-        conn.commit().await.unwrap();
+        conn.commit().await?;
         Ok(())
     }
 
-    pub async fn delete(&self, id: u32) -> Result<(), String> {
-        let mut conn = match self.pool.begin().await {
-            Ok(conn) => conn,
-            Err(e) => return Err(e.to_string()),
-        };
+    pub async fn delete(&self, id: u32) -> AppResult<()> {
+        let mut conn = self.pool.begin().await?;
         self.repo.delete_workout(&mut conn, id).await?;
-        conn.commit().await.unwrap();
+        conn.commit().await?;
         Ok(())
     }
 
-    pub async fn create_exercise(&self, req: WorkoutExerciseReq) -> Result<(), String> {
-        let mut conn = match self.pool.begin().await {
-            Ok(conn) => conn,
-            Err(e) => return Err(e.to_string()),
-        };
+    pub async fn create_exercise(&self, req: WorkoutExerciseReq) -> AppResult<()> {
+        let mut conn = self.pool.begin().await?;
         self.repo.create_workout_exercise(&mut conn, req).await?;
-        conn.commit().await.unwrap();
+        conn.commit().await?;
         Ok(())
     }
 
-    pub async fn update_exercise(&self, id: u32, req: WorkoutExerciseReq) -> Result<(), String> {
-        let mut conn = match self.pool.begin().await {
-            Ok(conn) => conn,
-            Err(e) => return Err(e.to_string()),
-        };
+    pub async fn update_exercise(&self, id: u32, req: WorkoutExerciseReq) -> AppResult<()> {
+        let mut conn = self.pool.begin().await?;
         self.repo
             .update_workout_exercise(&mut conn, id, req)
             .await?;
-        conn.commit().await.unwrap();
+        conn.commit().await?;
         Ok(())
     }
 
-    pub async fn delete_exercise(&self, id: u32) -> Result<(), String> {
-        let mut conn = match self.pool.begin().await {
-            Ok(conn) => conn,
-            Err(e) => return Err(e.to_string()),
-        };
+    pub async fn delete_exercise(&self, id: u32) -> AppResult<()> {
+        let mut conn = self.pool.begin().await?;
         self.repo.delete_workout_exercise(&mut conn, id).await?;
-        conn.commit().await.unwrap();
+        conn.commit().await?;
         Ok(())
     }
 
-    pub async fn get_one(&self, id: u32) -> Result<WorkoutRes, String> {
-        Ok(self.repo.get_one_workout(&self.pool, id).await?)
+    pub async fn get_one(&self, id: u32) -> AppResult<WorkoutRes> {
+        self.repo.get_one_workout(&self.pool, id).await
     }
 
-    pub async fn get_one_exercise(&self, id: u32) -> Result<WorkoutExerciseRes, String> {
-        Ok(self.repo.get_one_workout_exercise(&self.pool, id).await?)
+    pub async fn get_one_exercise(&self, id: u32) -> AppResult<WorkoutExerciseRes> {
+        self.repo.get_one_workout_exercise(&self.pool, id).await
     }
 
     pub async fn get_all_exercises_by_workout_id(
         &self,
         workout_id: u32,
-    ) -> Result<Vec<WorkoutExerciseRes>, String> {
-        Ok(self
-            .repo
+    ) -> AppResult<Vec<WorkoutExerciseRes>> {
+        self.repo
             .get_workout_exercises_by_workout_id(&self.pool, workout_id)
-            .await?)
+            .await
+    }
+
+    pub async fn get_next_workout(&self) -> AppResult<Option<WorkoutRes>> {
+        self.repo.get_next_workout(&self.pool).await
     }
 
     pub async fn paginate(
         &self,
         pagination_filters: Option<WorkoutsFilterReq>,
         pagination_params: PaginationParams,
-    ) -> Result<PaginationRes<WorkoutRes>, String> {
-        Ok(self
-            .repo
+    ) -> AppResult<PaginationRes<WorkoutRes>> {
+        self.repo
             .paginate_workouts(&self.pool, pagination_filters, pagination_params)
-            .await?)
+            .await
+    }
+
+    // workout exercises created before the library link existed only carry a free-text name.
+    // link them to the library exercise whose full name matches, skipping ambiguous names.
+    // runs once per database, None when it already has
+    pub async fn backfill_exercise_library_ids(&self) -> AppResult<Option<u64>> {
+        let mut conn = self.pool.begin().await?;
+        if self
+            .repo
+            .has_backfill_run(&mut *conn, LIBRARY_BACKFILL)
+            .await?
+        {
+            return Ok(None);
+        }
+
+        let library = self.exercise_repo.get_all_exercises(&mut *conn).await?;
+        let mut by_name: HashMap<String, Option<u32>> = HashMap::new();
+        for exercise in &library {
+            by_name
+                .entry(exercise.full_name().trim().to_lowercase())
+                .and_modify(|id| *id = None)
+                .or_insert(Some(exercise.id));
+        }
+
+        let mut linked = 0;
+        for (full_name, id) in by_name {
+            if let Some(id) = id {
+                linked += self
+                    .repo
+                    .link_workout_exercises_by_name(&mut conn, &full_name, id)
+                    .await?;
+            }
+        }
+
+        self.repo
+            .mark_backfill_run(&mut conn, LIBRARY_BACKFILL)
+            .await?;
+
+        conn.commit().await?;
+        Ok(Some(linked))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::{IN_MEMORY_DB_URL, init_db};
+    use crate::enums::{CompoundOrIsolation, DynamicOrStatic, UpperOrLower, WeightUnit};
+    use crate::workout::workout_dto::{
+        WorkoutExerciseReq, WorkoutReq, default_exercise_req, default_workout_req,
+    };
+    use crate::workout::workout_service::WorkoutService;
+
+    #[tokio::test]
+    async fn test_backfill_runs_once() {
+        let pool = init_db(IN_MEMORY_DB_URL).await.unwrap();
+        let service = WorkoutService::new(pool.clone());
+
+        sqlx::query(
+            r#"INSERT INTO exercise_library (
+                name, dynamic_or_static, upper_or_lower, compound_or_isolation
+            ) VALUES (?, ?, ?, ?)"#,
+        )
+        .bind("Squat")
+        .bind(DynamicOrStatic::Dynamic)
+        .bind(UpperOrLower::Lower)
+        .bind(CompoundOrIsolation::Compound)
+        .execute(&pool)
+        .await
+        .unwrap();
+        service
+            .create(
+                WorkoutReq {
+                    name: "Leg Day".to_string(),
+                    ..default_workout_req()
+                },
+                vec![WorkoutExerciseReq {
+                    name: "Squat".to_string(),
+                    code: "A1".to_string(),
+                    ..default_exercise_req(0, WeightUnit::Kg, 60)
+                }],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(service.backfill_exercise_library_ids().await, Ok(Some(1)));
+
+        // the user unlinks it on purpose, the next start leaves it alone
+        sqlx::query("UPDATE workout_exercises SET exercise_library_id = NULL")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(service.backfill_exercise_library_ids().await, Ok(None));
+        let linked: Option<u32> =
+            sqlx::query_scalar("SELECT exercise_library_id FROM workout_exercises")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(linked, None);
     }
 }
@@ -1,6 +1,14 @@
 use crate::client::app::PageAction;
 use crate::client::app_utils::{CommonUiState, render_pagination};
-use crate::db::pagination_support::{PaginationRes, PaginationState};
+use crate::db::pagination_support::{
+    PaginationDirection, PaginationParams, PaginationRes, PaginationState,
+};
+use crate::enums::{Band, WeightUnit};
+use crate::error::AppError;
+use crate::exercise::exercise_dto::{ExerciseLibraryFilterReq, ExerciseLibraryRes, ExerciseName};
+use crate::exercise::exercise_service::ExerciseService;
+use crate::settings::settings_dto::SettingsRes;
+use crate::tempo::Tempo;
 use crate::workout::workout_dto::{
     RestMinuteAndSeconds, WorkoutExerciseReq, WorkoutExerciseRes, WorkoutReq, WorkoutRes,
     WorkoutsFilterReq, default_exercise_req, default_workout_req, exercise_res_to_req,
@@ -14,6 +22,7 @@ use std::sync::mpsc::{Receiver, Sender, channel};
 
 pub struct WorkoutsPage {
     service: WorkoutService,
+    exercise_service: ExerciseService,
     state: WorkoutsPageState,
     // Data
     list_items: Vec<WorkoutRes>,
@@ -25,6 +34,14 @@ pub struct WorkoutsPage {
     show_exercise_form: bool,
     form_exercise: WorkoutExerciseReq,
     editing_exercise_id: Option<u32>,
+    // Exercise Library Picker
+    library_search: String,
+    library_items: Vec<ExerciseLibraryRes>,
+    library_needs_fetch: bool,
+    // Display unit for weights, forms edit in this unit
+    weight_unit: WeightUnit,
+    bodyweight_kg: Option<f64>,
+    default_rest_seconds: u16, // for new exercises
     // For Create New Workout mode:
     new_workout_exercises: Vec<WorkoutExerciseReq>,
     // Search/Filter State
@@ -41,24 +58,32 @@ pub struct WorkoutsPage {
 pub enum WorkoutsPageMsg {
     ListLoaded(PaginationRes<WorkoutRes>),
     DetailLoaded(WorkoutRes, Vec<WorkoutExerciseRes>),
+    LibraryLoaded(Vec<ExerciseLibraryRes>),
     Saved,
     Deleted,
-    Error(String),
+    Error(AppError),
 }
 
 impl WorkoutsPage {
     pub fn default(pool: Pool<Sqlite>) -> Self {
         let (sender, receiver) = channel();
         Self {
-            service: WorkoutService::new(pool),
+            service: WorkoutService::new(pool.clone()),
+            exercise_service: ExerciseService::new(pool),
             state: WorkoutsPageState::DetailsClosed,
             list_items: Vec::new(),
             current_workout: None,
             current_exercises: Vec::new(),
             form_workout: default_workout_req(),
             show_exercise_form: false,
-            form_exercise: default_exercise_req(0),
+            form_exercise: default_exercise_req(0, WeightUnit::default(), 60),
             editing_exercise_id: None,
+            library_search: String::new(),
+            library_items: Vec::new(),
+            library_needs_fetch: true,
+            weight_unit: WeightUnit::default(),
+            bodyweight_kg: None,
+            default_rest_seconds: 60,
             new_workout_exercises: Vec::new(),
             pagination_filters: WorkoutsFilterReq::default(),
             pagination_state: PaginationState::default(),
@@ -69,6 +94,20 @@ impl WorkoutsPage {
     }
 }
 
+impl WorkoutsPage {
+    pub fn apply_settings(&mut self, settings: &SettingsRes) {
+        self.weight_unit = settings.weight_unit;
+        self.bodyweight_kg = settings.bodyweight_kg;
+        self.default_rest_seconds = settings.default_rest_seconds;
+        if self
+            .pagination_state
+            .apply_default_limit(settings.pagination_limit)
+        {
+            self.trigger_list_refresh();
+        }
+    }
+}
+
 pub enum WorkoutsPageState {
     DetailsClosed,
     DetailsOpenView,
@@ -92,6 +131,9 @@ impl WorkoutsPage {
                     self.form_workout = workout_to_req(&workout);
                     self.new_workout_exercises.clear();
                 }
+                WorkoutsPageMsg::LibraryLoaded(items) => {
+                    self.library_items = items;
+                }
                 WorkoutsPageMsg::Saved => {
                     self.common_ui_state.show_success("Saved successfully");
                     if matches!(self.state, WorkoutsPageState::CreateNew) {
@@ -110,7 +152,7 @@ impl WorkoutsPage {
                     self.trigger_list_refresh();
                 }
                 WorkoutsPageMsg::Error(e) => {
-                    self.common_ui_state.show_error(&e);
+                    self.common_ui_state.show_app_error(&e);
                 }
             }
         }
@@ -165,6 +207,37 @@ impl WorkoutsPage {
         });
     }
 
+    fn fetch_library(&mut self, ctx: &egui::Context) {
+        self.library_needs_fetch = false;
+        let sender = self.sender.clone();
+        let ctx = ctx.clone();
+        let service = self.exercise_service.clone();
+        let filter = ExerciseLibraryFilterReq {
+            name: if self.library_search.is_empty() {
+                None
+            } else {
+                Some(self.library_search.clone())
+            },
+            ..ExerciseLibraryFilterReq::default()
+        };
+        let params = PaginationParams {
+            limit: 50,
+            cursor: None,
+            direction: PaginationDirection::Forward,
+        };
+        tokio::spawn(async move {
+            match service.paginate(Some(filter), params).await {
+                Ok(res) => {
+                    let _ = sender.send(WorkoutsPageMsg::LibraryLoaded(res.items));
+                }
+                Err(e) => {
+                    let _ = sender.send(WorkoutsPageMsg::Error(e));
+                }
+            }
+            ctx.request_repaint();
+        });
+    }
+
     fn save_workout(&mut self, ctx: &egui::Context) {
         self.common_ui_state.set_as_loading();
         let sender = self.sender.clone();
@@ -205,6 +278,10 @@ impl WorkoutsPage {
     }
 
     fn save_exercise(&mut self, ctx: &egui::Context) {
+        // store the canonical form, e.g. "31x0" -> "31X0"
+        if let Ok(tempo) = self.form_exercise.tempo.parse::<Tempo>() {
+            self.form_exercise.tempo = tempo.to_string();
+        }
         // Logic depends on whether we are in CreateNew or EditView
         if matches!(self.state, WorkoutsPageState::CreateNew) {
             // Memory only
@@ -326,6 +403,54 @@ impl WorkoutsPage {
         }
     }
 
+    fn render_library_picker(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        if self.library_needs_fetch {
+            self.fetch_library(ctx);
+        }
+
+        ui.label("Library Exercise");
+        ui.horizontal(|ui| {
+            if ui
+                .add(egui::TextEdit::singleline(&mut self.library_search).hint_text("Search..."))
+                .changed()
+            {
+                self.library_needs_fetch = true;
+            }
+
+            let req = &mut self.form_exercise;
+            let selected_text = match req.exercise_library_id {
+                Some(id) => self
+                    .library_items
+                    .iter()
+                    .find(|item| item.id == id)
+                    .map(|item| item.full_name())
+                    .unwrap_or_else(|| req.name.clone()),
+                None => "None (free text)".to_string(),
+            };
+
+            egui::ComboBox::from_id_salt("exercise_library_picker")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    if ui
+                        .selectable_label(req.exercise_library_id.is_none(), "None (free text)")
+                        .clicked()
+                    {
+                        req.exercise_library_id = None;
+                    }
+                    for item in &self.library_items {
+                        let full_name = item.full_name();
+                        if ui
+                            .selectable_label(req.exercise_library_id == Some(item.id), &full_name)
+                            .clicked()
+                        {
+                            req.exercise_library_id = Some(item.id);
+                            req.name = full_name;
+                        }
+                    }
+                });
+        });
+    }
+
     fn render_exercise_form(&mut self, ui: &mut egui::Ui) {
         let req = &mut self.form_exercise;
         ui.label("Code (e.g. A1)");
@@ -343,8 +468,31 @@ impl WorkoutsPage {
                 ui.add(egui::DragValue::new(&mut req.reps_or_seconds_target));
                 ui.end_row();
 
-                ui.label("Weight");
-                ui.add(egui::DragValue::new(&mut req.working_weight));
+                ui.label("Added Weight");
+                ui.add(
+                    egui::DragValue::new(&mut req.working_weight)
+                        .speed(0.25)
+                        .range(-500.0..=1000.0)
+                        .max_decimals(2)
+                        .suffix(format!(" {}", req.weight_unit)),
+                )
+                .on_hover_text("Negative for assistance, e.g. an assisted pull-up machine");
+                ui.end_row();
+
+                ui.label("Bodyweight Fraction");
+                ui.add(
+                    egui::DragValue::new(&mut req.bodyweight_fraction)
+                        .speed(0.05)
+                        .range(0.0..=1.0)
+                        .max_decimals(2),
+                )
+                .on_hover_text(
+                    "Share of bodyweight moved: 1.0 for a pull-up, 0 for a barbell lift",
+                );
+                ui.end_row();
+
+                ui.label("Assistance Band");
+                render_band_picker(ui, "exercise_form_band", &mut req.assistance_band);
                 ui.end_row();
 
                 ui.label("Rest (s)");
@@ -352,8 +500,16 @@ impl WorkoutsPage {
                 ui.end_row();
             });
 
-        ui.label("Tempo");
+        ui.label("Tempo (e.g. 3010, X for explosive)");
         ui.text_edit_singleline(&mut req.tempo);
+        match req.tempo.parse::<Tempo>() {
+            Ok(tempo) => {
+                ui.label(tempo.describe_tut(req.reps_or_seconds_target));
+            }
+            Err(e) => {
+                ui.colored_label(egui::Color32::RED, e);
+            }
+        }
         ui.checkbox(&mut req.emom, "EMOM");
         ui.label("Description");
         let mut desc = req.description.clone().unwrap_or_default();
@@ -371,6 +527,10 @@ impl WorkoutsPage {
         self.form_workout.description = if desc.is_empty() { None } else { Some(desc) };
 
         ui.checkbox(&mut self.form_workout.active, "Active");
+        ui.checkbox(
+            &mut self.form_workout.auto_start_rest_timer,
+            "Auto-start rest timer when a set is done",
+        );
     }
 
     fn render_details_open_view(&mut self, ui: &mut egui::Ui) -> PageAction {
@@ -395,8 +555,12 @@ impl WorkoutsPage {
                 for ex in &self.current_exercises {
                     ui.label(egui::RichText::new(&ex.code).strong());
                     ui.label(&ex.name);
-                    ui.label(ex.target());
+                    ui.label(ex.target(self.weight_unit, self.bodyweight_kg));
                     ui.label(ex.rest_minutes_and_seconds());
+                    ui.label(ex.tut_summary());
+                    if ui.button("History").clicked() {
+                        page_action = PageAction::GoToExerciseHistory(ex.history_target());
+                    }
                     ui.end_row();
                 }
             });
@@ -438,8 +602,11 @@ impl WorkoutsPage {
         ui.horizontal(|ui| {
             ui.heading("Exercises");
             if ui.button("+ Add Exercise").clicked() {
-                self.form_exercise =
-                    default_exercise_req(self.current_workout.as_ref().map(|w| w.id).unwrap_or(0));
+                self.form_exercise = default_exercise_req(
+                    self.current_workout.as_ref().map(|w| w.id).unwrap_or(0),
+                    self.weight_unit,
+                    self.default_rest_seconds,
+                );
                 self.editing_exercise_id = None;
                 self.show_exercise_form = true;
             }
@@ -453,6 +620,7 @@ impl WorkoutsPage {
                     ui.label("Name");
                     ui.label("Target");
                     ui.label("Rest");
+                    ui.label("TUT");
                     ui.label("Actions");
                     ui.end_row();
 
@@ -462,8 +630,9 @@ impl WorkoutsPage {
                     for ex in &self.current_exercises {
                         ui.label(&ex.code);
                         ui.label(&ex.name);
-                        ui.label(ex.target());
+                        ui.label(ex.target(self.weight_unit, self.bodyweight_kg));
                         ui.label(ex.rest_minutes_and_seconds());
+                        ui.label(ex.tut_summary());
 
                         ui.horizontal(|ui| {
                             if ui.button("Edit").clicked() {
@@ -480,6 +649,7 @@ impl WorkoutsPage {
                         // Find the exercise and load form
                         if let Some(ex) = self.current_exercises.iter().find(|e| e.id == id) {
                             self.form_exercise = exercise_res_to_req(ex);
+                            self.form_exercise.set_weight_unit(self.weight_unit);
                             self.editing_exercise_id = Some(id);
                             self.show_exercise_form = true;
                         }
@@ -520,7 +690,8 @@ impl WorkoutsPage {
         ui.horizontal(|ui| {
             ui.heading("Exercises");
             if ui.button("+ Add Exercise").clicked() {
-                self.form_exercise = default_exercise_req(0);
+                self.form_exercise =
+                    default_exercise_req(0, self.weight_unit, self.default_rest_seconds);
                 self.editing_exercise_id = None;
                 self.show_exercise_form = true;
             }
@@ -544,7 +715,9 @@ impl WorkoutsPage {
                         ui.label(&ex.name);
                         ui.label(format!(
                             "{}x{} @ {}",
-                            ex.sets_target, ex.reps_or_seconds_target, ex.working_weight
+                            ex.sets_target,
+                            ex.reps_or_seconds_target,
+                            ex.load().describe(self.weight_unit, self.bodyweight_kg)
                         ));
 
                         ui.horizontal(|ui| {
@@ -560,6 +733,7 @@ impl WorkoutsPage {
 
                     if let Some(idx) = edit_idx {
                         self.form_exercise = self.new_workout_exercises[idx].clone();
+                        self.form_exercise.set_weight_unit(self.weight_unit);
                         self.editing_exercise_id = Some(idx as u32);
                         self.show_exercise_form = true;
                     }
@@ -593,15 +767,24 @@ impl WorkoutsPage {
             } else {
                 "Add Exercise (New)"
             });
+            self.render_library_picker(ctx, ui);
             self.render_exercise_form(ui);
             ui.horizontal(|ui| {
                 if ui.button("Cancel").clicked() {
                     self.show_exercise_form = false;
                 }
-                if ui.button("Save Exercise").clicked() {
+                let tempo_valid = self.form_exercise.validate().is_ok();
+                if ui
+                    .add_enabled(tempo_valid, egui::Button::new("Save Exercise"))
+                    .clicked()
+                {
                     self.save_exercise(ctx);
                 }
             });
+            if !self.show_exercise_form {
+                // library may have changed by the next time the form is opened
+                self.library_needs_fetch = true;
+            }
             ui.separator();
             return Some(PageAction::None);
         }
@@ -770,3 +953,20 @@ impl WorkoutsPage {
         }
     }
 }
+
+pub fn render_band_picker(ui: &mut Ui, id_salt: &str, band: &mut Option<Band>) {
+    egui::ComboBox::from_id_salt(id_salt)
+        .selected_text(band.map(|b| b.to_string()).unwrap_or("None".to_string()))
+        .show_ui(ui, |ui| {
+            ui.selectable_value(band, None, "None");
+            for option in [
+                Band::Yellow,
+                Band::Red,
+                Band::Black,
+                Band::Purple,
+                Band::Green,
+            ] {
+                ui.selectable_value(band, Some(option), option.to_string());
+            }
+        });
+}
@@ -0,0 +1,279 @@
+use crate::client::app::PageAction;
+use crate::client::app_utils::{CommonUiState, render_pagination};
+use crate::db::pagination_support::{PaginationRes, PaginationState};
+use crate::enums::WeightUnit;
+use crate::error::AppError;
+use crate::settings::settings_dto::SettingsRes;
+use crate::workout_log::workout_log_dto::{
+    ExerciseHistoryTarget, WorkoutLogDetailRes, WorkoutLogFilterReq,
+};
+use crate::workout_log::workout_log_service::WorkoutLogService;
+use eframe::egui;
+use sqlx::{Pool, Sqlite};
+use std::sync::mpsc::{Receiver, Sender, channel};
+
+// every set logged for one exercise, across all workouts and sessions
+pub struct ExerciseHistoryPage {
+    service: WorkoutLogService,
+    target: Option<ExerciseHistoryTarget>,
+    // Data
+    list_items: Vec<WorkoutLogDetailRes>,
+    // Display unit for weights
+    weight_unit: WeightUnit,
+    bodyweight_kg: Option<f64>,
+    // Async Communication
+    receiver: Receiver<ExerciseHistoryPageMsg>,
+    sender: Sender<ExerciseHistoryPageMsg>,
+    // Search/Filter State, the exercise itself comes from the target
+    pagination_filters: WorkoutLogFilterReq,
+    // Filter UI state (strings for text input)
+    filter_date_gte_str: String,
+    filter_date_lte_str: String,
+    filter_workout_name_str: String,
+    // Pagination State
+    pagination_state: PaginationState,
+    // UI Status
+    common_ui_state: CommonUiState,
+}
+
+pub enum ExerciseHistoryPageMsg {
+    ListLoaded(PaginationRes<WorkoutLogDetailRes>),
+    Error(AppError),
+}
+
+impl ExerciseHistoryPage {
+    pub fn default(pool: Pool<Sqlite>) -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            service: WorkoutLogService::new(pool),
+            target: None,
+            list_items: Vec::new(),
+            weight_unit: WeightUnit::default(),
+            bodyweight_kg: None,
+            receiver,
+            sender,
+            pagination_filters: WorkoutLogFilterReq::default(),
+            filter_date_gte_str: String::new(),
+            filter_date_lte_str: String::new(),
+            filter_workout_name_str: String::new(),
+            pagination_state: PaginationState::default(),
+            common_ui_state: CommonUiState::default(),
+        }
+    }
+}
+
+impl ExerciseHistoryPage {
+    pub fn apply_settings(&mut self, settings: &SettingsRes) {
+        self.weight_unit = settings.weight_unit;
+        self.bodyweight_kg = settings.bodyweight_kg;
+        if self
+            .pagination_state
+            .apply_default_limit(settings.pagination_limit)
+        {
+            self.trigger_list_refresh();
+        }
+    }
+
+    pub fn open(&mut self, target: ExerciseHistoryTarget) {
+        self.pagination_filters = target.to_filter();
+        self.filter_date_gte_str.clear();
+        self.filter_date_lte_str.clear();
+        self.filter_workout_name_str.clear();
+        self.pagination_state.reset_pagination();
+        self.list_items.clear();
+        self.target = Some(target);
+        self.trigger_list_refresh();
+    }
+}
+
+impl ExerciseHistoryPage {
+    fn handle_async_messages(&mut self) {
+        while let Ok(msg) = self.receiver.try_recv() {
+            self.common_ui_state.set_as_not_loading();
+            match msg {
+                ExerciseHistoryPageMsg::ListLoaded(res) => {
+                    self.list_items = res.items;
+                    self.pagination_state.next_cursor = res.next_cursor;
+                    self.pagination_state.prev_cursor = res.prev_cursor;
+                }
+                ExerciseHistoryPageMsg::Error(err) => {
+                    self.common_ui_state.show_app_error(&err);
+                }
+            }
+        }
+    }
+
+    fn trigger_list_refresh(&mut self) {
+        self.common_ui_state.not_initialized();
+    }
+
+    fn fetch_list(&mut self, ctx: &egui::Context) {
+        if self.target.is_none() || self.common_ui_state.initialized || self.common_ui_state.loading
+        {
+            return;
+        }
+        self.common_ui_state.set_as_loading();
+        self.common_ui_state.initialize();
+
+        let service = self.service.clone();
+        let sender = self.sender.clone();
+        let filters = Some(self.pagination_filters.clone());
+        let params = self.pagination_state.to_pagination_params();
+        let ctx = ctx.clone();
+
+        tokio::spawn(async move {
+            match service.paginate_logs(filters, params).await {
+                Ok(res) => {
+                    let _ = sender.send(ExerciseHistoryPageMsg::ListLoaded(res));
+                }
+                Err(e) => {
+                    let _ = sender.send(ExerciseHistoryPageMsg::Error(e));
+                }
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    fn render_filters(&mut self, ui: &mut egui::Ui) {
+        let mut filters_changed = false;
+
+        ui.horizontal(|ui| {
+            ui.label("Date From:");
+            if ui
+                .text_edit_singleline(&mut self.filter_date_gte_str)
+                .changed()
+            {
+                self.pagination_filters.workout_date_gte =
+                    chrono::NaiveDate::parse_from_str(&self.filter_date_gte_str, "%Y-%m-%d").ok();
+                filters_changed = true;
+            }
+
+            ui.label("Date To:");
+            if ui
+                .text_edit_singleline(&mut self.filter_date_lte_str)
+                .changed()
+            {
+                self.pagination_filters.workout_date_lte =
+                    chrono::NaiveDate::parse_from_str(&self.filter_date_lte_str, "%Y-%m-%d").ok();
+                filters_changed = true;
+            }
+
+            ui.label("Workout:");
+            if ui
+                .text_edit_singleline(&mut self.filter_workout_name_str)
+                .changed()
+            {
+                self.pagination_filters.workout_name = if self.filter_workout_name_str.is_empty() {
+                    None
+                } else {
+                    Some(self.filter_workout_name_str.clone())
+                };
+                filters_changed = true;
+            }
+
+            if ui.button("Clear Filters").clicked()
+                && let Some(target) = &self.target
+            {
+                self.filter_date_gte_str.clear();
+                self.filter_date_lte_str.clear();
+                self.filter_workout_name_str.clear();
+                self.pagination_filters = target.to_filter();
+                filters_changed = true;
+            }
+        });
+
+        if filters_changed {
+            self.pagination_state.reset_pagination();
+            self.trigger_list_refresh();
+        }
+    }
+
+    // sets arrive ordered by session date then session, so each session's sets are next to each other
+    fn render_sessions(&self, ui: &mut egui::Ui) {
+        let mut sessions: Vec<(u32, Vec<&WorkoutLogDetailRes>)> = Vec::new();
+        for log in &self.list_items {
+            match sessions.last_mut() {
+                Some((group_id, logs)) if *group_id == log.workout_log_group_id => logs.push(log),
+                _ => sessions.push((log.workout_log_group_id, vec![log])),
+            }
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (group_id, logs) in sessions {
+                let first = logs[0];
+                egui::CollapsingHeader::new(format!(
+                    "{} - {}",
+                    first.workout_date, first.workout_name
+                ))
+                .id_salt(("exercise_history_session", group_id))
+                .default_open(true)
+                .show(ui, |ui| {
+                    egui::Grid::new(("exercise_history_grid", group_id))
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label("Set");
+                            ui.label("Reps/Secs");
+                            ui.label("Load");
+                            ui.label("RPE");
+                            ui.label("RIR");
+                            ui.label("Description");
+                            ui.end_row();
+
+                            for log in logs {
+                                ui.label(format!("{}", log.set_number));
+                                ui.label(format!("{}", log.rep_number_or_seconds));
+                                ui.label(log.load().describe(self.weight_unit, self.bodyweight_kg));
+                                ui.label(log.rpe.map(|v| v.to_string()).unwrap_or("-".into()));
+                                ui.label(log.rir.map(|v| v.to_string()).unwrap_or("-".into()));
+                                ui.label(log.description.as_deref().unwrap_or("-"));
+                                ui.end_row();
+                            }
+                        });
+                });
+            }
+        });
+    }
+
+    pub fn render_page(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) -> PageAction {
+        self.handle_async_messages();
+        self.fetch_list(ctx);
+
+        let mut page_action = PageAction::None;
+
+        ui.with_layout(egui::Layout::bottom_up(egui::Align::Min), |ui| {
+            if render_pagination(ui, &mut self.pagination_state) {
+                self.trigger_list_refresh();
+            }
+
+            ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("← Back").clicked() {
+                        page_action = PageAction::CloseExerciseHistory;
+                    }
+                    ui.heading(match &self.target {
+                        Some(target) => format!("Exercise History - {}", target.exercise_name),
+                        None => "Exercise History".to_string(),
+                    });
+                });
+                self.render_filters(ui);
+                ui.separator();
+
+                if self.common_ui_state.loading {
+                    ui.spinner();
+                    return;
+                }
+
+                self.common_ui_state.show_toasts(ui);
+
+                if self.list_items.is_empty() {
+                    ui.label("No sets logged for this exercise yet.");
+                } else {
+                    self.render_sessions(ui);
+                }
+                ui.separator();
+            });
+        });
+
+        page_action
+    }
+}
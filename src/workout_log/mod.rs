@@ -1,3 +1,5 @@
+pub mod exercise_history_page;
+pub mod session_timing;
 pub mod workout_log_dto;
 pub mod workout_log_entity;
 mod workout_log_repo;
@@ -0,0 +1,120 @@
+use crate::enums::WeightUnit;
+use crate::workout_log::workout_log_dto::WorkoutLogDetailRes;
+use chrono::TimeDelta;
+use std::collections::HashMap;
+
+// the rest after each set is the time until the next set of the session was checked off,
+// whichever exercise it was, so supersets rest between their exercises. keyed by workout log id
+pub fn actual_rests(logs: &[WorkoutLogDetailRes]) -> HashMap<u32, TimeDelta> {
+    let mut timed: Vec<&WorkoutLogDetailRes> = logs
+        .iter()
+        .filter(|log| log.completed_at.is_some())
+        .collect();
+    timed.sort_by_key(|log| log.completed_at);
+
+    timed
+        .windows(2)
+        .filter_map(|pair| Some((pair[0].id, pair[1].completed_at? - pair[0].completed_at?)))
+        .collect()
+}
+
+// load x reps moved per minute of the session, in the given unit. holds add no volume
+pub fn session_density(
+    logs: &[WorkoutLogDetailRes],
+    duration: TimeDelta,
+    unit: WeightUnit,
+    bodyweight_kg: Option<f64>,
+) -> Option<f64> {
+    let minutes = duration.num_seconds() as f64 / 60.0;
+    if minutes <= 0.0 {
+        return None;
+    }
+    let volume: f64 = logs
+        .iter()
+        .map(|log| {
+            log.load().volume_in(
+                unit,
+                bodyweight_kg,
+                log.rep_number_or_seconds,
+                log.is_static,
+            )
+        })
+        .sum();
+    Some(volume / minutes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, NaiveDate, Utc};
+
+    fn log(
+        id: u32,
+        completed_at: Option<DateTime<Utc>>,
+        reps: u8,
+        weight: f64,
+    ) -> WorkoutLogDetailRes {
+        WorkoutLogDetailRes {
+            id,
+            workout_log_group_id: 1,
+            workout_date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            workout_id: 1,
+            workout_name: "Test Workout".to_string(),
+            workout_exercise_id: 1,
+            workout_exercise_name: "Squat".to_string(),
+            exercise_library_id: None,
+            set_number: id as u8,
+            rep_number_or_seconds: reps,
+            weight,
+            weight_unit: WeightUnit::Kg,
+            assistance_band: None,
+            bodyweight_fraction: 0.0,
+            rpe: None,
+            rir: None,
+            description: None,
+            completed_at,
+            rest_period_seconds: 120,
+            is_static: false,
+        }
+    }
+
+    #[test]
+    fn test_actual_rests() {
+        let start = Utc::now();
+        let at = |seconds| Some(start + TimeDelta::seconds(seconds));
+        // checked off out of id order, and one set logged without a time
+        let logs = [
+            log(1, at(0), 5, 100.0),
+            log(2, at(300), 5, 100.0),
+            log(3, at(130), 5, 100.0),
+            log(4, None, 5, 100.0),
+        ];
+        let rests = actual_rests(&logs);
+        assert_eq!(rests.len(), 2);
+        assert_eq!(rests[&1], TimeDelta::seconds(130));
+        assert_eq!(rests[&3], TimeDelta::seconds(170));
+        assert!(!rests.contains_key(&2));
+    }
+
+    #[test]
+    fn test_session_density() {
+        let logs = [log(1, None, 5, 100.0), log(2, None, 10, 50.0)];
+        let density = session_density(&logs, TimeDelta::minutes(10), WeightUnit::Kg, None);
+        assert_eq!(density, Some(100.0));
+        assert_eq!(
+            session_density(&logs, TimeDelta::zero(), WeightUnit::Kg, None),
+            None
+        );
+    }
+
+    #[test]
+    fn test_session_density_leaves_out_holds() {
+        let hold = WorkoutLogDetailRes {
+            is_static: true,
+            ..log(2, None, 30, 10.0)
+        };
+        let logs = [log(1, None, 5, 100.0), hold];
+        let density = session_density(&logs, TimeDelta::minutes(10), WeightUnit::Kg, None);
+        assert_eq!(density, Some(50.0));
+    }
+}
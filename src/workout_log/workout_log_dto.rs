@@ -1,12 +1,17 @@
 use crate::db::pagination_support::HasId;
+use crate::enums::{Band, WeightUnit};
+use crate::error::{AppError, AppResult};
+use crate::load::Load;
 use crate::workout_log::workout_log_entity::WorkoutLogGroupEntity;
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
 use sqlx::FromRow;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct WorkoutLogGroupReq {
     pub date: NaiveDate,
     pub notes: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
 }
 
 impl WorkoutLogGroupReq {
@@ -14,6 +19,8 @@ impl WorkoutLogGroupReq {
         Self {
             date: chrono::Local::now().date_naive(),
             notes,
+            started_at: None,
+            finished_at: None,
         }
     }
 }
@@ -22,6 +29,8 @@ pub struct WorkoutLogGroupRes {
     pub id: u32,
     pub date: NaiveDate,
     pub notes: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
 }
 
 impl WorkoutLogGroupRes {
@@ -30,8 +39,15 @@ impl WorkoutLogGroupRes {
             id: entity.id,
             date: entity.date,
             notes: entity.notes,
+            started_at: entity.started_at,
+            finished_at: entity.finished_at,
         }
     }
+
+    // None for sessions logged before they were timed
+    pub fn duration(&self) -> Option<TimeDelta> {
+        Some(self.finished_at? - self.started_at?)
+    }
 }
 
 #[derive(FromRow)]
@@ -47,7 +63,17 @@ impl HasId for WorkoutLogGroupPageRes {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+// a logged session with the workouts its sets came from
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct RecentSessionRes {
+    pub id: u32,
+    pub date: NaiveDate,
+    pub notes: Option<String>,
+    pub workout_names: Option<String>, // comma separated, None for a session without sets
+    pub set_count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct WorkoutLogReq {
     pub workout_id: u32,
     pub workout_exercise_id: u32,
@@ -55,11 +81,17 @@ pub struct WorkoutLogReq {
     pub exercise_name: String,
     pub set_number: u8,
     pub rep_number_or_seconds: u8,
-    pub weight: u16,
+    pub weight: f64,
+    pub weight_unit: WeightUnit,
+    pub assistance_band: Option<Band>,
+    pub bodyweight_fraction: f64,
+    pub rpe: Option<f64>,
+    pub rir: Option<f64>,
     pub description: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct WorkoutLogRes {
     pub id: u32,
     pub workout_id: u32,
@@ -67,35 +99,123 @@ pub struct WorkoutLogRes {
     pub workout_log_group_id: u32,
     pub set_number: u8,
     pub rep_number_or_seconds: u8,
-    pub weight: u16,
+    pub weight: f64,
+    pub weight_unit: WeightUnit,
+    pub assistance_band: Option<Band>,
+    pub bodyweight_fraction: f64,
+    pub rpe: Option<f64>,
+    pub rir: Option<f64>,
+    pub description: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+// valid effort values: 1 to 10 in half steps
+pub fn is_valid_effort(value: f64) -> bool {
+    (1.0..=10.0).contains(&value) && (value * 2.0).fract() == 0.0
+}
+
+fn validate_effort(rpe: Option<f64>, rir: Option<f64>) -> AppResult<()> {
+    if let Some(rpe) = rpe
+        && !is_valid_effort(rpe)
+    {
+        return Err(AppError::Validation(format!(
+            "RPE must be 1-10 in half steps, got {}",
+            rpe
+        )));
+    }
+    if let Some(rir) = rir
+        && !is_valid_effort(rir)
+    {
+        return Err(AppError::Validation(format!(
+            "RIR must be 1-10 in half steps, got {}",
+            rir
+        )));
+    }
+    Ok(())
+}
+
+impl WorkoutLogReq {
+    pub fn validate(&self) -> AppResult<()> {
+        validate_effort(self.rpe, self.rir)
+    }
+}
+
+// the parts of a logged set that can be corrected, the exercise and unit stay as logged
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkoutLogUpdateReq {
+    pub set_number: u8,
+    pub rep_number_or_seconds: u8,
+    pub weight: f64,
+    pub assistance_band: Option<Band>,
+    pub rpe: Option<f64>,
+    pub rir: Option<f64>,
     pub description: Option<String>,
 }
 
+impl WorkoutLogUpdateReq {
+    pub fn validate(&self) -> AppResult<()> {
+        validate_effort(self.rpe, self.rir)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WorkoutLogGroupUpdateReq {
+    pub date: NaiveDate,
+    pub notes: Option<String>,
+}
+
+// every correction to a saved session, applied together
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WorkoutLogGroupEditReq {
+    pub updated_logs: Vec<(u32, WorkoutLogUpdateReq)>,
+    pub new_logs: Vec<WorkoutLogReq>,
+    pub deleted_log_ids: Vec<u32>,
+}
+
+// the exercise is matched by its library entry or by the exact name it was logged under,
+// when both are set a set matching either one is included
+#[derive(Clone, Default)]
 pub struct WorkoutLogFilterReq {
     pub workout_date_gte: Option<NaiveDate>,
     pub workout_date_lte: Option<NaiveDate>,
     pub workout_name: Option<String>,
     pub workout_exercise_name: Option<String>,
+    pub exercise_library_id: Option<u32>,
 }
 
-#[derive(Clone)]
-pub struct WorkoutLogGroupFilterReq {
-    pub workout_date_gte: Option<NaiveDate>,
-    pub workout_date_lte: Option<NaiveDate>,
-    pub notes: Option<String>,
+// the exercise an exercise history view is opened for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExerciseHistoryTarget {
+    pub exercise_library_id: Option<u32>,
+    pub exercise_name: String,
 }
 
-impl Default for WorkoutLogGroupFilterReq {
-    fn default() -> Self {
-        Self {
-            workout_date_gte: None,
-            workout_date_lte: None,
-            notes: None,
+impl ExerciseHistoryTarget {
+    // the same exercise as the history sees it: the same name or the same library entry
+    pub fn matches(&self, other: &ExerciseHistoryTarget) -> bool {
+        self.exercise_name
+            .eq_ignore_ascii_case(&other.exercise_name)
+            || (self.exercise_library_id.is_some()
+                && self.exercise_library_id == other.exercise_library_id)
+    }
+
+    pub fn to_filter(&self) -> WorkoutLogFilterReq {
+        WorkoutLogFilterReq {
+            workout_exercise_name: Some(self.exercise_name.clone()),
+            exercise_library_id: self.exercise_library_id,
+            ..Default::default()
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, FromRow)]
+#[derive(Clone, Default)]
+pub struct WorkoutLogGroupFilterReq {
+    pub workout_date_gte: Option<NaiveDate>,
+    pub workout_date_lte: Option<NaiveDate>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
 pub struct WorkoutLogDetailRes {
     pub id: u32,
     pub workout_log_group_id: u32,
@@ -104,10 +224,41 @@ pub struct WorkoutLogDetailRes {
     pub workout_name: String,
     pub workout_exercise_id: u32,
     pub workout_exercise_name: String,
+    pub exercise_library_id: Option<u32>,
     pub set_number: u8,
     pub rep_number_or_seconds: u8,
-    pub weight: u16,
+    pub weight: f64,
+    pub weight_unit: WeightUnit,
+    pub assistance_band: Option<Band>,
+    pub bodyweight_fraction: f64,
+    pub rpe: Option<f64>,
+    pub rir: Option<f64>,
     pub description: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub rest_period_seconds: u16, // planned rest after each set of the workout exercise
+    pub is_static: bool,          // the linked library exercise is a hold
+}
+
+impl WorkoutLogDetailRes {
+    pub fn weight_in(&self, unit: WeightUnit) -> f64 {
+        self.weight_unit.convert(self.weight, unit)
+    }
+
+    pub fn load(&self) -> Load {
+        Load {
+            weight: self.weight,
+            weight_unit: self.weight_unit,
+            assistance_band: self.assistance_band,
+            bodyweight_fraction: self.bodyweight_fraction,
+        }
+    }
+
+    pub fn history_target(&self) -> ExerciseHistoryTarget {
+        ExerciseHistoryTarget {
+            exercise_library_id: self.exercise_library_id,
+            exercise_name: self.workout_exercise_name.clone(),
+        }
+    }
 }
 
 impl HasId for WorkoutLogDetailRes {
@@ -115,3 +266,26 @@ impl HasId for WorkoutLogDetailRes {
         self.id
     }
 }
+
+#[derive(Debug, Clone, PartialEq, FromRow)]
+pub struct ExerciseAverageRpeRes {
+    pub workout_exercise_id: u32,
+    pub exercise_name: String,
+    pub average_rpe: f64,
+    pub set_count: u32, // sets with an rpe in the range
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WorkoutSessionDraftReq {
+    pub workout_id: u32,
+    pub session: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromRow)]
+pub struct WorkoutSessionDraftRes {
+    pub id: u32,
+    pub workout_id: u32,
+    pub workout_name: String,
+    pub updated_at: DateTime<Utc>,
+    pub session: String,
+}
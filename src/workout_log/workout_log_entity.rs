@@ -1,7 +1,8 @@
+use crate::enums::{Band, WeightUnit};
 use chrono::{DateTime, NaiveDate, Utc};
 use sqlx::FromRow;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, FromRow)]
+#[derive(Debug, Clone, PartialEq, FromRow)]
 pub struct WorkoutLogEntity {
     pub id: u32,
     pub workout_id: u32,           // fk to WorkoutEntity
@@ -10,8 +11,14 @@ pub struct WorkoutLogEntity {
     pub exercise_name: String,
     pub set_number: u8,
     pub rep_number_or_seconds: u8,
-    pub weight: u8,
+    pub weight: f64,             // negative for assistance
+    pub weight_unit: WeightUnit, // unit the weight was logged in
+    pub assistance_band: Option<Band>,
+    pub bodyweight_fraction: f64, // share of bodyweight moved, 0.0 for external load only
+    pub rpe: Option<f64>,         // rate of perceived exertion, 1-10 in half steps
+    pub rir: Option<f64>,         // reps in reserve, 1-10 in half steps
     pub description: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>, // when the set was checked off
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, FromRow)]
@@ -20,5 +27,16 @@ pub struct WorkoutLogGroupEntity {
     pub created_at: DateTime<Utc>, // should be some kinda DateTime
     pub date: NaiveDate,           // should be some kinda Date
     pub notes: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
 }
 // WorkoutLogGroupEntity -> WorkoutLogEntity 1:many
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, FromRow)]
+pub struct WorkoutSessionDraftEntity {
+    pub id: u32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub workout_id: u32, // fk to WorkoutEntity
+    pub session: String, // serialized in-progress session
+}
@@ -1,15 +1,47 @@
 use crate::db::pagination_support::{
-    PaginationParams, PaginationRes, get_cursors, keyset_paginate,
+    PaginationDirection, PaginationParams, PaginationRes, get_cursors, keyset_paginate,
 };
 use crate::db::{SqliteExecutor, SqliteTx};
+use crate::error::{AppError, AppResult};
 use crate::workout_log::workout_log_dto::{
-    WorkoutLogDetailRes, WorkoutLogGroupFilterReq, WorkoutLogGroupPageRes, WorkoutLogGroupReq,
-    WorkoutLogGroupRes, WorkoutLogReq,
+    ExerciseAverageRpeRes, RecentSessionRes, WorkoutLogDetailRes, WorkoutLogFilterReq,
+    WorkoutLogGroupFilterReq, WorkoutLogGroupPageRes, WorkoutLogGroupReq, WorkoutLogGroupRes,
+    WorkoutLogGroupUpdateReq, WorkoutLogReq, WorkoutLogUpdateReq, WorkoutSessionDraftReq,
+    WorkoutSessionDraftRes,
 };
 use crate::workout_log::workout_log_entity::WorkoutLogGroupEntity;
-use chrono::Utc;
+use chrono::{NaiveDate, Utc};
 use sqlx::{QueryBuilder, Sqlite};
 
+// every WorkoutLogDetailRes query selects these columns
+const LOG_DETAIL_SELECT: &str = r#"
+    SELECT wl.id,
+           wlg.id AS workout_log_group_id,
+           wlg.date AS workout_date,
+           wo.id AS workout_id,
+           wo.name AS workout_name,
+           wl.workout_exercise_id,
+           wl.exercise_name AS workout_exercise_name,
+           we.exercise_library_id,
+           wl.set_number,
+           wl.rep_number_or_seconds,
+           wl.weight,
+           wl.weight_unit,
+           wl.assistance_band,
+           wl.bodyweight_fraction,
+           wl.rpe,
+           wl.rir,
+           wl.description,
+           wl.completed_at,
+           we.rest_period_seconds,
+           COALESCE(el.dynamic_or_static = 'Static', 0) AS is_static
+    FROM workout_logs wl
+    JOIN workouts wo ON wl.workout_id = wo.id
+    JOIN workout_exercises we ON wl.workout_exercise_id = we.id
+    JOIN workout_log_groups wlg ON wl.workout_log_group_id = wlg.id
+    LEFT JOIN exercise_library el ON we.exercise_library_id = el.id
+"#;
+
 #[derive(Clone, Copy)]
 pub struct WorkoutLogRepo {}
 
@@ -22,32 +54,56 @@ impl WorkoutLogRepo {
         &self,
         tx: &mut SqliteTx<'_>,
         req: WorkoutLogGroupReq,
-    ) -> Result<u32, String> {
+    ) -> AppResult<u32> {
         let created_at = Utc::now();
 
         let result = sqlx::query(
-            r#"INSERT INTO workout_log_groups (created_at, date, notes) VALUES (?, ?, ?)"#,
+            r#"INSERT INTO workout_log_groups (created_at, date, notes, started_at, finished_at)
+            VALUES (?, ?, ?, ?, ?)"#,
         )
         .bind(created_at)
         .bind(req.date)
         .bind(req.notes)
+        .bind(req.started_at)
+        .bind(req.finished_at)
         .execute(&mut **tx)
         .await
-        .map_err(|e| format!("Failed to create log group: {}", e))?;
+        .map_err(AppError::db("Failed to create log group"))?;
 
         Ok(result.last_insert_rowid() as u32)
     }
 
-    pub async fn delete_log_group(&self, tx: &mut SqliteTx<'_>, id: u32) -> Result<(), String> {
-        // Will fail if any workout_log references it (ON DELETE RESTRICT)
+    pub async fn update_log_group(
+        &self,
+        tx: &mut SqliteTx<'_>,
+        id: u32,
+        req: WorkoutLogGroupUpdateReq,
+    ) -> AppResult<()> {
+        let result = sqlx::query("UPDATE workout_log_groups SET date = ?, notes = ? WHERE id = ?")
+            .bind(req.date)
+            .bind(req.notes)
+            .bind(id)
+            .execute(&mut **tx)
+            .await
+            .map_err(AppError::db("Failed to update log group"))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Workout log group"));
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete_log_group(&self, tx: &mut SqliteTx<'_>, id: u32) -> AppResult<()> {
+        // workout_logs reference their group with ON DELETE RESTRICT
         let result = sqlx::query("DELETE FROM workout_log_groups WHERE id = ?")
             .bind(id)
             .execute(&mut **tx)
             .await
-            .map_err(|e| format!("Failed to delete log group (may be in use): {}", e))?;
+            .map_err(AppError::db_delete("Workout log group"))?;
 
         if result.rows_affected() == 0 {
-            return Err("Log group not found".to_string());
+            return Err(AppError::NotFound("Log group"));
         }
 
         Ok(())
@@ -57,27 +113,26 @@ impl WorkoutLogRepo {
         &self,
         executor: impl SqliteExecutor<'e>,
         id: u32,
-    ) -> Result<WorkoutLogGroupRes, String> {
+    ) -> AppResult<WorkoutLogGroupRes> {
         let entity: WorkoutLogGroupEntity =
             sqlx::query_as("SELECT * FROM workout_log_groups WHERE id = ?")
                 .bind(id)
                 .fetch_optional(executor)
                 .await
-                .map_err(|e| format!("Database error: {}", e))?
-                .ok_or_else(|| "Workout log group not found".to_string())?;
+                .map_err(AppError::db("Database error"))?
+                .ok_or(AppError::NotFound("Workout log group"))?;
         Ok(WorkoutLogGroupRes::from_entity(entity))
     }
 
-    pub async fn create_log(
-        &self,
-        tx: &mut SqliteTx<'_>,
-        req: WorkoutLogReq,
-    ) -> Result<u32, String> {
+    pub async fn create_log(&self, tx: &mut SqliteTx<'_>, req: WorkoutLogReq) -> AppResult<u32> {
+        req.validate()?;
+
         let result = sqlx::query(
             r#"INSERT INTO workout_logs (
             workout_id, workout_exercise_id, workout_log_group_id,
-            exercise_name, set_number, rep_number_or_seconds, weight, description
-        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)"#,
+            exercise_name, set_number, rep_number_or_seconds, weight, weight_unit,
+            assistance_band, bodyweight_fraction, rpe, rir, description, completed_at
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#,
         )
         .bind(req.workout_id)
         .bind(req.workout_exercise_id)
@@ -86,81 +141,450 @@ impl WorkoutLogRepo {
         .bind(req.set_number)
         .bind(req.rep_number_or_seconds)
         .bind(req.weight)
+        .bind(req.weight_unit)
+        .bind(req.assistance_band)
+        .bind(req.bodyweight_fraction)
+        .bind(req.rpe)
+        .bind(req.rir)
         .bind(req.description)
+        .bind(req.completed_at)
         .execute(&mut **tx)
         .await
-        .map_err(|e| format!("Failed to create core log: {}", e))?;
+        .map_err(AppError::db("Failed to create workout log"))?;
 
         Ok(result.last_insert_rowid() as u32)
     }
 
-    pub async fn delete_log(&self, tx: &mut SqliteTx<'_>, id: u32) -> Result<(), String> {
+    // only a set of the given session, so an edit can't reach into another one
+    pub async fn update_log(
+        &self,
+        tx: &mut SqliteTx<'_>,
+        workout_log_group_id: u32,
+        id: u32,
+        req: WorkoutLogUpdateReq,
+    ) -> AppResult<()> {
+        req.validate()?;
+
+        let result = sqlx::query(
+            r#"
+        UPDATE workout_logs
+        SET set_number = ?, rep_number_or_seconds = ?, weight = ?, assistance_band = ?,
+            rpe = ?, rir = ?, description = ?
+        WHERE id = ? AND workout_log_group_id = ?
+        "#,
+        )
+        .bind(req.set_number)
+        .bind(req.rep_number_or_seconds)
+        .bind(req.weight)
+        .bind(req.assistance_band)
+        .bind(req.rpe)
+        .bind(req.rir)
+        .bind(req.description)
+        .bind(id)
+        .bind(workout_log_group_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::db("Failed to update workout log"))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Workout log"));
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete_log(&self, tx: &mut SqliteTx<'_>, id: u32) -> AppResult<()> {
         let result = sqlx::query("DELETE FROM workout_logs WHERE id = ?")
             .bind(id)
             .execute(&mut **tx)
             .await
-            .map_err(|e| format!("Failed to delete core log: {}", e))?;
+            .map_err(AppError::db_delete("Workout log"))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Workout log"));
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete_log_in_group(
+        &self,
+        tx: &mut SqliteTx<'_>,
+        workout_log_group_id: u32,
+        id: u32,
+    ) -> AppResult<()> {
+        let result =
+            sqlx::query("DELETE FROM workout_logs WHERE id = ? AND workout_log_group_id = ?")
+                .bind(id)
+                .bind(workout_log_group_id)
+                .execute(&mut **tx)
+                .await
+                .map_err(AppError::db_delete("Workout log"))?;
 
         if result.rows_affected() == 0 {
-            return Err("Workout log not found".to_string());
+            return Err(AppError::NotFound("Workout log"));
         }
 
         Ok(())
     }
 
+    pub async fn get_one_log<'e>(
+        &self,
+        executor: impl SqliteExecutor<'e>,
+        id: u32,
+    ) -> AppResult<WorkoutLogDetailRes> {
+        sqlx::query_as(&format!("{} WHERE wl.id = ?", LOG_DETAIL_SELECT))
+            .bind(id)
+            .fetch_optional(executor)
+            .await
+            .map_err(AppError::db("Database error"))?
+            .ok_or(AppError::NotFound("Workout log"))
+    }
+
+    // the given session and every session after it, in date order
+    pub async fn get_log_group_ids_from<'e>(
+        &self,
+        executor: impl SqliteExecutor<'e>,
+        date: NaiveDate,
+        id: u32,
+    ) -> AppResult<Vec<u32>> {
+        let res = sqlx::query_scalar(
+            "SELECT id FROM workout_log_groups WHERE (date, id) >= (?, ?) ORDER BY date, id",
+        )
+        .bind(date)
+        .bind(id)
+        .fetch_all(executor)
+        .await
+        .map_err(AppError::db("Database error"))?;
+
+        Ok(res)
+    }
+
     pub async fn get_logs_by_workout_log_group_id<'e>(
         &self,
         executor: impl SqliteExecutor<'e>,
         workout_log_group_id: u32,
-    ) -> Result<Vec<WorkoutLogDetailRes>, String> {
-        let res: Vec<WorkoutLogDetailRes> = sqlx::query_as(
+    ) -> AppResult<Vec<WorkoutLogDetailRes>> {
+        let res: Vec<WorkoutLogDetailRes> = sqlx::query_as(&format!(
+            "{} WHERE wl.workout_log_group_id = ?",
+            LOG_DETAIL_SELECT
+        ))
+        .bind(workout_log_group_id)
+        .fetch_all(executor)
+        .await
+        .map_err(AppError::db("Database error"))?;
+
+        Ok(res)
+    }
+
+    // sets from the most recent session that logged this workout exercise
+    pub async fn get_last_logs_by_workout_exercise_id<'e>(
+        &self,
+        executor: impl SqliteExecutor<'e>,
+        workout_exercise_id: u32,
+    ) -> AppResult<Vec<WorkoutLogDetailRes>> {
+        let res: Vec<WorkoutLogDetailRes> = sqlx::query_as(&format!(
+            r#"{}
+                WHERE wl.workout_exercise_id = ?
+                  AND wl.workout_log_group_id = (
+                      SELECT g.id
+                      FROM workout_log_groups g
+                      JOIN workout_logs l ON l.workout_log_group_id = g.id
+                      WHERE l.workout_exercise_id = ?
+                      ORDER BY g.date DESC, g.id DESC
+                      LIMIT 1
+                  )
+                ORDER BY wl.set_number
+                "#,
+            LOG_DETAIL_SELECT
+        ))
+        .bind(workout_exercise_id)
+        .bind(workout_exercise_id)
+        .fetch_all(executor)
+        .await
+        .map_err(AppError::db("Database error"))?;
+
+        Ok(res)
+    }
+
+    // sets without an rpe are left out of the average
+    pub async fn get_average_rpe_by_exercise<'e>(
+        &self,
+        executor: impl SqliteExecutor<'e>,
+        date_gte: Option<NaiveDate>,
+        date_lte: Option<NaiveDate>,
+    ) -> AppResult<Vec<ExerciseAverageRpeRes>> {
+        let mut qb = QueryBuilder::new(
             r#"
-                SELECT wl.id,
-                       wlg.id AS workout_log_group_id,
-                       wlg.date AS workout_date,
-                       wo.id AS workout_id,
-                       wo.name AS workout_name,
-                       wl.workout_exercise_id,
-                       wl.exercise_name AS workout_exercise_name,
-                       wl.set_number,
-                       wl.rep_number_or_seconds,
-                       wl.weight,
-                       wl.description
+                SELECT wl.workout_exercise_id,
+                       MAX(wl.exercise_name) AS exercise_name,
+                       AVG(wl.rpe) AS average_rpe,
+                       COUNT(wl.rpe) AS set_count
                 FROM workout_logs wl
-                JOIN workouts wo ON wl.workout_id = wo.id
                 JOIN workout_log_groups wlg ON wl.workout_log_group_id = wlg.id
-                WHERE wl.workout_log_group_id = ?
+                WHERE wl.rpe IS NOT NULL
+                "#,
+        );
+        if let Some(date_gte) = date_gte {
+            qb.push(" AND wlg.date >= ");
+            qb.push_bind(date_gte);
+        }
+        if let Some(date_lte) = date_lte {
+            qb.push(" AND wlg.date <= ");
+            qb.push_bind(date_lte);
+        }
+        qb.push(" GROUP BY wl.workout_exercise_id ORDER BY exercise_name");
+
+        let res = qb
+            .build_query_as()
+            .fetch_all(executor)
+            .await
+            .map_err(AppError::db("Database error"))?;
+
+        Ok(res)
+    }
+
+    // newest first
+    pub async fn get_recent_sessions<'e>(
+        &self,
+        executor: impl SqliteExecutor<'e>,
+        limit: u32,
+    ) -> AppResult<Vec<RecentSessionRes>> {
+        let res = sqlx::query_as(
+            r#"
+                SELECT wlg.id,
+                       wlg.date,
+                       wlg.notes,
+                       GROUP_CONCAT(DISTINCT wo.name) AS workout_names,
+                       COUNT(wl.id) AS set_count
+                FROM workout_log_groups wlg
+                LEFT JOIN workout_logs wl ON wl.workout_log_group_id = wlg.id
+                LEFT JOIN workouts wo ON wl.workout_id = wo.id
+                GROUP BY wlg.id
+                ORDER BY wlg.date DESC, wlg.id DESC
+                LIMIT ?
                 "#,
         )
-        .bind(workout_log_group_id)
+        .bind(limit)
         .fetch_all(executor)
         .await
-        .map_err(|e| format!("Database error: {}", e))?;
+        .map_err(AppError::db("Database error"))?;
 
         Ok(res)
     }
 
+    pub async fn count_log_groups<'e>(
+        &self,
+        executor: impl SqliteExecutor<'e>,
+        date_gte: NaiveDate,
+        date_lte: NaiveDate,
+    ) -> AppResult<u32> {
+        let count: u32 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM workout_log_groups WHERE date >= ? AND date <= ?",
+        )
+        .bind(date_gte)
+        .bind(date_lte)
+        .fetch_one(executor)
+        .await
+        .map_err(AppError::db("Database error"))?;
+
+        Ok(count)
+    }
+
     pub async fn paginate_workout_log_groups<'e>(
         &self,
         executor: impl SqliteExecutor<'e>,
         pagination_filters: Option<WorkoutLogGroupFilterReq>,
         pagination_params: PaginationParams,
-    ) -> Result<PaginationRes<WorkoutLogGroupPageRes>, String> {
+    ) -> AppResult<PaginationRes<WorkoutLogGroupPageRes>> {
         let mut qb = QueryBuilder::new("SELECT * FROM workout_log_groups WHERE 1=1");
         self.log_group_pagination_filters(pagination_filters, &mut qb);
         keyset_paginate(&pagination_params, None, &mut qb);
 
-        let mut rows: Vec<WorkoutLogGroupPageRes> =
-            qb.build_query_as()
-                .fetch_all(executor)
-                .await
-                .map_err(|e| format!("Database error: {}", e))?;
+        let mut rows: Vec<WorkoutLogGroupPageRes> = qb
+            .build_query_as()
+            .fetch_all(executor)
+            .await
+            .map_err(AppError::db("Database error"))?;
+
+        let cursors = get_cursors(&pagination_params, &mut rows);
+
+        Ok(PaginationRes::new(rows, cursors))
+    }
+
+    pub async fn paginate_workout_logs<'e>(
+        &self,
+        executor: impl SqliteExecutor<'e>,
+        pagination_filters: Option<WorkoutLogFilterReq>,
+        pagination_params: PaginationParams,
+    ) -> AppResult<PaginationRes<WorkoutLogDetailRes>> {
+        let mut qb = QueryBuilder::new(LOG_DETAIL_SELECT);
+        qb.push(" WHERE 1=1");
+        self.log_pagination_filters(pagination_filters, &mut qb);
+        self.session_keyset_paginate(&pagination_params, &mut qb);
+
+        let mut rows: Vec<WorkoutLogDetailRes> = qb
+            .build_query_as()
+            .fetch_all(executor)
+            .await
+            .map_err(AppError::db("Database error"))?;
 
         let cursors = get_cursors(&pagination_params, &mut rows);
 
         Ok(PaginationRes::new(rows, cursors))
     }
 
+    pub async fn create_draft(
+        &self,
+        tx: &mut SqliteTx<'_>,
+        req: WorkoutSessionDraftReq,
+    ) -> AppResult<u32> {
+        let now = Utc::now();
+
+        let result = sqlx::query(
+            r#"INSERT INTO workout_session_drafts (created_at, updated_at, workout_id, session) VALUES (?, ?, ?, ?)"#,
+        )
+        .bind(now)
+        .bind(now)
+        .bind(req.workout_id)
+        .bind(req.session)
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::db("Failed to create session draft"))?;
+
+        Ok(result.last_insert_rowid() as u32)
+    }
+
+    pub async fn update_draft(
+        &self,
+        tx: &mut SqliteTx<'_>,
+        id: u32,
+        req: WorkoutSessionDraftReq,
+    ) -> AppResult<()> {
+        let result = sqlx::query(
+            r#"UPDATE workout_session_drafts SET updated_at = ?, workout_id = ?, session = ? WHERE id = ?"#,
+        )
+        .bind(Utc::now())
+        .bind(req.workout_id)
+        .bind(req.session)
+        .bind(id)
+        .execute(&mut **tx)
+        .await
+        .map_err(AppError::db("Failed to update session draft"))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Session draft"));
+        }
+
+        Ok(())
+    }
+
+    pub async fn delete_draft(&self, tx: &mut SqliteTx<'_>, id: u32) -> AppResult<()> {
+        let result = sqlx::query("DELETE FROM workout_session_drafts WHERE id = ?")
+            .bind(id)
+            .execute(&mut **tx)
+            .await
+            .map_err(AppError::db_delete("Session draft"))?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Session draft"));
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_all_drafts<'e>(
+        &self,
+        executor: impl SqliteExecutor<'e>,
+    ) -> AppResult<Vec<WorkoutSessionDraftRes>> {
+        let res = sqlx::query_as(
+            r#"SELECT
+                d.id,
+                d.workout_id,
+                w.name AS workout_name,
+                d.updated_at,
+                d.session
+            FROM workout_session_drafts d
+            JOIN workouts w ON d.workout_id = w.id
+            ORDER BY d.updated_at DESC, d.id DESC"#,
+        )
+        .fetch_all(executor)
+        .await
+        .map_err(AppError::db("Database error"))?;
+
+        Ok(res)
+    }
+
+    // pages by session date then session so each session's sets stay together,
+    // the cursor is still a log id and its position is looked up from its session
+    fn session_keyset_paginate(&self, params: &PaginationParams, qb: &mut QueryBuilder<Sqlite>) {
+        let (comparison, order) = match params.direction {
+            PaginationDirection::Forward => (">", "ASC"),
+            PaginationDirection::Backward => ("<", "DESC"),
+        };
+        if let Some(cursor) = params.cursor {
+            qb.push(format!(
+                r#" AND (wlg.date, wlg.id, wl.id) {} (
+                    SELECT cg.date, cg.id, cl.id
+                    FROM workout_logs cl
+                    JOIN workout_log_groups cg ON cl.workout_log_group_id = cg.id
+                    WHERE cl.id = "#,
+                comparison
+            ));
+            qb.push_bind(cursor);
+            qb.push(")");
+        }
+        qb.push(format!(
+            " ORDER BY wlg.date {0}, wlg.id {0}, wl.id {0} LIMIT ",
+            order
+        ));
+        qb.push_bind(params.limit + 1);
+    }
+
+    fn log_pagination_filters(
+        &self,
+        filter_req: Option<WorkoutLogFilterReq>,
+        qb: &mut QueryBuilder<Sqlite>,
+    ) {
+        if let Some(req) = filter_req {
+            if let Some(date_gte) = req.workout_date_gte {
+                qb.push(" AND wlg.date >= ");
+                qb.push_bind(date_gte);
+            }
+
+            if let Some(date_lte) = req.workout_date_lte {
+                qb.push(" AND wlg.date <= ");
+                qb.push_bind(date_lte);
+            }
+
+            if let Some(workout_name) = req.workout_name {
+                qb.push(" AND wo.name LIKE ");
+                qb.push_bind(format!("%{}%", workout_name));
+            }
+
+            // exact name so "Squat" doesn't pull in "Front Squat"
+            match (req.exercise_library_id, req.workout_exercise_name) {
+                (Some(library_id), Some(name)) => {
+                    qb.push(" AND (we.exercise_library_id = ");
+                    qb.push_bind(library_id);
+                    qb.push(" OR wl.exercise_name = ");
+                    qb.push_bind(name);
+                    qb.push(" COLLATE NOCASE)");
+                }
+                (Some(library_id), None) => {
+                    qb.push(" AND we.exercise_library_id = ");
+                    qb.push_bind(library_id);
+                }
+                (None, Some(name)) => {
+                    qb.push(" AND wl.exercise_name = ");
+                    qb.push_bind(name);
+                    qb.push(" COLLATE NOCASE");
+                }
+                (None, None) => {}
+            }
+        }
+    }
+
     fn log_group_pagination_filters(
         &self,
         filter_req: Option<WorkoutLogGroupFilterReq>,
@@ -189,16 +613,18 @@ impl WorkoutLogRepo {
 mod tests {
     use crate::db::pagination_support::{PaginationDirection, PaginationParams};
     use crate::db::{IN_MEMORY_DB_URL, SqliteTx, init_db};
-    use crate::enums::{Band, Equipment};
+    use crate::enums::{Band, Equipment, WeightUnit};
+    use crate::error::AppError;
     use crate::workout_log::workout_log_dto::{
-        WorkoutLogGroupFilterReq, WorkoutLogGroupReq, WorkoutLogReq,
+        WorkoutLogFilterReq, WorkoutLogGroupFilterReq, WorkoutLogGroupReq,
+        WorkoutLogGroupUpdateReq, WorkoutLogReq, WorkoutLogUpdateReq, WorkoutSessionDraftReq,
     };
     use crate::workout_log::workout_log_repo::WorkoutLogRepo;
     use chrono::{NaiveDate, Utc};
     use sqlx::SqlitePool;
 
     async fn setup_db() -> SqlitePool {
-        init_db(IN_MEMORY_DB_URL).await
+        init_db(IN_MEMORY_DB_URL).await.unwrap()
     }
 
     // Helper: Create dummy data
@@ -255,6 +681,8 @@ mod tests {
         let workout_log_group_req = WorkoutLogGroupReq {
             date: today,
             notes: Some("Morning session".to_string()),
+            started_at: Some(Utc::now() - chrono::TimeDelta::minutes(50)),
+            finished_at: Some(Utc::now()),
         };
 
         // Create
@@ -270,6 +698,7 @@ mod tests {
             .expect("Failed to get log group");
         assert_eq!(group.date, today);
         assert_eq!(group.notes, Some("Morning session".to_string()));
+        assert_eq!(group.duration().unwrap().num_minutes(), 50);
 
         // Delete
         repository
@@ -299,6 +728,8 @@ mod tests {
         let workout_log_group_req = WorkoutLogGroupReq {
             date: today,
             notes: None,
+            started_at: None,
+            finished_at: None,
         };
 
         let group_id = repository
@@ -307,6 +738,7 @@ mod tests {
             .unwrap();
 
         // Create log
+        let completed_at = Utc::now();
         let log_req = WorkoutLogReq {
             workout_id,
             workout_exercise_id,
@@ -314,8 +746,14 @@ mod tests {
             exercise_name: "Dummy Ex".to_string(),
             set_number: 2,
             rep_number_or_seconds: 8,
-            weight: 95,
+            weight: -15.0,
+            weight_unit: WeightUnit::Kg,
+            assistance_band: Some(Band::Yellow),
+            bodyweight_fraction: 1.0,
+            rpe: Some(8.5),
+            rir: Some(1.5),
             description: Some("Felt strong".to_string()),
+            completed_at: Some(completed_at),
         };
 
         let log_id = repository
@@ -334,14 +772,21 @@ mod tests {
         assert_eq!(log.workout_exercise_id, workout_exercise_id);
         assert_eq!(log.workout_log_group_id, group_id);
         assert_eq!(log.set_number, 2);
-        assert_eq!(log.weight, 95);
+        assert_eq!(log.weight, -15.0);
+        assert_eq!(log.weight_unit, WeightUnit::Kg);
+        assert_eq!(log.assistance_band, Some(Band::Yellow));
+        assert_eq!(log.bodyweight_fraction, 1.0);
+        assert_eq!(log.rpe, Some(8.5));
+        assert_eq!(log.rir, Some(1.5));
         assert_eq!(log.description, Some("Felt strong".to_string()));
+        assert_eq!(log.completed_at, Some(completed_at));
+        assert_eq!(log.rest_period_seconds, 60);
 
         // Delete
         repository
             .delete_log(&mut tx, log_id)
             .await
-            .expect("Failed to delete core log");
+            .expect("Failed to delete workout log");
 
         let found_again = repository
             .get_logs_by_workout_log_group_id(&mut *tx, group_id)
@@ -353,6 +798,87 @@ mod tests {
         tx.commit().await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_get_last_logs_by_workout_exercise_id() {
+        let pool = setup_db().await;
+        let mut tx = pool.begin().await.unwrap();
+        let repository = WorkoutLogRepo::new();
+
+        let (workout_id, workout_exercise_id) = create_workout_exercise(&mut tx).await;
+
+        // nothing logged yet
+        let none = repository
+            .get_last_logs_by_workout_exercise_id(&mut *tx, workout_exercise_id)
+            .await
+            .unwrap();
+        assert!(none.is_empty());
+
+        let older_group_id = repository
+            .create_log_group(
+                &mut tx,
+                WorkoutLogGroupReq {
+                    date: NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+                    notes: None,
+                    started_at: None,
+                    finished_at: None,
+                },
+            )
+            .await
+            .unwrap();
+        let newer_group_id = repository
+            .create_log_group(
+                &mut tx,
+                WorkoutLogGroupReq {
+                    date: NaiveDate::from_ymd_opt(2023, 1, 8).unwrap(),
+                    notes: None,
+                    started_at: None,
+                    finished_at: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        for (group_id, set_number, weight) in [
+            (older_group_id, 1, 20.0),
+            (newer_group_id, 2, 27.5),
+            (newer_group_id, 1, 26.25),
+        ] {
+            let log_req = WorkoutLogReq {
+                workout_id,
+                workout_exercise_id,
+                workout_log_group_id: group_id,
+                exercise_name: "Dummy Ex".to_string(),
+                set_number,
+                rep_number_or_seconds: 10,
+                weight,
+                weight_unit: WeightUnit::Kg,
+                assistance_band: None,
+                bodyweight_fraction: 0.0,
+                rpe: None,
+                rir: None,
+                description: None,
+                completed_at: None,
+            };
+            repository.create_log(&mut tx, log_req).await.unwrap();
+        }
+
+        let last = repository
+            .get_last_logs_by_workout_exercise_id(&mut *tx, workout_exercise_id)
+            .await
+            .expect("Failed to get last logs");
+
+        assert_eq!(last.len(), 2);
+        assert!(
+            last.iter()
+                .all(|l| l.workout_log_group_id == newer_group_id)
+        );
+        assert_eq!(last[0].set_number, 1);
+        assert_eq!(last[0].weight, 26.25);
+        assert_eq!(last[1].set_number, 2);
+
+        tx.commit().await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_paginate_workout_log_groups() {
         let pool = setup_db().await;
@@ -364,6 +890,8 @@ mod tests {
         let req1 = WorkoutLogGroupReq {
             date: date1,
             notes: Some("Note 1".to_string()),
+            started_at: None,
+            finished_at: None,
         };
         let _g1_id = repository.create_log_group(&mut tx, req1).await.unwrap();
 
@@ -371,6 +899,8 @@ mod tests {
         let req2 = WorkoutLogGroupReq {
             date: date2,
             notes: Some("Note 2".to_string()),
+            started_at: None,
+            finished_at: None,
         };
         let _g2_id = repository.create_log_group(&mut tx, req2).await.unwrap();
 
@@ -378,6 +908,8 @@ mod tests {
         let req3 = WorkoutLogGroupReq {
             date: date3,
             notes: Some("Note 3".to_string()),
+            started_at: None,
+            finished_at: None,
         };
         let _g3_id = repository.create_log_group(&mut tx, req3).await.unwrap();
 
@@ -434,6 +966,466 @@ mod tests {
         tx.commit().await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_paginate_workout_logs_for_exercise() {
+        let pool = setup_db().await;
+        let mut tx = pool.begin().await.unwrap();
+        let repository = WorkoutLogRepo::new();
+
+        let (workout_id, dummy_exercise_id) = create_workout_exercise(&mut tx).await;
+
+        let library_id = sqlx::query(
+            r#"INSERT INTO exercise_library (
+                name, dynamic_or_static, upper_or_lower, compound_or_isolation
+            ) VALUES ('Pull Up', 'Dynamic', 'Upper', 'Compound')"#,
+        )
+        .execute(&mut *tx)
+        .await
+        .unwrap()
+        .last_insert_rowid() as u32;
+
+        // linked to the library, logged under the same name without a link, and a similar name
+        let mut workout_exercise_ids = vec![];
+        for (name, exercise_library_id) in [
+            ("Wide Pull Up", Some(library_id)),
+            ("pull up", None),
+            ("Weighted Pull Up", None),
+        ] {
+            let id = sqlx::query(
+                r#"INSERT INTO workout_exercises (
+                    created_at, workout_id, exercise_library_id, code, name,
+                    sets_target, reps_or_seconds_target, working_weight,
+                    rest_period_seconds, tempo, emom, equipments, bands
+                ) VALUES (?, ?, ?, 'B1', ?, 3, 5, 0, 90, '2010', 0, '[]', '[]')"#,
+            )
+            .bind(Utc::now())
+            .bind(workout_id)
+            .bind(exercise_library_id)
+            .bind(name)
+            .execute(&mut *tx)
+            .await
+            .unwrap()
+            .last_insert_rowid() as u32;
+            workout_exercise_ids.push((id, name));
+        }
+
+        for day in [1, 8] {
+            let group_id = repository
+                .create_log_group(
+                    &mut tx,
+                    WorkoutLogGroupReq {
+                        date: NaiveDate::from_ymd_opt(2023, 1, day).unwrap(),
+                        notes: None,
+                        started_at: None,
+                        finished_at: None,
+                    },
+                )
+                .await
+                .unwrap();
+            for (workout_exercise_id, name) in workout_exercise_ids
+                .iter()
+                .copied()
+                .chain([(dummy_exercise_id, "Dummy Ex")])
+            {
+                let log_req = WorkoutLogReq {
+                    workout_id,
+                    workout_exercise_id,
+                    workout_log_group_id: group_id,
+                    exercise_name: name.to_string(),
+                    set_number: 1,
+                    rep_number_or_seconds: 5,
+                    weight: 10.0,
+                    weight_unit: WeightUnit::Kg,
+                    assistance_band: None,
+                    bodyweight_fraction: 1.0,
+                    rpe: None,
+                    rir: None,
+                    description: None,
+                    completed_at: None,
+                };
+                repository.create_log(&mut tx, log_req).await.unwrap();
+            }
+        }
+
+        let filters = WorkoutLogFilterReq {
+            exercise_library_id: Some(library_id),
+            workout_exercise_name: Some("Pull Up".to_string()),
+            ..Default::default()
+        };
+        let params = PaginationParams {
+            limit: 3,
+            cursor: None,
+            direction: PaginationDirection::Forward,
+        };
+        let res = repository
+            .paginate_workout_logs(&mut *tx, Some(filters.clone()), params)
+            .await
+            .expect("Pagination failed");
+        assert_eq!(res.items.len(), 3);
+        assert!(res.next_cursor.is_some());
+        assert_eq!(res.items[0].exercise_library_id, Some(library_id));
+        assert_eq!(res.items[1].workout_exercise_name, "pull up");
+
+        let res_next = repository
+            .paginate_workout_logs(
+                &mut *tx,
+                Some(filters.clone()),
+                PaginationParams {
+                    limit: 3,
+                    cursor: res.next_cursor,
+                    direction: PaginationDirection::Forward,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(res_next.items.len(), 1);
+        assert!(res_next.next_cursor.is_none());
+        assert!(
+            res.items
+                .iter()
+                .chain(&res_next.items)
+                .all(|l| l.workout_exercise_name != "Weighted Pull Up")
+        );
+
+        // date range and workout name narrow it down
+        let second_week = repository
+            .paginate_workout_logs(
+                &mut *tx,
+                Some(WorkoutLogFilterReq {
+                    workout_date_gte: NaiveDate::from_ymd_opt(2023, 1, 2),
+                    workout_name: Some("test".to_string()),
+                    ..filters
+                }),
+                PaginationParams {
+                    limit: 10,
+                    cursor: None,
+                    direction: PaginationDirection::Forward,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(second_week.items.len(), 2);
+        assert!(
+            second_week
+                .items
+                .iter()
+                .all(|l| l.workout_date == NaiveDate::from_ymd_opt(2023, 1, 8).unwrap())
+        );
+
+        tx.commit().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_paginate_workout_logs_keeps_sessions_together() {
+        let pool = setup_db().await;
+        let mut tx = pool.begin().await.unwrap();
+        let repository = WorkoutLogRepo::new();
+
+        let (workout_id, workout_exercise_id) = create_workout_exercise(&mut tx).await;
+        let log = |group_id: u32, set_number: u8| WorkoutLogReq {
+            workout_id,
+            workout_exercise_id,
+            workout_log_group_id: group_id,
+            exercise_name: "Dummy Ex".to_string(),
+            set_number,
+            rep_number_or_seconds: 5,
+            weight: 10.0,
+            weight_unit: WeightUnit::Kg,
+            assistance_band: None,
+            bodyweight_fraction: 1.0,
+            rpe: None,
+            rir: None,
+            description: None,
+            completed_at: None,
+        };
+
+        // logged out of date order: the 10th, then a backfilled 3rd, then a set added to the 10th
+        let mut group_ids = vec![];
+        for day in [10, 3] {
+            let group_id = repository
+                .create_log_group(
+                    &mut tx,
+                    WorkoutLogGroupReq {
+                        date: NaiveDate::from_ymd_opt(2023, 1, day).unwrap(),
+                        notes: None,
+                        started_at: None,
+                        finished_at: None,
+                    },
+                )
+                .await
+                .unwrap();
+            for set_number in 1..=2 {
+                repository
+                    .create_log(&mut tx, log(group_id, set_number))
+                    .await
+                    .unwrap();
+            }
+            group_ids.push(group_id);
+        }
+        repository
+            .create_log(&mut tx, log(group_ids[0], 3))
+            .await
+            .unwrap();
+
+        let filters = WorkoutLogFilterReq {
+            workout_exercise_name: Some("Dummy Ex".to_string()),
+            ..Default::default()
+        };
+        let mut pages = vec![];
+        let mut cursor = None;
+        loop {
+            let page = repository
+                .paginate_workout_logs(
+                    &mut *tx,
+                    Some(filters.clone()),
+                    PaginationParams {
+                        limit: 2,
+                        cursor,
+                        direction: PaginationDirection::Forward,
+                    },
+                )
+                .await
+                .unwrap();
+            pages.push(page.items);
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        let order: Vec<(u32, u8)> = pages
+            .iter()
+            .flatten()
+            .map(|l| (l.workout_log_group_id, l.set_number))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                (group_ids[1], 1),
+                (group_ids[1], 2),
+                (group_ids[0], 1),
+                (group_ids[0], 2),
+                (group_ids[0], 3),
+            ]
+        );
+
+        // going back from the last page returns the page before it
+        let last_page_first_id = pages.last().unwrap()[0].id;
+        let back = repository
+            .paginate_workout_logs(
+                &mut *tx,
+                Some(filters),
+                PaginationParams {
+                    limit: 2,
+                    cursor: Some(last_page_first_id),
+                    direction: PaginationDirection::Backward,
+                },
+            )
+            .await
+            .unwrap();
+        let back_ids: Vec<u32> = back.items.iter().map(|l| l.id).collect();
+        let expected: Vec<u32> = pages[pages.len() - 2].iter().map(|l| l.id).collect();
+        assert_eq!(back_ids, expected);
+
+        tx.commit().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_recent_sessions_and_count() {
+        let pool = setup_db().await;
+        let mut tx = pool.begin().await.unwrap();
+        let repository = WorkoutLogRepo::new();
+
+        let (workout_id, workout_exercise_id) = create_workout_exercise(&mut tx).await;
+
+        let mut group_ids = vec![];
+        for day in [1, 3, 4, 9] {
+            let group_id = repository
+                .create_log_group(
+                    &mut tx,
+                    WorkoutLogGroupReq {
+                        date: NaiveDate::from_ymd_opt(2024, 1, day).unwrap(),
+                        notes: None,
+                        started_at: None,
+                        finished_at: None,
+                    },
+                )
+                .await
+                .unwrap();
+            group_ids.push(group_id);
+        }
+        for set_number in 1..=2 {
+            let log_req = WorkoutLogReq {
+                workout_id,
+                workout_exercise_id,
+                workout_log_group_id: group_ids[2],
+                exercise_name: "Dummy Ex".to_string(),
+                set_number,
+                rep_number_or_seconds: 5,
+                weight: 100.0,
+                weight_unit: WeightUnit::Kg,
+                assistance_band: None,
+                bodyweight_fraction: 0.0,
+                rpe: None,
+                rir: None,
+                description: None,
+                completed_at: None,
+            };
+            repository.create_log(&mut tx, log_req).await.unwrap();
+        }
+
+        let recent = repository.get_recent_sessions(&mut *tx, 3).await.unwrap();
+        assert_eq!(
+            recent.iter().map(|s| s.id).collect::<Vec<_>>(),
+            vec![group_ids[3], group_ids[2], group_ids[1]]
+        );
+        assert_eq!(recent[0].workout_names, None);
+        assert_eq!(recent[0].set_count, 0);
+        assert_eq!(recent[1].workout_names, Some("Test Workout".to_string()));
+        assert_eq!(recent[1].set_count, 2);
+
+        // monday 2024-01-01 to sunday 2024-01-07
+        let count = repository
+            .count_log_groups(
+                &mut *tx,
+                NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2024, 1, 7).unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(count, 3);
+
+        tx.commit().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_update_log_and_log_group() {
+        let pool = setup_db().await;
+        let mut tx = pool.begin().await.unwrap();
+        let repository = WorkoutLogRepo::new();
+
+        let (workout_id, workout_exercise_id) = create_workout_exercise(&mut tx).await;
+        let group_id = repository
+            .create_log_group(&mut tx, WorkoutLogGroupReq::new(None))
+            .await
+            .unwrap();
+        let log_id = repository
+            .create_log(
+                &mut tx,
+                WorkoutLogReq {
+                    workout_id,
+                    workout_exercise_id,
+                    workout_log_group_id: group_id,
+                    exercise_name: "Dummy Ex".to_string(),
+                    set_number: 1,
+                    rep_number_or_seconds: 5,
+                    weight: 1000.0, // meant 100
+                    weight_unit: WeightUnit::Kg,
+                    assistance_band: None,
+                    bodyweight_fraction: 0.0,
+                    rpe: None,
+                    rir: None,
+                    description: None,
+                    completed_at: None,
+                },
+            )
+            .await
+            .unwrap();
+
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        repository
+            .update_log_group(
+                &mut tx,
+                group_id,
+                WorkoutLogGroupUpdateReq {
+                    date,
+                    notes: Some("Logged the next day".to_string()),
+                },
+            )
+            .await
+            .expect("Failed to update log group");
+        let fix = WorkoutLogUpdateReq {
+            set_number: 1,
+            rep_number_or_seconds: 6,
+            weight: 100.0,
+            assistance_band: None,
+            rpe: Some(8.0),
+            rir: None,
+            description: Some("fixed".to_string()),
+        };
+        repository
+            .update_log(&mut tx, group_id, log_id, fix.clone())
+            .await
+            .expect("Failed to update workout log");
+
+        let group = repository
+            .get_one_log_group(&mut *tx, group_id)
+            .await
+            .unwrap();
+        assert_eq!(group.date, date);
+        assert_eq!(group.notes, Some("Logged the next day".to_string()));
+
+        let logs = repository
+            .get_logs_by_workout_log_group_id(&mut *tx, group_id)
+            .await
+            .unwrap();
+        assert_eq!(logs[0].workout_date, date);
+        assert_eq!(logs[0].rep_number_or_seconds, 6);
+        assert_eq!(logs[0].weight, 100.0);
+        assert_eq!(logs[0].rpe, Some(8.0));
+        assert_eq!(logs[0].description, Some("fixed".to_string()));
+
+        // invalid effort is rejected like on create, missing rows are not found
+        let invalid = WorkoutLogUpdateReq {
+            rpe: Some(11.0),
+            ..fix.clone()
+        };
+        assert!(matches!(
+            repository
+                .update_log(&mut tx, group_id, log_id, invalid)
+                .await,
+            Err(AppError::Validation(_))
+        ));
+        assert!(matches!(
+            repository
+                .update_log(&mut tx, group_id, log_id + 1, fix.clone())
+                .await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            repository
+                .update_log_group(
+                    &mut tx,
+                    group_id + 1,
+                    WorkoutLogGroupUpdateReq { date, notes: None }
+                )
+                .await,
+            Err(AppError::NotFound(_))
+        ));
+
+        // a set is only reached through its own session
+        let other_group_id = repository
+            .create_log_group(&mut tx, WorkoutLogGroupReq::new(None))
+            .await
+            .unwrap();
+        assert!(matches!(
+            repository
+                .update_log(&mut tx, other_group_id, log_id, fix)
+                .await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(matches!(
+            repository
+                .delete_log_in_group(&mut tx, other_group_id, log_id)
+                .await,
+            Err(AppError::NotFound(_))
+        ));
+        repository
+            .delete_log_in_group(&mut tx, group_id, log_id)
+            .await
+            .expect("Failed to delete workout log");
+    }
+
     #[tokio::test]
     async fn test_cannot_delete_log_group_with_logs() {
         let pool = setup_db().await;
@@ -445,6 +1437,8 @@ mod tests {
         let workout_log_group_req = WorkoutLogGroupReq {
             date: today,
             notes: None,
+            started_at: None,
+            finished_at: None,
         };
         let group_id = repository
             .create_log_group(&mut tx, workout_log_group_req)
@@ -459,15 +1453,190 @@ mod tests {
             exercise_name: "Dummy Ex".to_string(),
             set_number: 1,
             rep_number_or_seconds: 10,
-            weight: 80,
+            weight: 80.0,
+            weight_unit: WeightUnit::Kg,
+            assistance_band: None,
+            bodyweight_fraction: 0.0,
+            rpe: None,
+            rir: None,
             description: None,
+            completed_at: None,
         };
         repository.create_log(&mut tx, log_req).await.unwrap();
 
         // Attempt to delete group → should fail
         let result = repository.delete_log_group(&mut tx, group_id).await;
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("may be in use"));
+        assert!(matches!(
+            result,
+            Err(AppError::ForeignKeyRestricted {
+                what: "Workout log group",
+                ..
+            })
+        ));
+
+        tx.commit().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_session_draft_crud() {
+        let pool = setup_db().await;
+        let mut tx = pool.begin().await.unwrap();
+        let repository = WorkoutLogRepo::new();
+
+        let (workout_id, _) = create_workout_exercise(&mut tx).await;
+
+        // Create
+        let draft_id = repository
+            .create_draft(
+                &mut tx,
+                WorkoutSessionDraftReq {
+                    workout_id,
+                    session: "{\"sets\":1}".to_string(),
+                },
+            )
+            .await
+            .expect("Create draft failed");
+
+        let drafts = repository.get_all_drafts(&mut *tx).await.unwrap();
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(drafts[0].id, draft_id);
+        assert_eq!(drafts[0].workout_name, "Test Workout");
+
+        // Update
+        repository
+            .update_draft(
+                &mut tx,
+                draft_id,
+                WorkoutSessionDraftReq {
+                    workout_id,
+                    session: "{\"sets\":2}".to_string(),
+                },
+            )
+            .await
+            .expect("Update draft failed");
+
+        let drafts = repository.get_all_drafts(&mut *tx).await.unwrap();
+        assert_eq!(drafts[0].session, "{\"sets\":2}");
+
+        // Delete
+        repository.delete_draft(&mut tx, draft_id).await.unwrap();
+        assert!(
+            repository
+                .get_all_drafts(&mut *tx)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(repository.delete_draft(&mut tx, draft_id).await.is_err());
+
+        tx.commit().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_get_average_rpe_by_exercise() {
+        let pool = setup_db().await;
+        let mut tx = pool.begin().await.unwrap();
+        let repository = WorkoutLogRepo::new();
+
+        let (workout_id, workout_exercise_id) = create_workout_exercise(&mut tx).await;
+
+        let mut group_ids = vec![];
+        for day in [1, 8] {
+            let group_id = repository
+                .create_log_group(
+                    &mut tx,
+                    WorkoutLogGroupReq {
+                        date: NaiveDate::from_ymd_opt(2023, 1, day).unwrap(),
+                        notes: None,
+                        started_at: None,
+                        finished_at: None,
+                    },
+                )
+                .await
+                .unwrap();
+            group_ids.push(group_id);
+        }
+
+        for (group_id, set_number, rpe) in [
+            (group_ids[0], 1, Some(6.0)),
+            (group_ids[1], 1, Some(8.0)),
+            (group_ids[1], 2, Some(9.5)),
+            (group_ids[1], 3, None),
+        ] {
+            let log_req = WorkoutLogReq {
+                workout_id,
+                workout_exercise_id,
+                workout_log_group_id: group_id,
+                exercise_name: "Dummy Ex".to_string(),
+                set_number,
+                rep_number_or_seconds: 5,
+                weight: 100.0,
+                weight_unit: WeightUnit::Kg,
+                assistance_band: None,
+                bodyweight_fraction: 0.0,
+                rpe,
+                rir: None,
+                description: None,
+                completed_at: None,
+            };
+            repository.create_log(&mut tx, log_req).await.unwrap();
+        }
+
+        let all = repository
+            .get_average_rpe_by_exercise(&mut *tx, None, None)
+            .await
+            .expect("Failed to get average rpe");
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].workout_exercise_id, workout_exercise_id);
+        assert_eq!(all[0].set_count, 3);
+        assert!((all[0].average_rpe - 23.5 / 3.0).abs() < 1e-9);
+
+        let second_week = repository
+            .get_average_rpe_by_exercise(
+                &mut *tx,
+                NaiveDate::from_ymd_opt(2023, 1, 2),
+                NaiveDate::from_ymd_opt(2023, 1, 31),
+            )
+            .await
+            .unwrap();
+        assert_eq!(second_week[0].set_count, 2);
+        assert_eq!(second_week[0].average_rpe, 8.75);
+
+        tx.commit().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_create_log_rejects_invalid_effort() {
+        let pool = setup_db().await;
+        let mut tx = pool.begin().await.unwrap();
+        let repository = WorkoutLogRepo::new();
+
+        let (workout_id, workout_exercise_id) = create_workout_exercise(&mut tx).await;
+        let group_id = repository
+            .create_log_group(&mut tx, WorkoutLogGroupReq::new(None))
+            .await
+            .unwrap();
+
+        for (rpe, rir) in [(Some(8.3), None), (Some(11.0), None), (None, Some(0.5))] {
+            let log_req = WorkoutLogReq {
+                workout_id,
+                workout_exercise_id,
+                workout_log_group_id: group_id,
+                exercise_name: "Dummy Ex".to_string(),
+                set_number: 1,
+                rep_number_or_seconds: 5,
+                weight: 100.0,
+                weight_unit: WeightUnit::Kg,
+                assistance_band: None,
+                bodyweight_fraction: 0.0,
+                rpe,
+                rir,
+                description: None,
+                completed_at: None,
+            };
+            let result = repository.create_log(&mut tx, log_req).await;
+            assert!(matches!(result, Err(AppError::Validation(ref e)) if e.contains("half steps")));
+        }
 
         tx.commit().await.unwrap();
     }
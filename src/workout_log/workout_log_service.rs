@@ -1,15 +1,25 @@
+use crate::db::SqliteTx;
 use crate::db::pagination_support::{PaginationParams, PaginationRes};
+use crate::error::AppResult;
+use crate::personal_record::personal_record_dto::RecordSetRes;
+use crate::personal_record::personal_record_repo::PersonalRecordRepo;
+use crate::personal_record::record_detection::detect_records;
 use crate::workout_log::workout_log_dto::{
-    WorkoutLogDetailRes, WorkoutLogGroupFilterReq, WorkoutLogGroupPageRes, WorkoutLogGroupReq,
-    WorkoutLogGroupRes, WorkoutLogReq,
+    ExerciseAverageRpeRes, ExerciseHistoryTarget, RecentSessionRes, WorkoutLogDetailRes,
+    WorkoutLogFilterReq, WorkoutLogGroupEditReq, WorkoutLogGroupFilterReq, WorkoutLogGroupPageRes,
+    WorkoutLogGroupReq, WorkoutLogGroupRes, WorkoutLogGroupUpdateReq, WorkoutLogReq,
+    WorkoutSessionDraftReq, WorkoutSessionDraftRes,
 };
 use crate::workout_log::workout_log_repo::WorkoutLogRepo;
+use chrono::{Datelike, NaiveDate};
 use sqlx::{Pool, Sqlite};
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Clone)]
 pub struct WorkoutLogService {
     pool: Pool<Sqlite>,
     repo: WorkoutLogRepo,
+    record_repo: PersonalRecordRepo,
 }
 
 impl WorkoutLogService {
@@ -17,81 +27,573 @@ impl WorkoutLogService {
         Self {
             pool,
             repo: WorkoutLogRepo::new(),
+            record_repo: PersonalRecordRepo::new(),
         }
     }
 
+    // returns the id of the new log group
     pub async fn create_log_group(
         &self,
         req: WorkoutLogGroupReq,
         logs: Vec<WorkoutLogReq>,
-    ) -> Result<(), String> {
-        let mut conn = match self.pool.begin().await {
-            Ok(conn) => conn,
-            Err(e) => return Err(e.to_string()),
-        };
+        draft_id: Option<u32>,
+    ) -> AppResult<u32> {
+        let mut conn = self.pool.begin().await?;
         let log_group_id = self.repo.create_log_group(&mut conn, req).await?;
 
         for mut log in logs {
             log.workout_log_group_id = log_group_id;
             self.repo.create_log(&mut conn, log).await?;
         }
+        self.record_personal_records(&mut conn, log_group_id, None)
+            .await?;
+
+        // the draft is consumed in the same transaction as the logs it became
+        if let Some(draft_id) = draft_id {
+            self.repo.delete_draft(&mut conn, draft_id).await?;
+        }
+
+        conn.commit().await?;
+        Ok(log_group_id)
+    }
+
+    // corrections to a saved session. its records, and those of every later session of the
+    // same exercises, are detected again since the edit changes what they had to beat
+    pub async fn edit_log_group(
+        &self,
+        id: u32,
+        group_req: WorkoutLogGroupUpdateReq,
+        edit_req: WorkoutLogGroupEditReq,
+    ) -> AppResult<()> {
+        let mut conn = self.pool.begin().await?;
+        let previous_date = self.repo.get_one_log_group(&mut *conn, id).await?.date;
+        let recheck_from = previous_date.min(group_req.date);
+        let mut edited = self.session_exercises(&mut conn, id).await?;
+
+        self.repo.update_log_group(&mut conn, id, group_req).await?;
+        for log_id in edit_req.deleted_log_ids {
+            self.repo.delete_log_in_group(&mut conn, id, log_id).await?;
+        }
+        for (log_id, log_req) in edit_req.updated_logs {
+            self.repo.update_log(&mut conn, id, log_id, log_req).await?;
+        }
+        for mut log in edit_req.new_logs {
+            log.workout_log_group_id = id;
+            self.repo.create_log(&mut conn, log).await?;
+        }
+        edited.extend(self.session_exercises(&mut conn, id).await?);
+        self.recheck_records_from(&mut conn, recheck_from, id, &edited)
+            .await?;
+
+        conn.commit().await?;
+        Ok(())
+    }
 
-        conn.commit().await.unwrap();
+    // in date order from the given session on, each session's records build on the ones before it
+    async fn recheck_records_from(
+        &self,
+        tx: &mut SqliteTx<'_>,
+        date: NaiveDate,
+        log_group_id: u32,
+        exercises: &[ExerciseHistoryTarget],
+    ) -> AppResult<()> {
+        let log_group_ids = self
+            .repo
+            .get_log_group_ids_from(&mut **tx, date, log_group_id)
+            .await?;
+        for log_group_id in log_group_ids {
+            self.record_personal_records(tx, log_group_id, Some(exercises))
+                .await?;
+        }
         Ok(())
     }
 
-    pub async fn delete_log_group(&self, id: u32) -> Result<(), String> {
-        let mut conn = match self.pool.begin().await {
-            Ok(conn) => conn,
-            Err(e) => return Err(e.to_string()),
+    async fn session_exercises(
+        &self,
+        tx: &mut SqliteTx<'_>,
+        log_group_id: u32,
+    ) -> AppResult<Vec<ExerciseHistoryTarget>> {
+        let sets = self
+            .record_repo
+            .get_record_sets_by_log_group_id(&mut **tx, log_group_id)
+            .await?;
+        Ok(sets.iter().map(RecordSetRes::target).collect())
+    }
+
+    // each exercise in the session is checked against its sets from the sessions dated before it,
+    // only the given exercises when there are some; records found before are replaced.
+    // exercises without a library link get none, there is no telling whether they log seconds
+    async fn record_personal_records(
+        &self,
+        tx: &mut SqliteTx<'_>,
+        log_group_id: u32,
+        exercises: Option<&[ExerciseHistoryTarget]>,
+    ) -> AppResult<()> {
+        let sets = self
+            .record_repo
+            .get_record_sets_by_log_group_id(&mut **tx, log_group_id)
+            .await?;
+
+        let mut by_exercise: BTreeMap<(Option<u32>, String), Vec<RecordSetRes>> = BTreeMap::new();
+        for set in sets {
+            by_exercise
+                .entry((set.exercise_library_id, set.exercise_name.to_lowercase()))
+                .or_default()
+                .push(set);
+        }
+
+        for session_sets in by_exercise.into_values() {
+            let target = session_sets[0].target();
+            if let Some(exercises) = exercises
+                && !exercises.iter().any(|exercise| exercise.matches(&target))
+            {
+                continue;
+            }
+
+            let log_ids: Vec<u32> = session_sets.iter().map(|set| set.workout_log_id).collect();
+            self.record_repo
+                .delete_records_by_workout_log_ids(tx, &log_ids)
+                .await?;
+            if target.exercise_library_id.is_none() {
+                continue;
+            }
+            let history = self
+                .record_repo
+                .get_earlier_record_sets(&mut **tx, &target, log_group_id)
+                .await?;
+            for record in detect_records(&session_sets, &history) {
+                self.record_repo.create_record(tx, record).await?;
+            }
+        }
+        Ok(())
+    }
+
+    // creates the draft on first save, returns its id
+    pub async fn save_draft(
+        &self,
+        draft_id: Option<u32>,
+        req: WorkoutSessionDraftReq,
+    ) -> AppResult<u32> {
+        let mut conn = self.pool.begin().await?;
+        let id = match draft_id {
+            Some(id) => {
+                self.repo.update_draft(&mut conn, id, req).await?;
+                id
+            }
+            None => self.repo.create_draft(&mut conn, req).await?,
         };
+        conn.commit().await?;
+        Ok(id)
+    }
+
+    pub async fn discard_draft(&self, id: u32) -> AppResult<()> {
+        let mut conn = self.pool.begin().await?;
+        self.repo.delete_draft(&mut conn, id).await?;
+        conn.commit().await?;
+        Ok(())
+    }
+
+    pub async fn get_unfinished_drafts(&self) -> AppResult<Vec<WorkoutSessionDraftRes>> {
+        self.repo
+            .get_all_drafts(&mut *self.pool.acquire().await?)
+            .await
+    }
+
+    // the session's sets go with it, and later sessions of its exercises no longer had to beat them
+    pub async fn delete_log_group(&self, id: u32) -> AppResult<()> {
+        let mut conn = self.pool.begin().await?;
+        let date = self.repo.get_one_log_group(&mut *conn, id).await?.date;
+        let deleted = self.session_exercises(&mut conn, id).await?;
+        let logs = self
+            .repo
+            .get_logs_by_workout_log_group_id(&mut *conn, id)
+            .await?;
+        for log in logs {
+            self.repo.delete_log_in_group(&mut conn, id, log.id).await?;
+        }
         self.repo.delete_log_group(&mut conn, id).await?;
-        conn.commit().await.unwrap();
+        self.recheck_records_from(&mut conn, date, id, &deleted)
+            .await?;
+        conn.commit().await?;
         Ok(())
     }
 
-    pub async fn delete_one_log_entry(&self, id: u32) -> Result<(), String> {
-        let mut conn = match self.pool.begin().await {
-            Ok(conn) => conn,
-            Err(e) => return Err(e.to_string()),
-        };
+    pub async fn delete_one_log_entry(&self, id: u32) -> AppResult<()> {
+        let mut conn = self.pool.begin().await?;
+        let log = self.repo.get_one_log(&mut *conn, id).await?;
         self.repo.delete_log(&mut conn, id).await?;
-        conn.commit().await.unwrap();
+        self.recheck_records_from(
+            &mut conn,
+            log.workout_date,
+            log.workout_log_group_id,
+            &[log.history_target()],
+        )
+        .await?;
+        conn.commit().await?;
         Ok(())
     }
 
-    pub async fn get_log_group(&self, id: u32) -> Result<WorkoutLogGroupRes, String> {
-        Ok(self
-            .repo
-            .get_one_log_group(&mut *self.pool.acquire().await.unwrap(), id)
-            .await?)
+    pub async fn get_log_group(&self, id: u32) -> AppResult<WorkoutLogGroupRes> {
+        self.repo
+            .get_one_log_group(&mut *self.pool.acquire().await?, id)
+            .await
     }
 
     pub async fn get_logs_by_workout_log_group_id(
         &self,
         workout_log_group_id: u32,
-    ) -> Result<Vec<WorkoutLogDetailRes>, String> {
-        Ok(self
-            .repo
+    ) -> AppResult<Vec<WorkoutLogDetailRes>> {
+        self.repo
             .get_logs_by_workout_log_group_id(
-                &mut *self.pool.acquire().await.unwrap(),
+                &mut *self.pool.acquire().await?,
                 workout_log_group_id,
             )
-            .await?)
+            .await
+    }
+
+    pub async fn get_last_logs_by_workout_exercise_ids(
+        &self,
+        workout_exercise_ids: Vec<u32>,
+    ) -> AppResult<HashMap<u32, Vec<WorkoutLogDetailRes>>> {
+        let mut conn = self.pool.acquire().await?;
+        let mut res = HashMap::new();
+        for workout_exercise_id in workout_exercise_ids {
+            let logs = self
+                .repo
+                .get_last_logs_by_workout_exercise_id(&mut *conn, workout_exercise_id)
+                .await?;
+            if !logs.is_empty() {
+                res.insert(workout_exercise_id, logs);
+            }
+        }
+        Ok(res)
+    }
+
+    pub async fn get_average_rpe_by_exercise(
+        &self,
+        date_gte: Option<NaiveDate>,
+        date_lte: Option<NaiveDate>,
+    ) -> AppResult<Vec<ExerciseAverageRpeRes>> {
+        self.repo
+            .get_average_rpe_by_exercise(&mut *self.pool.acquire().await?, date_gte, date_lte)
+            .await
+    }
+
+    pub async fn get_recent_sessions(&self, limit: u32) -> AppResult<Vec<RecentSessionRes>> {
+        self.repo
+            .get_recent_sessions(&mut *self.pool.acquire().await?, limit)
+            .await
+    }
+
+    // sessions logged from monday to sunday of the week containing today
+    pub async fn count_sessions_in_week(&self, today: NaiveDate) -> AppResult<u32> {
+        let monday = today - chrono::Days::new(today.weekday().num_days_from_monday() as u64);
+        let sunday = monday + chrono::Days::new(6);
+        self.repo
+            .count_log_groups(&mut *self.pool.acquire().await?, monday, sunday)
+            .await
     }
 
     pub async fn paginate_log_groups(
         &self,
         pagination_filters: Option<WorkoutLogGroupFilterReq>,
         pagination_params: PaginationParams,
-    ) -> Result<PaginationRes<WorkoutLogGroupPageRes>, String> {
-        Ok(self
-            .repo
+    ) -> AppResult<PaginationRes<WorkoutLogGroupPageRes>> {
+        self.repo
             .paginate_workout_log_groups(
-                &mut *self.pool.acquire().await.unwrap(),
+                &mut *self.pool.acquire().await?,
                 pagination_filters,
                 pagination_params,
             )
-            .await?)
+            .await
+    }
+
+    pub async fn paginate_logs(
+        &self,
+        pagination_filters: Option<WorkoutLogFilterReq>,
+        pagination_params: PaginationParams,
+    ) -> AppResult<PaginationRes<WorkoutLogDetailRes>> {
+        self.repo
+            .paginate_workout_logs(
+                &mut *self.pool.acquire().await?,
+                pagination_filters,
+                pagination_params,
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::{IN_MEMORY_DB_URL, init_db};
+    use crate::enums::{RecordType, WeightUnit};
+    use crate::error::AppError;
+    use crate::personal_record::personal_record_dto::PersonalRecordRes;
+    use crate::personal_record::personal_record_repo::PersonalRecordRepo;
+    use crate::workout::workout_dto::{
+        WorkoutExerciseReq, WorkoutReq, default_exercise_req, default_workout_req,
+    };
+    use crate::workout::workout_service::WorkoutService;
+    use crate::workout_log::workout_log_dto::{
+        WorkoutLogGroupEditReq, WorkoutLogGroupReq, WorkoutLogGroupUpdateReq, WorkoutLogReq,
+        WorkoutLogUpdateReq,
+    };
+    use crate::workout_log::workout_log_repo::WorkoutLogRepo;
+    use crate::workout_log::workout_log_service::WorkoutLogService;
+    use chrono::NaiveDate;
+    use sqlx::SqlitePool;
+
+    // a fresh database with one workout holding one squat from the library, all with id 1
+    async fn setup() -> (SqlitePool, WorkoutLogService) {
+        let pool = init_db(IN_MEMORY_DB_URL).await.unwrap();
+        sqlx::query(
+            r#"INSERT INTO exercise_library (
+                name, dynamic_or_static, upper_or_lower, compound_or_isolation
+            ) VALUES ('Squat', 'Dynamic', 'Lower', 'Compound')"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        WorkoutService::new(pool.clone())
+            .create(
+                WorkoutReq {
+                    name: "Leg Day".to_string(),
+                    ..default_workout_req()
+                },
+                vec![WorkoutExerciseReq {
+                    exercise_library_id: Some(1),
+                    name: "Squat".to_string(),
+                    code: "A1".to_string(),
+                    ..default_exercise_req(0, WeightUnit::Kg, 60)
+                }],
+            )
+            .await
+            .unwrap();
+        (pool.clone(), WorkoutLogService::new(pool))
+    }
+
+    fn squat(weight: f64) -> WorkoutLogReq {
+        WorkoutLogReq {
+            workout_id: 1,
+            workout_exercise_id: 1,
+            workout_log_group_id: 0,
+            exercise_name: "Squat".to_string(),
+            set_number: 1,
+            rep_number_or_seconds: 5,
+            weight,
+            weight_unit: WeightUnit::Kg,
+            assistance_band: None,
+            bodyweight_fraction: 0.0,
+            rpe: None,
+            rir: None,
+            description: None,
+            completed_at: None,
+        }
+    }
+
+    async fn log_session(service: &WorkoutLogService, day: u32, weight: f64) -> u32 {
+        service
+            .create_log_group(
+                WorkoutLogGroupReq {
+                    date: NaiveDate::from_ymd_opt(2024, 3, day).unwrap(),
+                    notes: None,
+                    started_at: None,
+                    finished_at: None,
+                },
+                vec![squat(weight)],
+                None,
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_records_are_checked_against_earlier_dates() {
+        let (pool, service) = setup().await;
+
+        log_session(&service, 8, 100.0).await;
+        // backfilled afterwards, nothing was done before it so it sets no record
+        log_session(&service, 1, 105.0).await;
+        let latest = log_session(&service, 15, 110.0).await;
+
+        let records = PersonalRecordRepo::new()
+            .get_all_records(&pool)
+            .await
+            .unwrap();
+        assert!(
+            records
+                .iter()
+                .all(|record| record.workout_log_group_id == latest)
+        );
+        let heaviest = records
+            .iter()
+            .find(|record| record.record_type == RecordType::HeaviestWeight)
+            .expect("110 kg beats both earlier sessions");
+        assert_eq!(heaviest.value, 110.0);
+        assert_eq!(heaviest.previous_value, 105.0);
+    }
+
+    #[tokio::test]
+    async fn test_unlinked_exercises_set_no_records() {
+        let (pool, service) = setup().await;
+        sqlx::query("UPDATE workout_exercises SET exercise_library_id = NULL")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // could be a weighted hold, the seconds would count as reps
+        log_session(&service, 1, 100.0).await;
+        log_session(&service, 8, 110.0).await;
+
+        let records = PersonalRecordRepo::new()
+            .get_all_records(&pool)
+            .await
+            .unwrap();
+        assert!(records.is_empty());
+    }
+
+    fn fix(weight: f64) -> WorkoutLogUpdateReq {
+        WorkoutLogUpdateReq {
+            set_number: 1,
+            rep_number_or_seconds: 5,
+            weight,
+            assistance_band: None,
+            rpe: None,
+            rir: None,
+            description: None,
+        }
+    }
+
+    async fn log_id(pool: &SqlitePool, log_group_id: u32) -> u32 {
+        WorkoutLogRepo::new()
+            .get_logs_by_workout_log_group_id(pool, log_group_id)
+            .await
+            .unwrap()[0]
+            .id
+    }
+
+    #[tokio::test]
+    async fn test_edit_rechecks_later_records() {
+        let (pool, service) = setup().await;
+        let first = log_session(&service, 1, 100.0).await;
+        let second = log_session(&service, 8, 110.0).await;
+        let third = log_session(&service, 15, 115.0).await;
+        let record_repo = PersonalRecordRepo::new();
+        let heaviest = |records: &[PersonalRecordRes], log_group_id: u32| {
+            records
+                .iter()
+                .find(|r| {
+                    r.workout_log_group_id == log_group_id
+                        && r.record_type == RecordType::HeaviestWeight
+                })
+                .map(|r| r.previous_value)
+        };
+        let records = record_repo.get_all_records(&pool).await.unwrap();
+        assert_eq!(heaviest(&records, second), Some(100.0));
+        assert_eq!(heaviest(&records, third), Some(110.0));
+
+        // the first session really was 120, neither later one beat it
+        service
+            .edit_log_group(
+                first,
+                WorkoutLogGroupUpdateReq {
+                    date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+                    notes: None,
+                },
+                WorkoutLogGroupEditReq {
+                    updated_logs: vec![(log_id(&pool, first).await, fix(120.0))],
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert!(record_repo.get_all_records(&pool).await.unwrap().is_empty());
+
+        // moving it after the third session makes the later ones records again
+        service
+            .edit_log_group(
+                first,
+                WorkoutLogGroupUpdateReq {
+                    date: NaiveDate::from_ymd_opt(2024, 3, 20).unwrap(),
+                    notes: None,
+                },
+                WorkoutLogGroupEditReq::default(),
+            )
+            .await
+            .unwrap();
+        let records = record_repo.get_all_records(&pool).await.unwrap();
+        assert_eq!(heaviest(&records, third), Some(110.0));
+        assert_eq!(heaviest(&records, first), Some(115.0));
+        assert_eq!(heaviest(&records, second), None);
+    }
+
+    #[tokio::test]
+    async fn test_deletes_recheck_later_records() {
+        let (pool, service) = setup().await;
+        let first = log_session(&service, 1, 100.0).await;
+        let second = log_session(&service, 8, 120.0).await;
+        let third = log_session(&service, 15, 110.0).await;
+        let record_repo = PersonalRecordRepo::new();
+        let records = record_repo.get_all_records(&pool).await.unwrap();
+        assert!(records.iter().all(|r| r.workout_log_group_id == second));
+
+        // without the 120, the third session beat everything before it
+        service.delete_log_group(second).await.unwrap();
+        let records = record_repo.get_all_records(&pool).await.unwrap();
+        assert!(!records.is_empty());
+        assert!(records.iter().all(|r| r.workout_log_group_id == third));
+        let heaviest = records
+            .iter()
+            .find(|r| r.record_type == RecordType::HeaviestWeight)
+            .unwrap();
+        assert_eq!(heaviest.previous_value, 100.0);
+
+        // and with no set before it at all, there was nothing to beat
+        service
+            .delete_one_log_entry(log_id(&pool, first).await)
+            .await
+            .unwrap();
+        assert!(record_repo.get_all_records(&pool).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_edit_is_one_transaction_scoped_to_the_session() {
+        let (pool, service) = setup().await;
+        let first = log_session(&service, 1, 100.0).await;
+        let second = log_session(&service, 8, 110.0).await;
+        let first_log_id = log_id(&pool, first).await;
+        let second_log_id = log_id(&pool, second).await;
+
+        // the other session's set can't be edited through this one, nothing else is kept
+        let res = service
+            .edit_log_group(
+                second,
+                WorkoutLogGroupUpdateReq {
+                    date: NaiveDate::from_ymd_opt(2024, 3, 9).unwrap(),
+                    notes: Some("moved".to_string()),
+                },
+                WorkoutLogGroupEditReq {
+                    deleted_log_ids: vec![second_log_id],
+                    updated_logs: vec![(first_log_id, fix(90.0))],
+                    ..Default::default()
+                },
+            )
+            .await;
+        assert_eq!(res, Err(AppError::NotFound("Workout log")));
+
+        let group = service.get_log_group(second).await.unwrap();
+        assert_eq!(group.date, NaiveDate::from_ymd_opt(2024, 3, 8).unwrap());
+        assert_eq!(group.notes, None);
+        assert_eq!(log_id(&pool, second).await, second_log_id);
+        let first_logs = WorkoutLogRepo::new()
+            .get_logs_by_workout_log_group_id(&pool, first)
+            .await
+            .unwrap();
+        assert_eq!(first_logs[0].weight, 100.0);
+        let records = PersonalRecordRepo::new()
+            .get_all_records(&pool)
+            .await
+            .unwrap();
+        assert!(records.iter().any(|r| r.workout_log_group_id == second));
     }
 }
@@ -1,20 +1,40 @@
-use crate::client::app_utils::{CommonUiState, render_pagination};
+use crate::client::app::PageAction;
+use crate::client::app_utils::{CommonUiState, effort_combo, render_pagination};
 use crate::db::pagination_support::{PaginationRes, PaginationState};
+use crate::enums::WeightUnit;
+use crate::error::AppError;
+use crate::personal_record::personal_record_dto::PersonalRecordRes;
+use crate::personal_record::personal_record_service::PersonalRecordService;
+use crate::settings::settings_dto::SettingsRes;
+use crate::workout::session_summary::format_duration;
+use crate::workout::workouts_page::render_band_picker;
+use crate::workout_log::session_timing::{actual_rests, session_density};
 use crate::workout_log::workout_log_dto::{
-    WorkoutLogDetailRes, WorkoutLogGroupFilterReq, WorkoutLogGroupPageRes, WorkoutLogGroupRes,
+    WorkoutLogDetailRes, WorkoutLogGroupEditReq, WorkoutLogGroupFilterReq, WorkoutLogGroupPageRes,
+    WorkoutLogGroupRes, WorkoutLogGroupUpdateReq, WorkoutLogReq, WorkoutLogUpdateReq,
 };
 use crate::workout_log::workout_log_service::WorkoutLogService;
+use chrono::TimeDelta;
 use eframe::egui;
 use sqlx::{Pool, Sqlite};
+use std::collections::HashMap;
 use std::sync::mpsc::{Receiver, Sender, channel};
 
 pub struct WorkoutLogsPage {
     service: WorkoutLogService,
+    record_service: PersonalRecordService,
     state: WorkoutLogsPageState,
     // Data
     list_items: Vec<WorkoutLogGroupPageRes>,
     current_log_group: Option<WorkoutLogGroupRes>,
     current_logs: Vec<WorkoutLogDetailRes>,
+    current_records: HashMap<u32, Vec<PersonalRecordRes>>, // by workout log id
+    current_rests: HashMap<u32, TimeDelta>,                // by workout log id
+    // Edit mode
+    edit_form: LogGroupEditForm,
+    // Display unit for weights
+    weight_unit: WeightUnit,
+    bodyweight_kg: Option<f64>,
     // Async Communication
     receiver: Receiver<WorkoutLogsPageMsg>,
     sender: Sender<WorkoutLogsPageMsg>,
@@ -32,8 +52,91 @@ pub struct WorkoutLogsPage {
 
 pub enum WorkoutLogsPageMsg {
     ListLoaded(PaginationRes<WorkoutLogGroupPageRes>),
-    DetailLoaded(WorkoutLogGroupRes, Vec<WorkoutLogDetailRes>),
-    Error(String),
+    DetailLoaded(
+        WorkoutLogGroupRes,
+        Vec<WorkoutLogDetailRes>,
+        Vec<PersonalRecordRes>,
+    ),
+    Updated(u32),
+    Error(AppError),
+}
+
+// a set in the edit form, new sets have no id until saved
+struct EditableSet {
+    id: Option<u32>,
+    log: WorkoutLogDetailRes,
+}
+
+#[derive(Default)]
+struct LogGroupEditForm {
+    date_str: String,
+    notes: String,
+    sets: Vec<EditableSet>,
+    deleted_log_ids: Vec<u32>,
+}
+
+impl LogGroupEditForm {
+    fn new(log_group: &WorkoutLogGroupRes, logs: &[WorkoutLogDetailRes]) -> Self {
+        Self {
+            date_str: log_group.date.format("%Y-%m-%d").to_string(),
+            notes: log_group.notes.clone().unwrap_or_default(),
+            sets: logs
+                .iter()
+                .map(|log| EditableSet {
+                    id: Some(log.id),
+                    log: log.clone(),
+                })
+                .collect(),
+            deleted_log_ids: Vec::new(),
+        }
+    }
+
+    fn to_reqs(&self) -> Option<(WorkoutLogGroupUpdateReq, WorkoutLogGroupEditReq)> {
+        let date = chrono::NaiveDate::parse_from_str(&self.date_str, "%Y-%m-%d").ok()?;
+        let group_req = WorkoutLogGroupUpdateReq {
+            date,
+            notes: (!self.notes.is_empty()).then(|| self.notes.clone()),
+        };
+
+        let mut edit_req = WorkoutLogGroupEditReq {
+            deleted_log_ids: self.deleted_log_ids.clone(),
+            ..Default::default()
+        };
+        for set in &self.sets {
+            let log = &set.log;
+            match set.id {
+                Some(id) => edit_req.updated_logs.push((
+                    id,
+                    WorkoutLogUpdateReq {
+                        set_number: log.set_number,
+                        rep_number_or_seconds: log.rep_number_or_seconds,
+                        weight: log.weight,
+                        assistance_band: log.assistance_band,
+                        rpe: log.rpe,
+                        rir: log.rir,
+                        description: log.description.clone(),
+                    },
+                )),
+                None => edit_req.new_logs.push(WorkoutLogReq {
+                    workout_id: log.workout_id,
+                    workout_exercise_id: log.workout_exercise_id,
+                    workout_log_group_id: log.workout_log_group_id,
+                    exercise_name: log.workout_exercise_name.clone(),
+                    set_number: log.set_number,
+                    rep_number_or_seconds: log.rep_number_or_seconds,
+                    weight: log.weight,
+                    weight_unit: log.weight_unit,
+                    assistance_band: log.assistance_band,
+                    bodyweight_fraction: log.bodyweight_fraction,
+                    rpe: log.rpe,
+                    rir: log.rir,
+                    description: log.description.clone(),
+                    completed_at: None, // added after the fact, not timed
+                }),
+            }
+        }
+        Some((group_req, edit_req))
+    }
 }
 
 impl WorkoutLogsPage {
@@ -41,10 +144,16 @@ impl WorkoutLogsPage {
         let (sender, receiver) = channel();
         Self {
             service: WorkoutLogService::new(pool.clone()),
+            record_service: PersonalRecordService::new(pool.clone()),
             state: WorkoutLogsPageState::DetailsClosed,
             list_items: Vec::new(),
             current_log_group: None,
             current_logs: Vec::new(),
+            current_records: HashMap::new(),
+            current_rests: HashMap::new(),
+            edit_form: LogGroupEditForm::default(),
+            weight_unit: WeightUnit::default(),
+            bodyweight_kg: None,
             pagination_filters: WorkoutLogGroupFilterReq::default(),
             filter_date_gte_str: String::new(),
             filter_date_lte_str: String::new(),
@@ -57,13 +166,27 @@ impl WorkoutLogsPage {
     }
 }
 
+impl WorkoutLogsPage {
+    pub fn apply_settings(&mut self, settings: &SettingsRes) {
+        self.weight_unit = settings.weight_unit;
+        self.bodyweight_kg = settings.bodyweight_kg;
+        if self
+            .pagination_state
+            .apply_default_limit(settings.pagination_limit)
+        {
+            self.trigger_list_refresh();
+        }
+    }
+}
+
 pub enum WorkoutLogsPageState {
     DetailsClosed,
     DetailsOpenView,
+    DetailsEditView,
 }
 
 impl WorkoutLogsPage {
-    fn handle_async_messages(&mut self) {
+    fn handle_async_messages(&mut self, ctx: &egui::Context) {
         while let Ok(msg) = self.receiver.try_recv() {
             match msg {
                 WorkoutLogsPageMsg::ListLoaded(res) => {
@@ -72,14 +195,28 @@ impl WorkoutLogsPage {
                     self.pagination_state.prev_cursor = res.prev_cursor;
                     self.common_ui_state.set_as_not_loading();
                 }
-                WorkoutLogsPageMsg::DetailLoaded(log_group, logs) => {
+                WorkoutLogsPageMsg::DetailLoaded(log_group, logs, records) => {
                     self.current_log_group = Some(log_group);
+                    self.current_rests = actual_rests(&logs);
                     self.current_logs = logs;
+                    self.current_records.clear();
+                    for record in records {
+                        self.current_records
+                            .entry(record.workout_log_id)
+                            .or_default()
+                            .push(record);
+                    }
                     self.state = WorkoutLogsPageState::DetailsOpenView;
                     self.common_ui_state.set_as_not_loading();
                 }
+                WorkoutLogsPageMsg::Updated(id) => {
+                    self.common_ui_state.show_success("Saved successfully");
+                    // reload the details, which also leaves edit mode
+                    self.fetch_detail(ctx, id);
+                    self.trigger_list_refresh();
+                }
                 WorkoutLogsPageMsg::Error(err) => {
-                    self.common_ui_state.show_error(&err);
+                    self.common_ui_state.show_app_error(&err);
                     self.common_ui_state.set_as_not_loading();
                 }
             }
@@ -121,18 +258,20 @@ impl WorkoutLogsPage {
         self.common_ui_state.set_as_loading();
 
         let service = self.service.clone();
+        let record_service = self.record_service.clone();
         let sender = self.sender.clone();
         let ctx = ctx.clone();
 
         tokio::spawn(async move {
             let log_group_result = service.get_log_group(id).await;
             let logs_result = service.get_logs_by_workout_log_group_id(id).await;
+            let records_result = record_service.get_records_by_log_group_id(id).await;
 
-            match (log_group_result, logs_result) {
-                (Ok(log_group), Ok(logs)) => {
-                    let _ = sender.send(WorkoutLogsPageMsg::DetailLoaded(log_group, logs));
+            match (log_group_result, logs_result, records_result) {
+                (Ok(log_group), Ok(logs), Ok(records)) => {
+                    let _ = sender.send(WorkoutLogsPageMsg::DetailLoaded(log_group, logs, records));
                 }
-                (Err(e), _) | (_, Err(e)) => {
+                (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
                     let _ = sender.send(WorkoutLogsPageMsg::Error(e));
                 }
             }
@@ -140,6 +279,10 @@ impl WorkoutLogsPage {
         });
     }
 
+    pub fn open_details_view(&mut self, ctx: &egui::Context, id: u32) {
+        self.fetch_detail(ctx, id);
+    }
+
     fn render_filters(&mut self, ui: &mut egui::Ui) {
         let mut filters_changed = false;
 
@@ -192,15 +335,29 @@ impl WorkoutLogsPage {
         }
     }
 
-    fn render_details_open_view(&mut self, ui: &mut egui::Ui) {
-        if ui.button("← Back to List").clicked() {
+    fn render_details_open_view(&mut self, ui: &mut egui::Ui) -> PageAction {
+        let mut page_action = PageAction::None;
+        let mut back = false;
+        ui.horizontal(|ui| {
+            back = ui.button("← Back to List").clicked();
+            if let Some(log_group) = &self.current_log_group
+                && ui.button("Edit").clicked()
+            {
+                self.edit_form = LogGroupEditForm::new(log_group, &self.current_logs);
+                self.state = WorkoutLogsPageState::DetailsEditView;
+            }
+        });
+        if back {
             self.state = WorkoutLogsPageState::DetailsClosed;
             self.current_log_group = None;
             self.current_logs.clear();
-            return;
+            self.current_records.clear();
+            self.current_rests.clear();
+            return page_action;
         }
 
         ui.separator();
+        self.common_ui_state.show_toasts(ui);
 
         if let Some(log_group) = &self.current_log_group {
             // Header info
@@ -208,6 +365,22 @@ impl WorkoutLogsPage {
             if let Some(notes) = &log_group.notes {
                 ui.label(format!("Notes: {}", notes));
             }
+            if let Some(duration) = log_group.duration() {
+                let density = session_density(
+                    &self.current_logs,
+                    duration,
+                    self.weight_unit,
+                    self.bodyweight_kg,
+                )
+                .map(|density| format!("{}/min", self.weight_unit.format(density)))
+                .unwrap_or("-".to_string());
+                ui.label(format!(
+                    "Duration: {} | Density: {}",
+                    format_duration(duration),
+                    density
+                ))
+                .on_hover_text("Load times reps over the whole session, per minute");
+            }
             ui.separator();
 
             // Group logs by exercise name
@@ -228,15 +401,30 @@ impl WorkoutLogsPage {
                             .show(ui, |ui| {
                                 ui.label("Set");
                                 ui.label("Reps/Secs");
-                                ui.label("Weight");
+                                ui.label("Load");
+                                ui.label("RPE");
+                                ui.label("RIR");
+                                ui.label("Rest (Planned)");
                                 ui.label("Description");
+                                ui.label("PR");
+                                ui.label("");
                                 ui.end_row();
 
                                 for log in logs {
                                     ui.label(format!("{}", log.set_number));
                                     ui.label(format!("{}", log.rep_number_or_seconds));
-                                    ui.label(format!("{} kgs", log.weight));
+                                    ui.label(
+                                        log.load().describe(self.weight_unit, self.bodyweight_kg),
+                                    );
+                                    ui.label(log.rpe.map(|v| v.to_string()).unwrap_or("-".into()));
+                                    ui.label(log.rir.map(|v| v.to_string()).unwrap_or("-".into()));
+                                    self.render_rest(ui, log);
                                     ui.label(log.description.as_deref().unwrap_or("-"));
+                                    self.render_records(ui, log.id);
+                                    if ui.button("History").clicked() {
+                                        page_action =
+                                            PageAction::GoToExerciseHistory(log.history_target());
+                                    }
                                     ui.end_row();
                                 }
                             });
@@ -244,6 +432,216 @@ impl WorkoutLogsPage {
                 }
             });
         }
+
+        page_action
+    }
+
+    fn render_details_edit_view(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        let Some(log_group_id) = self.current_log_group.as_ref().map(|g| g.id) else {
+            self.state = WorkoutLogsPageState::DetailsClosed;
+            return;
+        };
+
+        ui.heading("Edit Workout Log");
+        ui.separator();
+        self.common_ui_state.show_toasts(ui);
+
+        egui::Grid::new("log_group_edit_grid").show(ui, |ui| {
+            ui.label("Date:");
+            ui.text_edit_singleline(&mut self.edit_form.date_str);
+            ui.end_row();
+            ui.label("Notes:");
+            ui.text_edit_multiline(&mut self.edit_form.notes);
+            ui.end_row();
+        });
+        ui.separator();
+
+        let reqs = self.edit_form.to_reqs();
+        let has_sets = !self.edit_form.sets.is_empty();
+        ui.horizontal(|ui| {
+            let save = ui.add_enabled(
+                reqs.is_some() && has_sets && !self.common_ui_state.loading,
+                egui::Button::new("Save"),
+            );
+            let save = if reqs.is_none() {
+                save.on_disabled_hover_text("Date must be YYYY-MM-DD")
+            } else {
+                save.on_disabled_hover_text("Keep at least one set")
+            };
+            if save.clicked()
+                && let Some((group_req, edit_req)) = reqs
+            {
+                self.save_edits(ctx, log_group_id, group_req, edit_req);
+            }
+            if ui.button("Cancel").clicked() {
+                self.state = WorkoutLogsPageState::DetailsOpenView;
+            }
+            if self.common_ui_state.loading {
+                ui.spinner();
+            }
+        });
+        ui.separator();
+
+        // set indexes grouped by exercise name, like the details view
+        let mut grouped_sets: std::collections::BTreeMap<String, Vec<usize>> =
+            std::collections::BTreeMap::new();
+        for (idx, set) in self.edit_form.sets.iter().enumerate() {
+            grouped_sets
+                .entry(set.log.workout_exercise_name.clone())
+                .or_default()
+                .push(idx);
+        }
+
+        let mut removed: Option<usize> = None;
+        let mut added_after: Option<usize> = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (exercise_name, indexes) in grouped_sets {
+                let show_band = indexes.iter().any(|idx| {
+                    let log = &self.edit_form.sets[*idx].log;
+                    log.bodyweight_fraction > 0.0 || log.assistance_band.is_some()
+                });
+                ui.collapsing(&exercise_name, |ui| {
+                    egui::Grid::new(format!("log_edit_grid_{}", exercise_name))
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label("Set");
+                            ui.label("Reps/Secs");
+                            ui.label("Weight");
+                            if show_band {
+                                ui.label("Band");
+                            }
+                            ui.label("RPE");
+                            ui.label("RIR");
+                            ui.label("Description");
+                            ui.label("");
+                            ui.end_row();
+
+                            for idx in &indexes {
+                                let log = &mut self.edit_form.sets[*idx].log;
+                                ui.add(
+                                    egui::DragValue::new(&mut log.set_number)
+                                        .speed(0.1)
+                                        .range(1..=u8::MAX),
+                                );
+                                ui.add(
+                                    egui::DragValue::new(&mut log.rep_number_or_seconds).speed(0.1),
+                                );
+                                ui.add(
+                                    egui::DragValue::new(&mut log.weight)
+                                        .speed(0.25)
+                                        .range(-500.0..=1000.0)
+                                        .max_decimals(2)
+                                        .suffix(format!(" {}", log.weight_unit)),
+                                );
+                                if show_band {
+                                    render_band_picker(
+                                        ui,
+                                        &format!("edit_band_{}", idx),
+                                        &mut log.assistance_band,
+                                    );
+                                }
+                                effort_combo(ui, &format!("edit_rpe_{}", idx), &mut log.rpe);
+                                effort_combo(ui, &format!("edit_rir_{}", idx), &mut log.rir);
+                                let mut description = log.description.clone().unwrap_or_default();
+                                if ui.text_edit_singleline(&mut description).changed() {
+                                    log.description =
+                                        (!description.is_empty()).then_some(description);
+                                }
+                                if ui.button("Remove").clicked() {
+                                    removed = Some(*idx);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    if ui.button("+ Add Set").clicked() {
+                        added_after = indexes.last().copied();
+                    }
+                });
+            }
+        });
+
+        if let Some(idx) = removed {
+            let set = self.edit_form.sets.remove(idx);
+            if let Some(id) = set.id {
+                self.edit_form.deleted_log_ids.push(id);
+            }
+        }
+        // a forgotten set starts as a copy of the exercise's last one
+        if let Some(idx) = added_after {
+            let mut log = self.edit_form.sets[idx].log.clone();
+            log.set_number = log.set_number.saturating_add(1);
+            log.completed_at = None;
+            self.edit_form
+                .sets
+                .insert(idx + 1, EditableSet { id: None, log });
+        }
+    }
+
+    fn save_edits(
+        &mut self,
+        ctx: &egui::Context,
+        id: u32,
+        group_req: WorkoutLogGroupUpdateReq,
+        edit_req: WorkoutLogGroupEditReq,
+    ) {
+        self.common_ui_state.set_as_loading();
+
+        let service = self.service.clone();
+        let sender = self.sender.clone();
+        let ctx = ctx.clone();
+
+        tokio::spawn(async move {
+            match service.edit_log_group(id, group_req, edit_req).await {
+                Ok(()) => {
+                    let _ = sender.send(WorkoutLogsPageMsg::Updated(id));
+                }
+                Err(e) => {
+                    let _ = sender.send(WorkoutLogsPageMsg::Error(e));
+                }
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    // the rest taken after the set, next to what the workout planned
+    fn render_rest(&self, ui: &mut egui::Ui, log: &WorkoutLogDetailRes) {
+        let planned = TimeDelta::seconds(log.rest_period_seconds as i64);
+        let Some(rest) = self.current_rests.get(&log.id) else {
+            ui.label(format!("- ({})", format_duration(planned)));
+            return;
+        };
+        let text = format!("{} ({})", format_duration(*rest), format_duration(planned));
+        if *rest > planned {
+            ui.label(egui::RichText::new(text).color(egui::Color32::ORANGE))
+                .on_hover_text("Rested longer than planned");
+        } else {
+            ui.label(text);
+        }
+    }
+
+    fn render_records(&self, ui: &mut egui::Ui, workout_log_id: u32) {
+        let Some(records) = self.current_records.get(&workout_log_id) else {
+            ui.label("");
+            return;
+        };
+        let hover = records
+            .iter()
+            .map(|record| {
+                format!(
+                    "{}: {} ({})",
+                    record.record_type,
+                    record.describe(self.weight_unit),
+                    record.describe_previous(self.weight_unit)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        ui.label(
+            egui::RichText::new(format!("PR x{}", records.len()))
+                .color(egui::Color32::GOLD)
+                .strong(),
+        )
+        .on_hover_text(hover);
     }
 
     fn render_list(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
@@ -299,16 +697,19 @@ impl WorkoutLogsPage {
         });
     }
 
-    pub fn render_page(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
-        self.handle_async_messages();
+    pub fn render_page(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) -> PageAction {
+        self.handle_async_messages(ctx);
 
         match self.state {
             WorkoutLogsPageState::DetailsClosed => {
                 self.fetch_list(ctx);
                 self.render_list(ctx, ui);
+                PageAction::None
             }
-            WorkoutLogsPageState::DetailsOpenView => {
-                self.render_details_open_view(ui);
+            WorkoutLogsPageState::DetailsOpenView => self.render_details_open_view(ui),
+            WorkoutLogsPageState::DetailsEditView => {
+                self.render_details_edit_view(ctx, ui);
+                PageAction::None
             }
         }
     }